@@ -212,6 +212,7 @@ fn demo_benchmark_suite(
 
         let config = BenchmarkConfig {
             warmup_iterations: 5,
+            warmup_time: None,
             test_iterations: 30,
             min_test_time: Duration::from_millis(50),
             max_test_time: Duration::from_secs(5),
@@ -1,6 +1,9 @@
+use std::borrow::Cow;
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, Read, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
@@ -8,11 +11,87 @@ use colored::*;
 use serde_json::Value;
 
 use xqpath::{
-    detect_format, extract, parse_path, JsonFormat, ValueFormat, YamlFormat,
+    apply_json_patch, apply_merge_patch, detect_format, detect_format_strict,
+    diff_values, dotted_path, evaluate_path_expression, extract_with_depth,
+    flatten_object, format_jsonpath, format_path, leaf_paths, parse_jsonpath,
+    parse_path, parse_path_expression, path_to_value, stream_value, strip_bom,
+    unflatten_object, value_to_path, Difference, ExpressionEvaluator,
+    ExpressionParser, FormatError, FormatRegistry, FunctionRegistry,
+    JsonFormat, JsonSupport, PathExpression, ValueFormat, XqpathError,
+    YamlFormatter, YamlQuoteStyle, YamlSupport,
 };
 
 #[cfg(feature = "update")]
-use xqpath::update;
+use xqpath::{delete, rename_key, update, Updater};
+
+#[cfg(feature = "benchmark")]
+use xqpath::BenchmarkConfig;
+
+#[cfg(feature = "debug")]
+use xqpath::truncate_str;
+
+/// 纯 ASCII 输出模式（`--plain`），在解析参数后由 main 设置
+static PLAIN_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// 纯 ASCII 模式下状态符号的替换表（带变体选择符的序列需排在前面）
+const GLYPH_REPLACEMENTS: &[(&str, &str)] = &[
+    ("⚠️", "[WARN]"),
+    ("⚠", "[WARN]"),
+    ("✅", "[OK]"),
+    ("✓", "[OK]"),
+    ("❌", "[ERROR]"),
+    ("✗", "[FAIL]"),
+    ("💡", "[HINT]"),
+    ("🔍", "[INFO]"),
+    ("📊", "[STATS]"),
+    ("📈", "[STATS]"),
+    ("🚀", "[RUN]"),
+    ("📄", "[FILE]"),
+    ("📁", "[DIR]"),
+    ("📋", "[LIST]"),
+    ("🔄", "[RELOAD]"),
+    ("⚡", "[PERF]"),
+    ("🎯", "[TARGET]"),
+    ("📝", "[LOG]"),
+    ("🐛", "[DEBUG]"),
+    ("💾", "[MEMORY]"),
+    ("🏁", "[DONE]"),
+    ("📖", "[HELP]"),
+    ("🔧", "[CONFIG]"),
+    ("👋", "[BYE]"),
+    ("↳", "->"),
+    ("━", "-"),
+    ("•", "*"),
+];
+
+/// 纯 ASCII 模式下将状态符号替换为 ASCII 等价物，否则原样返回
+fn plain_glyphs(text: &str) -> Cow<'_, str> {
+    if !PLAIN_OUTPUT.load(Ordering::Relaxed) || text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    let mut replaced = text.to_string();
+    for (glyph, ascii) in GLYPH_REPLACEMENTS {
+        if replaced.contains(glyph) {
+            replaced = replaced.replace(glyph, ascii);
+        }
+    }
+    Cow::Owned(replaced)
+}
+
+/// 输出带状态符号的提示行，遵循 `--plain`
+macro_rules! status_println {
+    ($($arg:tt)*) => {
+        println!("{}", plain_glyphs(&format!($($arg)*)))
+    };
+}
+
+/// 输出带状态符号的提示行到标准错误，遵循 `--plain`
+#[cfg(any(feature = "config-management", feature = "interactive-debug"))]
+macro_rules! status_eprintln {
+    ($($arg:tt)*) => {
+        eprintln!("{}", plain_glyphs(&format!($($arg)*)))
+    };
+}
 
 /// XQPath - A minimal jq-like path extractor and updater for structured data
 #[derive(Parser)]
@@ -43,11 +122,19 @@ struct Cli {
     #[arg(long, global = true, value_name = "FILE")]
     log_file: Option<PathBuf>,
 
-    /// Show execution timing
-    #[cfg(feature = "debug")]
+    /// Print execution time to stderr
     #[arg(long, global = true)]
     timing: bool,
 
+    /// Plain output: no colors and ASCII replacements for status glyphs
+    #[arg(
+        long,
+        global = true,
+        visible_alias = "ascii",
+        alias = "monochrome-output"
+    )]
+    plain: bool,
+
     /// Enable path tracing
     #[cfg(feature = "debug")]
     #[arg(long, global = true)]
@@ -71,6 +158,15 @@ enum Commands {
         /// Path expression (jq-style syntax)
         path: String,
 
+        /// Syntax of the path argument
+        #[arg(
+            long,
+            value_enum,
+            value_name = "SYNTAX",
+            default_value_t = PathSyntax::Jq
+        )]
+        path_syntax: PathSyntax,
+
         /// Input file (reads from stdin if not specified)
         #[arg(short, long, value_name = "FILE")]
         file: Option<PathBuf>,
@@ -83,6 +179,85 @@ enum Commands {
         #[arg(long)]
         pretty: bool,
 
+        /// Read all input documents into a single array before querying
+        #[arg(short, long)]
+        slurp: bool,
+
+        /// Use `null` as the input; stdin is only read by `input()`/`inputs()`
+        #[arg(short = 'n', long, conflicts_with_all = ["file", "slurp"])]
+        null_input: bool,
+
+        /// How to split the input into documents; `blank` treats chunks
+        /// separated by blank lines as separate documents, `lines` reads
+        /// one JSON document per line (NDJSON)
+        #[arg(long, value_enum, value_name = "MODE")]
+        input_stream: Option<InputStream>,
+
+        /// Prefix each result with the input line it came from; not
+        /// supported for paths that read documents with `input`/`inputs`
+        #[arg(
+            long,
+            requires = "input_stream",
+            conflicts_with_all = ["slurp", "null_input", "stream", "seq", "stats"]
+        )]
+        with_line_numbers: bool,
+
+        /// Emit each result as a stream of [path, leaf] events
+        #[arg(long)]
+        stream: bool,
+
+        /// Emit an RFC 7464 JSON text sequence (RS before each value);
+        /// mutually exclusive with --pretty
+        #[arg(long, conflicts_with_all = ["pretty", "stream", "stats"])]
+        seq: bool,
+
+        /// Delimiter written after each result value
+        #[arg(long, value_enum, default_value_t = ValueSeparator::Newline)]
+        separator: ValueSeparator,
+
+        /// Number of spaces per indentation level in YAML output
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 2,
+            value_parser = clap::value_parser!(u8).range(1..=16)
+        )]
+        yaml_indent: u8,
+
+        /// Indent YAML block sequences under their parent key
+        #[arg(long)]
+        indent_sequences: bool,
+
+        /// Quoting style for string values in YAML output
+        #[arg(long, value_enum, default_value_t = QuoteStyle::Auto)]
+        quote_style: QuoteStyle,
+
+        /// Print a histogram of result types instead of the values
+        #[arg(long)]
+        stats: bool,
+
+        /// Maximum number of levels the recursive wildcard `**` descends
+        /// (simple paths only)
+        #[arg(long, value_name = "N")]
+        depth: Option<usize>,
+
+        /// Write the result to FILE instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out_file: Option<PathBuf>,
+
+        /// Print the parsed expression tree to stderr before running it
+        #[arg(long)]
+        explain: bool,
+
+        /// Exit with 1 if the last result is falsy (false, null, 0, empty), 4 if
+        /// there is no result
+        #[arg(short = 'e', long)]
+        exit_status: bool,
+
+        /// Escape non-ASCII characters in JSON output as \uXXXX
+        #[arg(short = 'a', long)]
+        ascii_output: bool,
+
         /// Disable colored output
         #[arg(long)]
         no_color: bool,
@@ -121,6 +296,59 @@ enum Commands {
         verbose: bool,
     },
 
+    /// Apply a list of set/del/merge operations from a file
+    #[cfg(feature = "update")]
+    Patch {
+        /// Operations file: an array of {op, path, value} objects
+        #[arg(value_name = "OPS")]
+        ops: PathBuf,
+
+        /// Input file (reads from stdin if not specified)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Auto)]
+        output: OutputFormat,
+
+        /// Continue applying remaining operations after a failure
+        #[arg(long)]
+        keep_going: bool,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Recursively rename object keys throughout the document
+    #[cfg(feature = "update")]
+    RenameKey {
+        /// Key to rename
+        from: String,
+        /// New key name
+        to: String,
+
+        /// Input file (reads from stdin if not specified)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Auto)]
+        output: OutputFormat,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
     /// Check if path exists
     Exists {
         /// Path expression (jq-style syntax)
@@ -130,6 +358,10 @@ enum Commands {
         #[arg(short, long, value_name = "FILE")]
         file: Option<PathBuf>,
 
+        /// Print the number of matches instead of true/false
+        #[arg(long, alias = "count-only")]
+        count: bool,
+
         /// Disable colored output
         #[arg(long)]
         no_color: bool,
@@ -210,6 +442,10 @@ enum Commands {
         #[arg(long)]
         pretty: bool,
 
+        /// Write the result to FILE instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out_file: Option<PathBuf>,
+
         /// Disable colored output
         #[arg(long)]
         no_color: bool,
@@ -219,6 +455,70 @@ enum Commands {
         verbose: bool,
     },
 
+    /// List the path of every node, optionally filtered by an expression
+    Paths {
+        /// Filter evaluated on each node, e.g. 'type == "string"'
+        filter: Option<String>,
+
+        /// Print paths as xqpath expressions (e.g. `.a.b[0]`) usable with
+        /// get/set/del instead of JSON arrays
+        #[arg(short = 'x', long)]
+        expression: bool,
+
+        /// Render paths in the given syntax (implies --expression)
+        #[arg(long, value_enum, value_name = "SYNTAX")]
+        path_syntax: Option<PathSyntax>,
+
+        /// Input file (reads from stdin if not specified)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<PathBuf>,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+
+    /// Print every scalar leaf as a `a.b.c=value` line
+    FlattenPaths {
+        /// Input file (reads from stdin if not specified)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<PathBuf>,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+
+    /// Flatten a nested document into an object with dotted keys
+    Flatten {
+        /// Input file (reads from stdin if not specified)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Auto)]
+        output: OutputFormat,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+
+    /// Rebuild a nested document from an object with dotted keys
+    Unflatten {
+        /// Input file (reads from stdin if not specified)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Auto)]
+        output: OutputFormat,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+
     /// Interactive mode for exploring data
     Interactive {
         /// Input file (reads from stdin if not specified)
@@ -232,6 +532,15 @@ enum Commands {
         #[arg(short, long, value_name = "FILE")]
         file: Option<PathBuf>,
 
+        /// JSON Schema file to validate the document against
+        #[cfg(feature = "schema")]
+        #[arg(long, value_name = "FILE")]
+        schema: Option<PathBuf>,
+
+        /// Print nothing; report the result only through the exit code
+        #[arg(short, long, conflicts_with = "verbose")]
+        quiet: bool,
+
         /// Disable colored output
         #[arg(long)]
         no_color: bool,
@@ -243,9 +552,10 @@ enum Commands {
 
     /// Convert between formats
     Convert {
-        /// Target format
-        #[arg(value_enum)]
-        to: OutputFormat,
+        /// Target format: any registered format (json, yaml, yml), or
+        /// json-pretty / compact
+        #[arg(value_name = "FORMAT")]
+        to: String,
 
         /// Input file (reads from stdin if not specified)
         #[arg(short, long, value_name = "FILE")]
@@ -255,6 +565,31 @@ enum Commands {
         #[arg(long)]
         pretty: bool,
 
+        /// Escape non-ASCII characters in JSON output as \uXXXX
+        #[arg(short = 'a', long)]
+        ascii_output: bool,
+
+        /// Number of spaces per indentation level in YAML output
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 2,
+            value_parser = clap::value_parser!(u8).range(1..=16)
+        )]
+        yaml_indent: u8,
+
+        /// Indent YAML block sequences under their parent key
+        #[arg(long)]
+        indent_sequences: bool,
+
+        /// Quoting style for string values in YAML output
+        #[arg(long, value_enum, default_value_t = QuoteStyle::Auto)]
+        quote_style: QuoteStyle,
+
+        /// Write the result to FILE instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out_file: Option<PathBuf>,
+
         /// Disable colored output
         #[arg(long)]
         no_color: bool,
@@ -264,6 +599,79 @@ enum Commands {
         verbose: bool,
     },
 
+    /// Apply an RFC 6902 JSON Patch document
+    JsonPatch {
+        /// Patch file containing an array of JSON Patch operations
+        #[arg(value_name = "PATCH")]
+        patch: PathBuf,
+
+        /// Input file (reads from stdin if not specified)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Auto)]
+        output: OutputFormat,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+
+    /// Apply an RFC 7386 JSON Merge Patch document
+    MergePatch {
+        /// Merge patch file
+        #[arg(value_name = "PATCH")]
+        patch: PathBuf,
+
+        /// Input file (reads from stdin if not specified)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Auto)]
+        output: OutputFormat,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+
+    /// Show structural differences between two documents
+    Diff {
+        /// Original document
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+
+        /// Updated document
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+
+    /// Analyze the complexity of a path expression without running it
+    Complexity {
+        /// Path expression (jq-style syntax)
+        path: String,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+
+    /// List builtin functions with their descriptions
+    Functions {
+        /// Only show functions whose name contains this text
+        filter: Option<String>,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+
     /// Show examples of usage
     Examples,
 
@@ -313,6 +721,16 @@ enum Commands {
         #[arg(long)]
         html: bool,
 
+        /// Report format; `json` writes the full report to stdout (or
+        /// --output) for machine consumption
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = ProfileFormat::Text,
+            conflicts_with = "html"
+        )]
+        format: ProfileFormat,
+
         /// Output file for the report
         #[arg(short, long, value_name = "FILE")]
         output: Option<PathBuf>,
@@ -344,6 +762,11 @@ enum Commands {
         #[arg(long, default_value = "10")]
         warmup: usize,
 
+        /// Warm up for this many milliseconds instead of a fixed number of
+        /// iterations
+        #[arg(long, value_name = "MS", conflicts_with = "warmup")]
+        warmup_time: Option<u64>,
+
         /// Output format for benchmark results
         #[arg(long, value_enum, default_value_t = BenchmarkOutputFormat::Text)]
         format: BenchmarkOutputFormat,
@@ -355,6 +778,11 @@ enum Commands {
         /// Compare with baseline file
         #[arg(long, value_name = "FILE")]
         baseline: Option<PathBuf>,
+
+        /// Exit with an error if any test is slower than the baseline by
+        /// more than PERCENT
+        #[arg(long, value_name = "PERCENT", requires = "baseline")]
+        fail_threshold: Option<f64>,
     },
 
     /// Monitor performance metrics in real-time
@@ -495,9 +923,90 @@ impl OutputFormat {
     // 移除未使用的get_formatter方法
 }
 
+/// 多个结果值之间的分隔符
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum ValueSeparator {
+    /// Newline after each value
+    Newline,
+    /// NUL byte after each value (for `xargs -0`)
+    Nul,
+    /// No separator
+    None,
+}
+
+impl ValueSeparator {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ValueSeparator::Newline => "\n",
+            ValueSeparator::Nul => "\0",
+            ValueSeparator::None => "",
+        }
+    }
+}
+
+/// profile 命令的报告格式
+#[cfg(feature = "profiling")]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum ProfileFormat {
+    /// Human-readable summary
+    Text,
+    /// The full report as JSON
+    Json,
+}
+
+/// 多文档输入的切分方式
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum InputStream {
+    /// Documents separated by one or more blank lines
+    Blank,
+    /// One JSON document per line (NDJSON); blank lines are skipped
+    Lines,
+}
+
+/// 路径的书写方式
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum PathSyntax {
+    /// jq-style paths, e.g. `.users[0].name`
+    Jq,
+    /// JSONPath, e.g. `$.users[0].name`
+    Jsonpath,
+}
+
+/// YAML 输出中字符串值的引号风格
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum QuoteStyle {
+    /// Quote only when needed
+    Auto,
+    /// Single-quoted strings
+    Single,
+    /// Double-quoted strings
+    Double,
+}
+
+/// 根据命令行选项构建 YAML 格式化器
+fn yaml_formatter(
+    indent: u8,
+    indent_sequences: bool,
+    quote_style: QuoteStyle,
+) -> YamlFormatter {
+    YamlFormatter::new()
+        .with_indent(usize::from(indent))
+        .with_indent_sequences(indent_sequences)
+        .with_quote_style(match quote_style {
+            QuoteStyle::Auto => YamlQuoteStyle::Auto,
+            QuoteStyle::Single => YamlQuoteStyle::Single,
+            QuoteStyle::Double => YamlQuoteStyle::Double,
+        })
+}
+
 fn main() {
     let cli = Cli::parse();
 
+    if cli.plain {
+        PLAIN_OUTPUT.store(true, Ordering::Relaxed);
+        colored::control::set_override(false);
+    }
+
     // 初始化调试系统 (v1.4.1+)
     #[cfg(feature = "debug")]
     initialize_debug_system(&cli);
@@ -510,10 +1019,21 @@ fn main() {
         | Commands::Count { no_color, .. }
         | Commands::Length { no_color, .. }
         | Commands::Keys { no_color, .. }
+        | Commands::Paths { no_color, .. }
+        | Commands::FlattenPaths { no_color, .. }
+        | Commands::Flatten { no_color, .. }
+        | Commands::Unflatten { no_color, .. }
         | Commands::Validate { no_color, .. }
-        | Commands::Convert { no_color, .. } => *no_color,
+        | Commands::Convert { no_color, .. }
+        | Commands::Diff { no_color, .. }
+        | Commands::Complexity { no_color, .. }
+        | Commands::Functions { no_color, .. }
+        | Commands::JsonPatch { no_color, .. }
+        | Commands::MergePatch { no_color, .. } => *no_color,
         #[cfg(feature = "update")]
-        Commands::Set { no_color, .. } => *no_color,
+        Commands::Set { no_color, .. }
+        | Commands::Patch { no_color, .. }
+        | Commands::RenameKey { no_color, .. } => *no_color,
         _ => false,
     };
 
@@ -521,9 +1041,28 @@ fn main() {
         colored::control::set_override(false);
     }
 
+    let start_time = std::time::Instant::now();
     let result = run_command(&cli);
 
+    if cli.timing {
+        eprintln!(
+            "{} Execution time: {:?}",
+            "Timing:".green().bold(),
+            start_time.elapsed()
+        );
+    }
+
     if let Err(e) = result {
+        // get -e 的结果已经输出，只需以对应的退出码结束
+        if let Some(ExitStatus(code)) = e.downcast_ref::<ExitStatus>() {
+            std::process::exit(*code);
+        }
+
+        // validate --quiet 只通过退出码报告结果
+        if matches!(cli.command, Commands::Validate { quiet: true, .. }) {
+            std::process::exit(1);
+        }
+
         let verbose = match &cli.command {
             Commands::Get { verbose, .. }
             | Commands::Exists { verbose, .. }
@@ -534,7 +1073,9 @@ fn main() {
             | Commands::Validate { verbose, .. }
             | Commands::Convert { verbose, .. } => *verbose,
             #[cfg(feature = "update")]
-            Commands::Set { verbose, .. } => *verbose,
+            Commands::Set { verbose, .. }
+            | Commands::Patch { verbose, .. }
+            | Commands::RenameKey { verbose, .. } => *verbose,
             _ => false,
         };
 
@@ -543,10 +1084,39 @@ fn main() {
         } else {
             eprintln!("{} {}", "Error:".red().bold(), e);
         }
+        if let Some(hint) = format_error_hint(&e) {
+            eprintln!("{} {}", "Hint:".yellow().bold(), hint);
+        }
         std::process::exit(1);
     }
 }
 
+/// 针对格式检测错误给出操作建议
+fn format_error_hint(error: &anyhow::Error) -> Option<String> {
+    let format_error = error.chain().find_map(|cause| {
+        cause.downcast_ref::<FormatError>().or_else(|| {
+            match cause.downcast_ref::<XqpathError>() {
+                Some(XqpathError::Format(e)) => Some(e),
+                _ => None,
+            }
+        })
+    })?;
+
+    match format_error {
+        FormatError::Empty => Some(
+            "No input data received; pass --file <FILE> or pipe data via stdin"
+                .to_string(),
+        ),
+        FormatError::Ambiguous(candidates) => Some(format!(
+            "Input is valid as {} with different results; {} is preferred. \
+             Convert the file explicitly with `xqpath convert` if needed",
+            candidates.join(" and "),
+            candidates.first().map(String::as_str).unwrap_or("json")
+        )),
+        _ => None,
+    }
+}
+
 // v1.4.1 调试系统初始化
 
 #[cfg(feature = "debug")]
@@ -554,37 +1124,42 @@ fn initialize_debug_system(cli: &Cli) {
     // 设置日志级别
     if let Some(level) = cli.log_level {
         match level {
-            DebugLogLevel::Trace => println!("🔍 Debug level set to: TRACE"),
-            DebugLogLevel::Debug => println!("🔍 Debug level set to: DEBUG"),
-            DebugLogLevel::Info => println!("🔍 Debug level set to: INFO"),
-            DebugLogLevel::Warn => println!("🔍 Debug level set to: WARN"),
-            DebugLogLevel::Error => println!("🔍 Debug level set to: ERROR"),
+            DebugLogLevel::Trace => {
+                status_println!("🔍 Debug level set to: TRACE")
+            }
+            DebugLogLevel::Debug => {
+                status_println!("🔍 Debug level set to: DEBUG")
+            }
+            DebugLogLevel::Info => {
+                status_println!("🔍 Debug level set to: INFO")
+            }
+            DebugLogLevel::Warn => {
+                status_println!("🔍 Debug level set to: WARN")
+            }
+            DebugLogLevel::Error => {
+                status_println!("🔍 Debug level set to: ERROR")
+            }
         }
     }
 
     // 设置日志文件
     if let Some(log_file) = &cli.log_file {
-        println!("📝 Logging to file: {}", log_file.display());
+        status_println!("📝 Logging to file: {}", log_file.display());
     }
 
     // 启用调试模式
     if cli.debug {
-        println!("🐛 Debug mode enabled");
-    }
-
-    // 启用时间统计
-    if cli.timing {
-        println!("⏱️  Timing enabled");
+        status_println!("🐛 Debug mode enabled");
     }
 
     // 启用路径跟踪
     if cli.trace_path {
-        println!("📊 Path tracing enabled");
+        status_println!("📊 Path tracing enabled");
     }
 
     // 启用内存统计
     if cli.memory_stats {
-        println!("💾 Memory statistics enabled");
+        status_println!("💾 Memory statistics enabled");
     }
 }
 
@@ -592,12 +1167,55 @@ fn run_command(cli: &Cli) -> Result<()> {
     match &cli.command {
         Commands::Get {
             path,
+            path_syntax,
             file,
             output,
             pretty,
+            slurp,
+            null_input,
+            input_stream,
+            with_line_numbers,
+            stream,
+            seq,
+            separator,
+            yaml_indent,
+            indent_sequences,
+            quote_style,
+            stats,
+            depth,
+            out_file,
+            explain,
+            exit_status,
+            ascii_output,
             verbose,
             ..
-        } => run_get(path, file.as_ref(), output, *pretty, *verbose),
+        } => run_get(
+            &resolve_path_syntax(path, *path_syntax)?,
+            file.as_ref(),
+            &GetOptions {
+                output,
+                pretty: *pretty,
+                slurp: *slurp,
+                null_input: *null_input,
+                input_stream: *input_stream,
+                with_line_numbers: *with_line_numbers,
+                stream: *stream,
+                seq: *seq,
+                separator: *separator,
+                yaml: &yaml_formatter(
+                    *yaml_indent,
+                    *indent_sequences,
+                    *quote_style,
+                ),
+                stats: *stats,
+                depth: *depth,
+                out_file: out_file.as_ref(),
+                explain: *explain,
+                exit_status: *exit_status,
+                ascii_output: *ascii_output,
+                verbose: *verbose,
+            },
+        ),
         #[cfg(feature = "update")]
         Commands::Set {
             path,
@@ -608,12 +1226,31 @@ fn run_command(cli: &Cli) -> Result<()> {
             verbose,
             ..
         } => run_set(path, value, file.as_ref(), output, *pretty, *verbose),
+        #[cfg(feature = "update")]
+        Commands::Patch {
+            ops,
+            file,
+            output,
+            keep_going,
+            verbose,
+            ..
+        } => run_patch(ops, file.as_ref(), output, *keep_going, *verbose),
+        #[cfg(feature = "update")]
+        Commands::RenameKey {
+            from,
+            to,
+            file,
+            output,
+            verbose,
+            ..
+        } => run_rename_key(from, to, file.as_ref(), output, *verbose),
         Commands::Exists {
             path,
             file,
+            count,
             verbose,
             ..
-        } => run_exists(path, file.as_ref(), *verbose),
+        } => run_exists(path, file.as_ref(), *count, *verbose),
         Commands::Type {
             path,
             file,
@@ -637,20 +1274,86 @@ fn run_command(cli: &Cli) -> Result<()> {
             file,
             output,
             pretty,
+            out_file,
             verbose,
             ..
-        } => run_keys(path, file.as_ref(), output, *pretty, *verbose),
-        Commands::Interactive { file } => run_interactive(file.as_ref()),
-        Commands::Validate { file, verbose, .. } => {
-            run_validate(file.as_ref(), *verbose)
-        }
-        Commands::Convert {
-            to,
+        } => run_keys(
+            path,
+            file.as_ref(),
+            output,
+            *pretty,
+            out_file.as_ref(),
+            *verbose,
+        ),
+        Commands::Paths {
+            filter,
+            expression,
+            path_syntax,
+            file,
+            ..
+        } => run_paths(
+            filter.as_deref(),
+            path_syntax.or(expression.then_some(PathSyntax::Jq)),
+            file.as_ref(),
+        ),
+        Commands::FlattenPaths { file, .. } => run_flatten_paths(file.as_ref()),
+        Commands::Flatten { file, output, .. } => {
+            run_flatten(file.as_ref(), output, flatten_object)
+        }
+        Commands::Unflatten { file, output, .. } => {
+            run_flatten(file.as_ref(), output, unflatten_object)
+        }
+        Commands::Interactive { file } => run_interactive(file.as_ref()),
+        #[cfg(not(feature = "schema"))]
+        Commands::Validate {
+            file,
+            quiet,
+            verbose,
+            ..
+        } => run_validate(file.as_ref(), *quiet, *verbose),
+        #[cfg(feature = "schema")]
+        Commands::Validate {
+            file,
+            schema,
+            quiet,
+            verbose,
+            ..
+        } => run_validate(file.as_ref(), schema.as_ref(), *quiet, *verbose),
+        Commands::Convert {
+            to,
             file,
             pretty,
+            ascii_output,
+            yaml_indent,
+            indent_sequences,
+            quote_style,
+            out_file,
             verbose,
             ..
-        } => run_convert(to, file.as_ref(), *pretty, *verbose),
+        } => run_convert(
+            to,
+            file.as_ref(),
+            *pretty,
+            *ascii_output,
+            &yaml_formatter(*yaml_indent, *indent_sequences, *quote_style),
+            out_file.as_ref(),
+            *verbose,
+        ),
+        Commands::JsonPatch {
+            patch,
+            file,
+            output,
+            ..
+        } => run_json_patch(patch, file.as_ref(), output),
+        Commands::MergePatch {
+            patch,
+            file,
+            output,
+            ..
+        } => run_merge_patch(patch, file.as_ref(), output),
+        Commands::Diff { old, new, .. } => run_diff(old, new),
+        Commands::Complexity { path, .. } => run_complexity(path),
+        Commands::Functions { filter, .. } => run_functions(filter.as_deref()),
         Commands::Examples => run_examples(),
         #[cfg(feature = "debug")]
         Commands::Debug {
@@ -663,6 +1366,7 @@ fn run_command(cli: &Cli) -> Result<()> {
             path,
             file,
             detailed,
+            ..
         } => run_trace(path, file.as_ref(), *detailed),
         // v1.4.2 性能分析命令
         #[cfg(feature = "profiling")]
@@ -670,6 +1374,7 @@ fn run_command(cli: &Cli) -> Result<()> {
             path,
             file,
             html,
+            format,
             output,
             memory,
             hints,
@@ -677,6 +1382,7 @@ fn run_command(cli: &Cli) -> Result<()> {
             path,
             file.as_ref(),
             *html,
+            *format,
             output.as_ref(),
             *memory,
             *hints,
@@ -687,17 +1393,25 @@ fn run_command(cli: &Cli) -> Result<()> {
             file,
             iterations,
             warmup,
+            warmup_time,
             format,
             output,
             baseline,
+            fail_threshold,
         } => run_benchmark(
             path,
             file.as_ref(),
-            *iterations,
-            *warmup,
+            BenchmarkConfig {
+                warmup_iterations: *warmup,
+                warmup_time: warmup_time.map(std::time::Duration::from_millis),
+                test_iterations: *iterations,
+                min_test_time: std::time::Duration::from_millis(10),
+                max_test_time: std::time::Duration::from_secs(30),
+            },
             format,
             output.as_ref(),
             baseline.as_ref(),
+            *fail_threshold,
         ),
         #[cfg(feature = "profiling")]
         Commands::Monitor {
@@ -720,24 +1434,82 @@ fn run_command(cli: &Cli) -> Result<()> {
     }
 }
 
+/// 按字节读取输入并原地校验为 UTF-8，不做额外拷贝
 fn read_input(file: Option<&PathBuf>) -> Result<String> {
-    match file {
-        Some(path) => fs::read_to_string(path).with_context(|| {
+    let data = read_input_bytes(file)?;
+    String::from_utf8(data).map_err(|e| {
+        FormatError::InvalidEncoding(e.utf8_error().to_string()).into()
+    })
+}
+
+/// gzip 压缩数据的魔数
+#[cfg(feature = "gzip")]
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// 以 gzip 魔数开头或扩展名为 `.gz` 的输入先解压再解析
+#[cfg(feature = "gzip")]
+fn decompress_if_gzip(
+    file: Option<&PathBuf>,
+    data: Vec<u8>,
+) -> Result<Vec<u8>> {
+    let gz_extension = file
+        .and_then(|path| path.extension())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"));
+    if !gz_extension && !data.starts_with(&GZIP_MAGIC) {
+        return Ok(data);
+    }
+
+    let mut decompressed = Vec::new();
+    flate2::read::MultiGzDecoder::new(data.as_slice())
+        .read_to_end(&mut decompressed)
+        .context("Failed to decompress gzip input")?;
+    Ok(decompressed)
+}
+
+fn read_input_bytes(file: Option<&PathBuf>) -> Result<Vec<u8>> {
+    let data = match file {
+        Some(path) => fs::read(path).with_context(|| {
             format!("Failed to read file: {}", path.display())
-        }),
+        })?,
         None => {
-            let mut input = String::new();
+            let mut input = Vec::new();
             io::stdin()
-                .read_to_string(&mut input)
+                .read_to_end(&mut input)
                 .context("Failed to read from stdin")?;
-            Ok(input)
+            input
         }
+    };
+
+    #[cfg(feature = "gzip")]
+    let data = decompress_if_gzip(file, data)?;
+    Ok(data)
+}
+
+/// 执行查询：简单路径使用提取器，管道、函数、条件等表达式交给求值器
+///
+/// `depth` 限制简单路径中递归通配符 `**` 的下降层数，表达式会忽略它，
+/// 由调用方拒绝同时使用两者。
+fn evaluate_query(
+    data: &Value,
+    path: &str,
+    depth: Option<usize>,
+) -> Result<Vec<Value>> {
+    if let Ok(path_obj) = parse_path(path) {
+        let values = extract_with_depth(data, &path_obj, depth)
+            .context("Failed to extract values")?;
+        return Ok(values.into_iter().cloned().collect());
     }
+
+    let expression = parse_path_expression(path)
+        .context("Failed to parse path expression")?;
+    evaluate_path_expression(&expression, data)
+        .context("Failed to evaluate expression")
 }
 
 fn parse_and_extract(
     input: &str,
     path: &str,
+    depth: Option<usize>,
 ) -> Result<(Box<dyn ValueFormat>, Vec<Value>)> {
     let format =
         detect_format(input).context("Failed to detect input format")?;
@@ -745,23 +1517,168 @@ fn parse_and_extract(
     let parsed_data =
         format.parse(input).context("Failed to parse input data")?;
 
-    let path_obj =
-        parse_path(path).context("Failed to parse path expression")?;
+    Ok((format, evaluate_query(&parsed_data, path, depth)?))
+}
+
+/// 解析输入中的全部文档（多文档 YAML、拼接 JSON 或 NDJSON）
+fn parse_documents(input: &str) -> Result<(Box<dyn ValueFormat>, Vec<Value>)> {
+    let format =
+        detect_format(input).context("Failed to detect input format")?;
+
+    let documents = match format.name() {
+        "json" => serde_json::Deserializer::from_str(strip_bom(input))
+            .into_iter::<Value>()
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to parse input data")?,
+        "yaml" => YamlSupport::parse_multi_document(input)
+            .context("Failed to parse input data")?,
+        _ => vec![format.parse(input).context("Failed to parse input data")?],
+    };
+
+    Ok((format, documents))
+}
+
+/// 将输入按空行切分为文档块，返回每块的起始行号（从 1 开始）与内容
+fn split_blank_line_chunks(input: &str) -> Vec<(usize, String)> {
+    let mut chunks = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (index, line) in strip_bom(input).lines().enumerate() {
+        if line.trim().is_empty() {
+            chunks.extend(current.take());
+            continue;
+        }
+        let (_, chunk) =
+            current.get_or_insert_with(|| (index + 1, String::new()));
+        chunk.push_str(line);
+        chunk.push('\n');
+    }
+    chunks.extend(current);
+    chunks
+}
+
+/// 文档及其在输入中的起始行号（从 1 开始）
+type NumberedDocuments = Vec<(usize, Value)>;
+
+/// 解析以空行分隔的多个文档，每块单独检测格式；出错时报告该块的起始行
+fn parse_blank_line_documents(
+    input: &str,
+) -> Result<(Box<dyn ValueFormat>, NumberedDocuments)> {
+    let mut format: Option<Box<dyn ValueFormat>> = None;
+    let mut documents = Vec::new();
+
+    for (line, chunk) in split_blank_line_chunks(input) {
+        let chunk_format = detect_format(&chunk).with_context(|| {
+            format!(
+                "Failed to detect format of document starting at line {line}"
+            )
+        })?;
+        let document = chunk_format.parse(&chunk).with_context(|| {
+            format!("Failed to parse document starting at line {line}")
+        })?;
+        documents.push((line, document));
+        format.get_or_insert(chunk_format);
+    }
+
+    let format = format.unwrap_or_else(|| Box::new(JsonFormat));
+    Ok((format, documents))
+}
+
+/// 逐行解析 NDJSON，跳过空行；返回每个文档所在的行号（从 1 开始）
+fn parse_ndjson_lines(input: &str) -> Result<NumberedDocuments> {
+    let mut documents = Vec::new();
+    for (index, line) in strip_bom(input).lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let document = serde_json::from_str(line)
+            .with_context(|| format!("Failed to parse line {}", index + 1))?;
+        documents.push((index + 1, document));
+    }
+    Ok(documents)
+}
 
-    let values =
-        extract(&parsed_data, &path_obj).context("Failed to extract values")?;
+/// 按 `--input-stream` 模式切分输入，保留每个文档的起始行号
+fn read_numbered_documents(
+    input: &str,
+    input_stream: InputStream,
+) -> Result<(Box<dyn ValueFormat>, NumberedDocuments)> {
+    match input_stream {
+        InputStream::Blank => parse_blank_line_documents(input),
+        InputStream::Lines => {
+            let format: Box<dyn ValueFormat> = Box::new(JsonFormat);
+            Ok((format, parse_ndjson_lines(input)?))
+        }
+    }
+}
+
+/// 按 `--input-stream` 模式解析输入中的全部文档
+fn read_documents(
+    input: &str,
+    input_stream: Option<InputStream>,
+) -> Result<(Box<dyn ValueFormat>, Vec<Value>)> {
+    let Some(input_stream) = input_stream else {
+        return parse_documents(input);
+    };
+    let (format, documents) = read_numbered_documents(input, input_stream)?;
+    Ok((format, documents.into_iter().map(|(_, doc)| doc).collect()))
+}
 
-    let owned_values: Vec<Value> = values.into_iter().cloned().collect();
+/// 解析调用了 `input`/`inputs` 的表达式，其余表达式返回 `None`
+fn parse_input_expression(path: &str) -> Option<PathExpression> {
+    let expression = parse_path_expression(path).ok()?;
+    (expression.calls_function("input") || expression.calls_function("inputs"))
+        .then_some(expression)
+}
+
+/// 按 jq 语义处理多文档输入：表达式依次作用于每个未被 `input`/`inputs`
+/// 消费的文档；`null_input` 时只以 null 为输入执行一次
+fn evaluate_with_inputs(
+    expression: &PathExpression,
+    documents: Vec<Value>,
+    null_input: bool,
+) -> Result<Vec<Value>> {
+    let evaluator = ExpressionEvaluator::new().with_inputs(documents);
+    if null_input {
+        return evaluator
+            .evaluate(expression, &Value::Null)
+            .context("Failed to evaluate expression");
+    }
 
-    Ok((format, owned_values))
+    let mut results = Vec::new();
+    while let Some(document) = evaluator.next_input() {
+        results.extend(
+            evaluator
+                .evaluate(expression, &document)
+                .context("Failed to evaluate expression")?,
+        );
+    }
+    Ok(results)
 }
 
-fn output_values(
+/// 将所有输入文档收集为一个数组后再执行路径提取
+fn slurp_and_extract(
+    input: &str,
+    input_stream: Option<InputStream>,
+    path: &str,
+    depth: Option<usize>,
+) -> Result<(Box<dyn ValueFormat>, Vec<Value>)> {
+    let (format, documents) = read_documents(input, input_stream)?;
+    let slurped = Value::Array(documents);
+
+    Ok((format, evaluate_query(&slurped, path, depth)?))
+}
+
+/// 将结果格式化为输出文本，每个值之后写入分隔符
+fn format_values(
     values: &[Value],
     format: &dyn ValueFormat,
     output: &OutputFormat,
     pretty: bool,
-) -> Result<()> {
+    highlight: bool,
+    separator: ValueSeparator,
+    yaml: &YamlFormatter,
+) -> Result<String> {
     let output_format = match output {
         OutputFormat::Auto => format.name(),
         _ => match output {
@@ -773,47 +1690,161 @@ fn output_values(
     };
 
     let formatter = get_output_format(output_format)?;
+    let highlight = highlight
+        && output_format == "json"
+        && !matches!(output, OutputFormat::Compact);
+    let mut rendered = String::new();
 
-    for (i, value) in values.iter().enumerate() {
-        if i > 0 {
-            println!();
-        }
-
-        let output_str = if pretty
+    for value in values {
+        let output_str = if highlight {
+            colorize_json(value)
+        } else if pretty
             && matches!(output, OutputFormat::JsonPretty | OutputFormat::Auto)
         {
             serde_json::to_string_pretty(value)
                 .context("Failed to format output")?
         } else if matches!(output, OutputFormat::Compact) {
             serde_json::to_string(value).context("Failed to format output")?
+        } else if output_format == "yaml" {
+            yaml.format(value).context("Failed to format output")?
         } else {
             formatter
                 .to_string(value)
                 .context("Failed to format output")?
         };
 
-        print!("{output_str}");
+        rendered.push_str(&output_str);
+        rendered.push_str(separator.as_str());
     }
 
-    if !values.is_empty() {
-        println!();
+    Ok(rendered)
+}
+
+/// 输出到文件或标准输出；写文件时先写入临时文件再重命名，避免留下不完整的结果
+fn write_output(out_file: Option<&PathBuf>, content: &str) -> Result<()> {
+    let Some(path) = out_file else {
+        print!("{content}");
+        return Ok(());
+    };
+
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("Invalid output path: {}", path.display()))?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    fs::write(&tmp_path, content)
+        .and_then(|_| fs::rename(&tmp_path, path))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&tmp_path);
+        })
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// 将其他方言的路径翻译为等价的 jq 风格路径
+fn resolve_path_syntax(path: &str, syntax: PathSyntax) -> Result<Cow<'_, str>> {
+    match syntax {
+        PathSyntax::Jq => Ok(Cow::Borrowed(path)),
+        PathSyntax::Jsonpath => {
+            let segments = parse_jsonpath(path)
+                .map_err(|e| anyhow::anyhow!("Invalid JSONPath: {}", e))?;
+            Ok(Cow::Owned(format_path(&segments)))
+        }
     }
+}
 
-    Ok(())
+/// get 命令的执行选项
+struct GetOptions<'a> {
+    output: &'a OutputFormat,
+    pretty: bool,
+    slurp: bool,
+    null_input: bool,
+    input_stream: Option<InputStream>,
+    with_line_numbers: bool,
+    stream: bool,
+    seq: bool,
+    separator: ValueSeparator,
+    yaml: &'a YamlFormatter,
+    stats: bool,
+    depth: Option<usize>,
+    out_file: Option<&'a PathBuf>,
+    explain: bool,
+    exit_status: bool,
+    ascii_output: bool,
+    verbose: bool,
 }
 
 fn run_get(
     path: &str,
     file: Option<&PathBuf>,
-    output: &OutputFormat,
-    pretty: bool,
-    verbose: bool,
+    options: &GetOptions,
 ) -> Result<()> {
+    if options.explain {
+        print_explain(path)?;
+    }
+
+    // 表达式求值器不支持限制递归深度，避免静默忽略 --depth
+    if options.depth.is_some() && parse_path(path).is_err() {
+        return Err(anyhow::anyhow!(
+            "--depth only applies to simple paths, not to expressions"
+        ));
+    }
+
     let start_time = std::time::Instant::now();
-    let input = read_input(file)?;
-    let (format, values) = parse_and_extract(&input, path)?;
+    // 每个结果来自的输入行，仅在按行号切分输入时记录
+    let mut line_numbers = Vec::new();
+    let (format, values) = if let Some(expression) =
+        parse_input_expression(path)
+    {
+        // input/inputs 会跨文档消费输入，结果无法对应到单一的输入行
+        if options.with_line_numbers {
+            return Err(anyhow::anyhow!(
+                "--with-line-numbers cannot be used with input() or inputs()"
+            ));
+        }
+        let (format, documents) = if options.null_input {
+            // -n 时输入文档只能通过 input/inputs 读取
+            let input = read_input(None)?;
+            if input.trim().is_empty() {
+                let format: Box<dyn ValueFormat> = Box::new(JsonFormat);
+                (format, vec![])
+            } else {
+                read_documents(&input, options.input_stream)?
+            }
+        } else {
+            read_documents(&read_input(file)?, options.input_stream)?
+        };
+        let values =
+            evaluate_with_inputs(&expression, documents, options.null_input)?;
+        (format, values)
+    } else if options.null_input {
+        let format: Box<dyn ValueFormat> = Box::new(JsonFormat);
+        (format, evaluate_query(&Value::Null, path, options.depth)?)
+    } else if options.slurp {
+        slurp_and_extract(
+            &read_input(file)?,
+            options.input_stream,
+            path,
+            options.depth,
+        )?
+    } else if let Some(input_stream) = options.input_stream {
+        let (format, documents) =
+            read_numbered_documents(&read_input(file)?, input_stream)?;
+        let mut values = Vec::new();
+        for (line, document) in &documents {
+            let results = evaluate_query(document, path, options.depth)
+                .with_context(|| format!("Failed to evaluate line {line}"))?;
+            line_numbers.extend(std::iter::repeat_n(*line, results.len()));
+            values.extend(results);
+        }
+        (format, values)
+    } else {
+        parse_and_extract(&read_input(file)?, path, options.depth)?
+    };
 
-    if verbose {
+    if options.verbose {
         eprintln!("{} Found {} value(s)", "Info:".blue().bold(), values.len());
         let duration = start_time.elapsed();
         eprintln!(
@@ -823,66 +1854,464 @@ fn run_get(
         );
     }
 
-    output_values(&values, format.as_ref(), output, pretty)?;
+    let to_stdout = options.out_file.is_none();
+    let rendered = if options.stats {
+        format_type_histogram(&values, to_stdout)
+    } else if options.stream {
+        format_stream(&values)?
+    } else if options.seq {
+        format_json_seq(&values)?
+    } else if options.with_line_numbers {
+        let mut rendered = String::new();
+        for (line, value) in line_numbers.iter().zip(&values) {
+            rendered.push_str(&format!("{line}:"));
+            rendered.push_str(&format_values(
+                std::slice::from_ref(value),
+                format.as_ref(),
+                options.output,
+                options.pretty,
+                to_stdout && should_highlight(),
+                options.separator,
+                options.yaml,
+            )?);
+        }
+        rendered
+    } else {
+        format_values(
+            &values,
+            format.as_ref(),
+            options.output,
+            options.pretty,
+            to_stdout && should_highlight(),
+            options.separator,
+            options.yaml,
+        )?
+    };
+
+    // --stats 是纯文本，YAML 输出没有 \u 转义形式，只转义 JSON 输出
+    let json_output = options.stream
+        || options.seq
+        || match options.output {
+            OutputFormat::Yaml => false,
+            OutputFormat::Auto => format.name() == "json",
+            _ => true,
+        };
+    let rendered = if options.ascii_output && json_output && !options.stats {
+        escape_non_ascii(&rendered)
+    } else {
+        rendered
+    };
+
+    write_output(options.out_file, &rendered)?;
+
+    if options.exit_status {
+        let code = exit_status_code(&values);
+        if code != 0 {
+            io::stdout().flush().context("Failed to flush output")?;
+            return Err(ExitStatus(code).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// 要求 main 以给定退出码结束进程，不再输出错误信息
+///
+/// 由 `get -e` 返回，使 `--timing` 等收尾输出仍能在退出前完成。
+#[derive(Debug)]
+struct ExitStatus(i32);
+
+impl std::fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "exit status {}", self.0)
+    }
+}
+
+impl std::error::Error for ExitStatus {}
+
+/// 按 jq `-e` 的约定计算退出码：最后一个结果为真时 0，为假时 1，无结果时 4
+///
+/// 真值判断使用表达式求值器的 `is_truthy`，与 `select` 等保持一致。
+fn exit_status_code(values: &[Value]) -> i32 {
+    match values.last() {
+        Some(last) if ExpressionEvaluator::new().is_truthy(last) => 0,
+        Some(_) => 1,
+        None => 4,
+    }
+}
+
+/// 将表达式的语法树与复杂度摘要输出到标准错误
+fn print_explain(path: &str) -> Result<()> {
+    let expression = parse_path_expression(path)
+        .context("Failed to parse path expression")?;
+
+    let mut tree = String::new();
+    render_ast(&expression, 0, &mut tree);
+    eprint!("{tree}");
+
+    let complexity = expression.analyze_complexity();
+    eprintln!(
+        "{} Depth: {}, Pipes: {}, Comma branches: {}, Wildcards: {}, Recursive wildcards: {}",
+        "Complexity:".cyan().bold(),
+        complexity.depth,
+        complexity.pipe_count,
+        complexity.comma_branches,
+        complexity.has_wildcards,
+        complexity.has_recursive_wildcards
+    );
+    Ok(())
+}
+
+/// 以缩进树的形式渲染表达式，每层缩进两个空格
+fn render_ast(expression: &PathExpression, indent: usize, out: &mut String) {
+    let (label, children): (String, Vec<&PathExpression>) = match expression {
+        PathExpression::Segments(segments) => {
+            (format!("Path {}", format_path(segments)), vec![])
+        }
+        PathExpression::Pipe { left, right } => {
+            ("Pipe".to_string(), vec![left, right])
+        }
+        PathExpression::Comma(exprs) => {
+            ("Comma".to_string(), exprs.iter().collect())
+        }
+        PathExpression::Literal(value) => (format!("Literal {value}"), vec![]),
+        PathExpression::Identity => ("Identity".to_string(), vec![]),
+        PathExpression::FunctionCall { name, args } => {
+            (format!("Call {name}"), args.iter().collect())
+        }
+        PathExpression::Conditional {
+            condition,
+            then_expr,
+            else_expr,
+        } => {
+            let mut children = vec![condition.as_ref(), then_expr.as_ref()];
+            children.extend(else_expr.as_deref());
+            ("If".to_string(), children)
+        }
+        PathExpression::Comparison { left, op, right } => {
+            (format!("Comparison {op:?}"), vec![left, right])
+        }
+        PathExpression::Arithmetic { left, op, right } => {
+            (format!("Arithmetic {op:?}"), vec![left, right])
+        }
+        PathExpression::Logical { op, operands } => {
+            (format!("Logical {op:?}"), operands.iter().collect())
+        }
+        PathExpression::TryCatch {
+            try_expr,
+            catch_expr,
+        } => {
+            let mut children = vec![try_expr.as_ref()];
+            children.extend(catch_expr.as_deref());
+            ("Try".to_string(), children)
+        }
+        PathExpression::Optional(expr) => ("Optional".to_string(), vec![expr]),
+    };
+
+    out.push_str(&"  ".repeat(indent));
+    out.push_str(&label);
+    out.push('\n');
+    for child in children {
+        render_ast(child, indent + 1, out);
+    }
+}
+
+/// 统计结果集中各类型值的数量，按固定类型顺序返回非零项
+fn type_histogram(values: &[Value]) -> Vec<(&'static str, usize)> {
+    const TYPE_ORDER: [&str; 6] =
+        ["null", "boolean", "number", "string", "array", "object"];
+
+    TYPE_ORDER
+        .iter()
+        .map(|type_name| {
+            let count = values
+                .iter()
+                .filter(|v| JsonSupport::get_type_name(v) == *type_name)
+                .count();
+            (*type_name, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+fn format_type_histogram(values: &[Value], bold_header: bool) -> String {
+    let mut rendered = if bold_header {
+        format!("{:<10} {:>8}\n", "Type".bold(), "Count".bold())
+    } else {
+        format!("{:<10} {:>8}\n", "Type", "Count")
+    };
+    for (type_name, count) in type_histogram(values) {
+        rendered.push_str(&format!("{type_name:<10} {count:>8}\n"));
+    }
+    rendered.push_str(&format!("{:<10} {:>8}\n", "total", values.len()));
+    rendered
+}
+
+/// 是否对输出做语法高亮：stdout 为终端（或设置了 CLICOLOR_FORCE）且未禁用颜色
+fn should_highlight() -> bool {
+    use std::io::IsTerminal;
+
+    let forced = std::env::var("CLICOLOR_FORCE").is_ok_and(|v| v != "0");
+    colored::control::SHOULD_COLORIZE.should_colorize()
+        && (forced || io::stdout().is_terminal())
+}
+
+/// 生成带语法高亮的 JSON，排版与 `serde_json::to_string_pretty` 一致
+fn colorize_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_colored_json(value, 0, &mut out);
+    out
+}
+
+fn write_colored_json(value: &Value, indent: usize, out: &mut String) {
+    let json_string = |s: &str| serde_json::to_string(s).unwrap_or_default();
+
+    match value {
+        Value::Null => out.push_str(&"null".dimmed().to_string()),
+        Value::Bool(b) => out.push_str(&b.to_string().yellow().to_string()),
+        Value::Number(n) => out.push_str(&n.to_string().cyan().to_string()),
+        Value::String(s) => out.push_str(&json_string(s).green().to_string()),
+        Value::Array(arr) if arr.is_empty() => out.push_str("[]"),
+        Value::Object(map) if map.is_empty() => out.push_str("{}"),
+        Value::Array(arr) => {
+            out.push_str("[\n");
+            for (i, item) in arr.iter().enumerate() {
+                out.push_str(&"  ".repeat(indent + 1));
+                write_colored_json(item, indent + 1, out);
+                out.push_str(if i + 1 < arr.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&"  ".repeat(indent));
+            out.push(']');
+        }
+        Value::Object(map) => {
+            out.push_str("{\n");
+            for (i, (key, item)) in map.iter().enumerate() {
+                out.push_str(&"  ".repeat(indent + 1));
+                out.push_str(&json_string(key).blue().bold().to_string());
+                out.push_str(": ");
+                write_colored_json(item, indent + 1, out);
+                out.push_str(if i + 1 < map.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&"  ".repeat(indent));
+            out.push('}');
+        }
+    }
+}
+
+/// 以 `[path, leaf]` 事件流输出每个结果，每行一个事件
+fn format_stream(values: &[Value]) -> Result<String> {
+    let mut rendered = String::new();
+    for value in values {
+        for (path, leaf) in stream_value(value) {
+            let event = Value::Array(vec![path_to_value(&path), leaf.clone()]);
+            rendered.push_str(&serde_json::to_string(&event)?);
+            rendered.push('\n');
+        }
+    }
+    Ok(rendered)
+}
+
+/// 以 RFC 7464 JSON 文本序列输出：每个值前加 RS (0x1E)，后加换行
+fn format_json_seq(values: &[Value]) -> Result<String> {
+    let mut rendered = String::new();
+    for value in values {
+        rendered.push('\u{1e}');
+        rendered.push_str(&serde_json::to_string(value)?);
+        rendered.push('\n');
+    }
+    Ok(rendered)
+}
+
+#[cfg(feature = "update")]
+fn run_set(
+    path: &str,
+    new_value_str: &str,
+    file: Option<&PathBuf>,
+    output: &OutputFormat,
+    _pretty: bool,
+    _verbose: bool,
+) -> Result<()> {
+    let input = read_input(file)?;
+
+    let format =
+        detect_format(&input).context("Failed to detect input format")?;
+
+    let mut parsed_data =
+        format.parse(&input).context("Failed to parse input data")?;
+
+    let path_obj =
+        parse_path(path).context("Failed to parse path expression")?;
+
+    let new_value: serde_json::Value = serde_json::from_str(new_value_str)
+        .context("Invalid JSON value for update")?;
+
+    update(&mut parsed_data, &path_obj, new_value)
+        .context("Failed to update value")?;
+
+    let output_format = match output {
+        OutputFormat::Auto => format.name(),
+        _ => match output {
+            OutputFormat::Json | OutputFormat::Compact => "json",
+            OutputFormat::JsonPretty => "json",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Auto => unreachable!(),
+        },
+    };
+
+    let formatter = get_output_format(output_format)?;
+    let output_str = formatter
+        .to_string(&parsed_data)
+        .context("Failed to format output")?;
+
+    print!("{output_str}");
+    Ok(())
+}
+
+/// 应用单个补丁操作
+#[cfg(feature = "update")]
+fn apply_patch_op(data: &mut Value, op: &Value) -> Result<()> {
+    let op_name = op
+        .get("op")
+        .and_then(Value::as_str)
+        .context("Operation is missing string field 'op'")?;
+    let path_str = op
+        .get("path")
+        .and_then(Value::as_str)
+        .context("Operation is missing string field 'path'")?;
+    let path_obj =
+        parse_path(path_str).context("Failed to parse path expression")?;
+
+    let value = || {
+        op.get("value")
+            .cloned()
+            .context("Operation is missing field 'value'")
+    };
+
+    match op_name {
+        "set" => update(data, &path_obj, value()?)?,
+        "del" => delete(data, &path_obj)?,
+        "merge" => Updater::merge(data, &path_obj, value()?)?,
+        other => {
+            return Err(anyhow::anyhow!(
+                "Unknown operation '{}' (expected set, del or merge)",
+                other
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "update")]
+fn run_patch(
+    ops_file: &PathBuf,
+    file: Option<&PathBuf>,
+    output: &OutputFormat,
+    keep_going: bool,
+    verbose: bool,
+) -> Result<()> {
+    let ops_input = read_input(Some(ops_file))?;
+    let ops_value = detect_format(&ops_input)
+        .context("Failed to detect operations file format")?
+        .parse(&ops_input)
+        .context("Failed to parse operations file")?;
+    let ops = ops_value
+        .as_array()
+        .context("Operations file must contain an array of operations")?;
+
+    let input = read_input(file)?;
+    let format =
+        detect_format(&input).context("Failed to detect input format")?;
+    let mut parsed_data =
+        format.parse(&input).context("Failed to parse input data")?;
+
+    let mut failures = 0;
+    for (i, op) in ops.iter().enumerate() {
+        if let Err(e) = apply_patch_op(&mut parsed_data, op) {
+            let op_desc = serde_json::to_string(op).unwrap_or_default();
+            if !keep_going {
+                return Err(anyhow::anyhow!(
+                    "Operation #{} failed: {}: {:#}",
+                    i + 1,
+                    op_desc,
+                    e
+                ));
+            }
+            failures += 1;
+            eprintln!(
+                "{} Operation #{} failed: {}: {}",
+                "Warning:".yellow().bold(),
+                i + 1,
+                op_desc,
+                e
+            );
+        } else if verbose {
+            eprintln!("{} Applied operation #{}", "Info:".blue().bold(), i + 1);
+        }
+    }
+
+    print_document(&parsed_data, format.as_ref(), output)?;
+
+    if failures > 0 {
+        return Err(anyhow::anyhow!(
+            "{} of {} operation(s) failed",
+            failures,
+            ops.len()
+        ));
+    }
+
     Ok(())
 }
 
 #[cfg(feature = "update")]
-fn run_set(
-    path: &str,
-    new_value_str: &str,
+fn run_rename_key(
+    from: &str,
+    to: &str,
     file: Option<&PathBuf>,
     output: &OutputFormat,
-    _pretty: bool,
-    _verbose: bool,
+    verbose: bool,
 ) -> Result<()> {
     let input = read_input(file)?;
-
     let format =
         detect_format(&input).context("Failed to detect input format")?;
-
     let mut parsed_data =
         format.parse(&input).context("Failed to parse input data")?;
 
-    let path_obj =
-        parse_path(path).context("Failed to parse path expression")?;
-
-    let new_value: serde_json::Value = serde_json::from_str(new_value_str)
-        .context("Invalid JSON value for update")?;
-
-    update(&mut parsed_data, &path_obj, new_value)
-        .context("Failed to update value")?;
-
-    let output_format = match output {
-        OutputFormat::Auto => format.name(),
-        _ => match output {
-            OutputFormat::Json | OutputFormat::Compact => "json",
-            OutputFormat::JsonPretty => "json",
-            OutputFormat::Yaml => "yaml",
-            OutputFormat::Auto => unreachable!(),
-        },
-    };
-
-    let formatter = get_output_format(output_format)?;
-    let output_str = formatter
-        .to_string(&parsed_data)
-        .context("Failed to format output")?;
+    let renamed = rename_key(&mut parsed_data, from, to)?;
+    if verbose {
+        eprintln!(
+            "{} Renamed {} occurrence(s) of '{}'",
+            "Info:".blue().bold(),
+            renamed,
+            from
+        );
+    }
 
-    print!("{output_str}");
-    Ok(())
+    print_document(&parsed_data, format.as_ref(), output)
 }
 
-fn run_exists(path: &str, file: Option<&PathBuf>, verbose: bool) -> Result<()> {
+fn run_exists(
+    path: &str,
+    file: Option<&PathBuf>,
+    count: bool,
+    verbose: bool,
+) -> Result<()> {
     let input = read_input(file)?;
-    let (_, values) = parse_and_extract(&input, path)?;
+    let (_, values) = parse_and_extract(&input, path, None)?;
 
     let exists = !values.is_empty();
 
-    if verbose {
+    if count {
+        if verbose {
+            println!("{} {} match(es)", "Matches:".blue().bold(), values.len());
+        } else {
+            println!("{}", values.len());
+        }
+    } else if verbose {
         if exists {
-            println!("{} Path exists", "✓".green().bold());
+            status_println!("{} Path exists", "✓".green().bold());
         } else {
-            println!("{} Path does not exist", "✗".red().bold());
+            status_println!("{} Path does not exist", "✗".red().bold());
         }
     } else {
         println!("{exists}");
@@ -893,7 +2322,7 @@ fn run_exists(path: &str, file: Option<&PathBuf>, verbose: bool) -> Result<()> {
 
 fn run_type(path: &str, file: Option<&PathBuf>, verbose: bool) -> Result<()> {
     let input = read_input(file)?;
-    let (_, values) = parse_and_extract(&input, path)?;
+    let (_, values) = parse_and_extract(&input, path, None)?;
 
     for value in values {
         let type_name = match value {
@@ -917,7 +2346,7 @@ fn run_type(path: &str, file: Option<&PathBuf>, verbose: bool) -> Result<()> {
 
 fn run_count(path: &str, file: Option<&PathBuf>, verbose: bool) -> Result<()> {
     let input = read_input(file)?;
-    let (_, values) = parse_and_extract(&input, path)?;
+    let (_, values) = parse_and_extract(&input, path, None)?;
 
     if verbose {
         println!("{} {} value(s) found", "Count:".blue().bold(), values.len());
@@ -930,7 +2359,7 @@ fn run_count(path: &str, file: Option<&PathBuf>, verbose: bool) -> Result<()> {
 
 fn run_length(path: &str, file: Option<&PathBuf>, verbose: bool) -> Result<()> {
     let input = read_input(file)?;
-    let (_, values) = parse_and_extract(&input, path)?;
+    let (_, values) = parse_and_extract(&input, path, None)?;
 
     for value in values {
         let length = match &value {
@@ -969,10 +2398,13 @@ fn run_keys(
     file: Option<&PathBuf>,
     output: &OutputFormat,
     pretty: bool,
+    out_file: Option<&PathBuf>,
     verbose: bool,
 ) -> Result<()> {
     let input = read_input(file)?;
-    let (format, values) = parse_and_extract(&input, path)?;
+    let (format, values) = parse_and_extract(&input, path, None)?;
+    let highlight = out_file.is_none() && should_highlight();
+    let mut rendered = String::new();
 
     for value in values {
         match &value {
@@ -980,38 +2412,49 @@ fn run_keys(
                 let keys: Vec<Value> =
                     obj.keys().map(|k| Value::String(k.clone())).collect();
                 let keys_array = Value::Array(keys);
-                output_values(&[keys_array], format.as_ref(), output, pretty)?;
+                rendered.push_str(&format_values(
+                    &[keys_array],
+                    format.as_ref(),
+                    output,
+                    pretty,
+                    highlight,
+                    ValueSeparator::Newline,
+                    &YamlFormatter::default(),
+                )?);
             }
             Value::Array(arr) => {
                 let indices: Vec<Value> = (0..arr.len())
                     .map(|i| Value::Number(serde_json::Number::from(i)))
                     .collect();
                 let indices_array = Value::Array(indices);
-                output_values(
+                rendered.push_str(&format_values(
                     &[indices_array],
                     format.as_ref(),
                     output,
                     pretty,
-                )?;
+                    highlight,
+                    ValueSeparator::Newline,
+                    &YamlFormatter::default(),
+                )?);
             }
             _ => {
                 if verbose {
-                    println!(
+                    eprintln!(
                         "{} Value is not an object or array",
                         "Info:".yellow().bold()
                     );
                 } else {
-                    println!("null");
+                    rendered.push_str("null\n");
                 }
             }
         }
     }
 
-    Ok(())
+    write_output(out_file, &rendered)
 }
 
 fn run_interactive(_file: Option<&PathBuf>) -> Result<()> {
-    println!(
+    status_println!(
         "{}",
         "🚀 Interactive mode is not yet implemented".yellow().bold()
     );
@@ -1020,31 +2463,69 @@ fn run_interactive(_file: Option<&PathBuf>) -> Result<()> {
     Ok(())
 }
 
-fn run_validate(file: Option<&PathBuf>, verbose: bool) -> Result<()> {
+/// 校验输入格式：有效时退出码为 0，任何失败（无法识别、解析错误、schema 违规）均返回错误
+///
+/// 默认在 stdout 输出 `valid`/`invalid`/`unknown`，`quiet` 时不输出任何内容。
+fn run_validate(
+    file: Option<&PathBuf>,
+    #[cfg(feature = "schema")] schema: Option<&PathBuf>,
+    quiet: bool,
+    verbose: bool,
+) -> Result<()> {
     let input = read_input(file)?;
+    #[cfg(not(feature = "schema"))]
+    let schema: Option<&PathBuf> = None;
+
+    let detected = match detect_format_strict(&input) {
+        Err(XqpathError::Format(FormatError::Ambiguous(candidates))) => {
+            if verbose {
+                println!(
+                    "{} Input matches {}; validating as {}",
+                    "!".yellow().bold(),
+                    candidates.join(", "),
+                    candidates[0]
+                );
+            }
+            Ok(Box::new(JsonFormat) as Box<dyn ValueFormat>)
+        }
+        other => other,
+    };
 
-    match detect_format(&input) {
-        Ok(format) => match format.parse(&input) {
-            Ok(_) => {
+    match detected {
+        Ok(format) => match (format.parse(&input), schema) {
+            #[cfg(feature = "schema")]
+            (Ok(value), Some(schema)) => {
+                validate_against_schema(&value, schema, quiet, verbose)?;
                 if verbose {
-                    println!(
+                    status_println!(
+                        "{} Valid {} document against schema",
+                        "✓".green().bold(),
+                        format.name()
+                    );
+                } else if !quiet {
+                    println!("valid");
+                }
+            }
+            (Ok(_), _) => {
+                if verbose {
+                    status_println!(
                         "{} Valid {} format",
                         "✓".green().bold(),
                         format.name()
                     );
-                } else {
+                } else if !quiet {
                     println!("valid");
                 }
             }
-            Err(e) => {
+            (Err(e), _) => {
                 if verbose {
-                    println!(
+                    status_println!(
                         "{} Invalid {}: {}",
                         "✗".red().bold(),
                         format.name(),
                         e
                     );
-                } else {
+                } else if !quiet {
                     println!("invalid");
                 }
                 return Err(anyhow::anyhow!("Validation failed: {}", e));
@@ -1052,21 +2533,86 @@ fn run_validate(file: Option<&PathBuf>, verbose: bool) -> Result<()> {
         },
         Err(e) => {
             if verbose {
-                println!("{} Cannot detect format: {}", "✗".red().bold(), e);
-            } else {
+                status_println!(
+                    "{} Cannot detect format: {}",
+                    "✗".red().bold(),
+                    e
+                );
+            } else if !quiet {
                 println!("unknown");
             }
-            return Err(anyhow::anyhow!("Format detection failed: {}", e));
+            let message = format!("Format detection failed: {e}");
+            return Err(anyhow::Error::new(e).context(message));
         }
     }
 
     Ok(())
 }
 
+/// 使用 JSON Schema 校验已解析的文档，逐条报告错误及其实例路径
+#[cfg(feature = "schema")]
+fn validate_against_schema(
+    value: &Value,
+    schema_file: &PathBuf,
+    quiet: bool,
+    verbose: bool,
+) -> Result<()> {
+    let schema_input = fs::read_to_string(schema_file).with_context(|| {
+        format!("Failed to read schema file: {}", schema_file.display())
+    })?;
+    let schema_format = detect_format(&schema_input)
+        .context("Failed to detect schema format")?;
+    let schema = schema_format
+        .parse(&schema_input)
+        .context("Failed to parse schema")?;
+
+    let compiled = jsonschema::JSONSchema::compile(&schema)
+        .map_err(|e| anyhow::anyhow!("Invalid JSON Schema: {}", e))?;
+
+    let errors: Vec<(String, String)> = match compiled.validate(value) {
+        Ok(()) => return Ok(()),
+        Err(errors) => errors
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                let path = if path.is_empty() {
+                    "/".to_string()
+                } else {
+                    path
+                };
+                (path, e.to_string())
+            })
+            .collect(),
+    };
+
+    if quiet {
+        return Err(anyhow::anyhow!("{} schema violation(s)", errors.len()));
+    }
+    if verbose {
+        status_println!(
+            "{} {} schema violation(s)",
+            "✗".red().bold(),
+            errors.len()
+        );
+    } else {
+        println!("invalid");
+    }
+    for (path, message) in &errors {
+        println!("  {}: {}", path.yellow(), message);
+    }
+
+    Err(anyhow::anyhow!(
+        "Schema validation failed: {} error(s)",
+        errors.len()
+    ))
+}
+
 fn run_convert(
-    to: &OutputFormat,
+    to: &str,
     file: Option<&PathBuf>,
     pretty: bool,
+    ascii_output: bool,
+    yaml: &YamlFormatter,
+    out_file: Option<&PathBuf>,
     _verbose: bool,
 ) -> Result<()> {
     let input = read_input(file)?;
@@ -1078,30 +2624,305 @@ fn run_convert(
         .parse(&input)
         .context("Failed to parse input data")?;
 
-    let output_format = match to {
-        OutputFormat::Auto => {
+    let output = match to.to_lowercase().as_str() {
+        "auto" => {
             return Err(anyhow::anyhow!("Cannot convert to 'auto' format"))
         }
+        "json-pretty" => serde_json::to_string_pretty(&parsed_data)
+            .context("Failed to format as pretty JSON")?,
+        "json" if pretty => serde_json::to_string_pretty(&parsed_data)
+            .context("Failed to format as pretty JSON")?,
+        "compact" => serde_json::to_string(&parsed_data)
+            .context("Failed to format as compact JSON")?,
+        name => {
+            let formatter = get_output_format(name)?;
+            if formatter.name() == "yaml" {
+                yaml.format(&parsed_data)
+            } else {
+                formatter.to_string(&parsed_data)
+            }
+            .context("Failed to format output")?
+        }
+    };
+
+    let to_json = matches!(
+        to.to_lowercase().as_str(),
+        "json" | "json-pretty" | "compact"
+    );
+    let output = if ascii_output && to_json {
+        escape_non_ascii(&output)
+    } else {
+        output
+    };
+
+    write_output(out_file, &output)
+}
+
+/// 将非 ASCII 字符转义为 `\uXXXX`（BMP 之外的字符使用 UTF-16 代理对），类似 jq 的 `-a`
+///
+/// 只适用于 JSON 文本：非 ASCII 字符只会出现在字符串字面量中，转义后语义不变。
+fn escape_non_ascii(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut units = [0u16; 2];
+    for ch in text.chars() {
+        if ch.is_ascii() {
+            escaped.push(ch);
+        } else {
+            for unit in ch.encode_utf16(&mut units) {
+                escaped.push_str(&format!("\\u{unit:04x}"));
+            }
+        }
+    }
+    escaped
+}
+
+/// 读取并解析文档文件（自动检测格式）
+fn load_document(path: &PathBuf) -> Result<Value> {
+    let input = read_input(Some(path))?;
+    let format = detect_format(&input).with_context(|| {
+        format!("Failed to detect format of {}", path.display())
+    })?;
+    format
+        .parse(&input)
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn run_json_patch(
+    patch_file: &PathBuf,
+    file: Option<&PathBuf>,
+    output: &OutputFormat,
+) -> Result<()> {
+    let patch = load_document(patch_file)?;
+
+    let input = read_input(file)?;
+    let format =
+        detect_format(&input).context("Failed to detect input format")?;
+    let mut parsed_data =
+        format.parse(&input).context("Failed to parse input data")?;
+
+    apply_json_patch(&mut parsed_data, &patch)
+        .context("Failed to apply JSON patch")?;
+
+    print_document(&parsed_data, format.as_ref(), output)
+}
+
+fn run_merge_patch(
+    patch_file: &PathBuf,
+    file: Option<&PathBuf>,
+    output: &OutputFormat,
+) -> Result<()> {
+    let patch = load_document(patch_file)?;
+
+    let input = read_input(file)?;
+    let format =
+        detect_format(&input).context("Failed to detect input format")?;
+    let mut parsed_data =
+        format.parse(&input).context("Failed to parse input data")?;
+
+    apply_merge_patch(&mut parsed_data, &patch);
+
+    print_document(&parsed_data, format.as_ref(), output)
+}
+
+/// 以输入格式（或指定格式）输出整个文档
+fn print_document(
+    document: &Value,
+    format: &dyn ValueFormat,
+    output: &OutputFormat,
+) -> Result<()> {
+    let output_format = match output {
+        OutputFormat::Auto => format.name(),
         OutputFormat::Json | OutputFormat::Compact => "json",
         OutputFormat::JsonPretty => "json",
         OutputFormat::Yaml => "yaml",
     };
 
     let formatter = get_output_format(output_format)?;
+    let output_str = formatter
+        .to_string(document)
+        .context("Failed to format output")?;
+    print!("{output_str}");
+    Ok(())
+}
 
-    let output = if matches!(to, OutputFormat::JsonPretty) || pretty {
-        serde_json::to_string_pretty(&parsed_data)
-            .context("Failed to format as pretty JSON")?
-    } else if matches!(to, OutputFormat::Compact) {
-        serde_json::to_string(&parsed_data)
-            .context("Failed to format as compact JSON")?
-    } else {
-        formatter
-            .to_string(&parsed_data)
-            .context("Failed to format output")?
+fn run_diff(old_file: &PathBuf, new_file: &PathBuf) -> Result<()> {
+    let old_value = load_document(old_file)?;
+    let new_value = load_document(new_file)?;
+
+    for difference in diff_values(&old_value, &new_value) {
+        let path = difference.path_string();
+        match &difference {
+            Difference::Added { value, .. } => println!(
+                "{} {}: {}",
+                "added".green().bold(),
+                path,
+                serde_json::to_string(value)?
+            ),
+            Difference::Removed { value, .. } => println!(
+                "{} {}: {}",
+                "removed".red().bold(),
+                path,
+                serde_json::to_string(value)?
+            ),
+            Difference::Changed { old, new, .. } => println!(
+                "{} {}: {} -> {}",
+                "changed".yellow().bold(),
+                path,
+                serde_json::to_string(old)?,
+                serde_json::to_string(new)?
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+fn run_paths(
+    filter: Option<&str>,
+    syntax: Option<PathSyntax>,
+    file: Option<&PathBuf>,
+) -> Result<()> {
+    let input = read_input(file)?;
+    let format =
+        detect_format(&input).context("Failed to detect input format")?;
+    let data = format.parse(&input).context("Failed to parse input data")?;
+
+    let args = match filter {
+        Some(filter) => vec![ExpressionParser::parse_filter_expression(filter)
+            .context("Failed to parse filter expression")?],
+        None => vec![],
+    };
+    let expression = PathExpression::FunctionCall {
+        name: "paths".to_string(),
+        args,
     };
+    let paths = evaluate_path_expression(&expression, &data)
+        .context("Failed to evaluate filter")?;
+
+    for path in paths {
+        let Some(syntax) = syntax else {
+            println!("{}", serde_json::to_string(&path)?);
+            continue;
+        };
+        let segments = value_to_path(&path)
+            .with_context(|| format!("Invalid path array: {path}"))?;
+        match syntax {
+            PathSyntax::Jq => println!("{}", format_path(&segments)),
+            PathSyntax::Jsonpath => println!(
+                "{}",
+                format_jsonpath(&segments)
+                    .context("Path can not be expressed as JSONPath")?
+            ),
+        }
+    }
+    Ok(())
+}
+
+fn run_flatten_paths(file: Option<&PathBuf>) -> Result<()> {
+    let input = read_input(file)?;
+    let format =
+        detect_format(&input).context("Failed to detect input format")?;
+    let data = format.parse(&input).context("Failed to parse input data")?;
+
+    for (path, leaf) in leaf_paths(&data) {
+        // 字符串不加引号，便于直接写入 .env 文件
+        let rendered = match leaf {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        println!("{}={}", dotted_path(&path), rendered);
+    }
+    Ok(())
+}
+
+/// 读取文档，应用扁平化或还原转换后按输入格式（或指定格式）输出
+fn run_flatten(
+    file: Option<&PathBuf>,
+    output: &OutputFormat,
+    transform: fn(&Value) -> Value,
+) -> Result<()> {
+    let input = read_input(file)?;
+    let format =
+        detect_format(&input).context("Failed to detect input format")?;
+    let data = format.parse(&input).context("Failed to parse input data")?;
+
+    print_document(&transform(&data), format.as_ref(), output)
+}
+
+fn run_complexity(path: &str) -> Result<()> {
+    let expression = parse_path_expression(path)
+        .context("Failed to parse path expression")?;
+    let complexity = expression.analyze_complexity();
+    let yes_no = |flag: bool| if flag { "yes" } else { "no" };
+
+    println!("{:<20} {}", "Depth:".bold(), complexity.depth);
+    println!("{:<20} {}", "Pipes:".bold(), complexity.pipe_count);
+    println!(
+        "{:<20} {}",
+        "Comma branches:".bold(),
+        complexity.comma_branches
+    );
+    println!(
+        "{:<20} {}",
+        "Wildcards:".bold(),
+        yes_no(complexity.has_wildcards)
+    );
+    println!(
+        "{:<20} {}",
+        "Recursive wildcards:".bold(),
+        yes_no(complexity.has_recursive_wildcards)
+    );
+    println!(
+        "{:<20} {}",
+        "Needs optimization:".bold(),
+        yes_no(expression.needs_optimization())
+    );
+    Ok(())
+}
+
+fn run_functions(filter: Option<&str>) -> Result<()> {
+    let registry = FunctionRegistry::new();
+    let filter = filter.map(str::to_lowercase);
+    let (advanced, basic): (Vec<_>, Vec<_>) = registry
+        .list_functions()
+        .into_iter()
+        .filter(|(name, _)| {
+            filter
+                .as_ref()
+                .is_none_or(|f| name.to_lowercase().contains(f))
+        })
+        .partition(|(name, _)| registry.get_advanced(name).is_some());
+
+    if basic.is_empty() && advanced.is_empty() {
+        eprintln!(
+            "No functions match '{}'",
+            filter.as_deref().unwrap_or_default()
+        );
+        return Ok(());
+    }
 
-    print!("{output}");
+    let width = basic
+        .iter()
+        .chain(&advanced)
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    for (title, functions) in [
+        ("Basic functions:", basic),
+        ("Advanced functions:", advanced),
+    ] {
+        if functions.is_empty() {
+            continue;
+        }
+        println!("{}", title.bold());
+        for (name, description) in functions {
+            println!(
+                "  {}  {}",
+                format!("{name:<width$}").green(),
+                description
+            );
+        }
+    }
     Ok(())
 }
 
@@ -1110,7 +2931,7 @@ fn run_examples() -> Result<()> {
     println!();
 
     println!("{}", "Basic Operations:".bold());
-    println!("  {} Extract field from JSON/YAML:", "•".blue());
+    status_println!("  {} Extract field from JSON/YAML:", "•".blue());
     println!("    {}", "xqpath get '.user.name' -f data.json".dimmed());
     println!(
         "    {}",
@@ -1118,25 +2939,25 @@ fn run_examples() -> Result<()> {
     );
     println!();
 
-    println!("  {} Check if path exists:", "•".blue());
+    status_println!("  {} Check if path exists:", "•".blue());
     println!(
         "    {}",
         "xqpath exists '.user.email' -f data.json".dimmed()
     );
     println!();
 
-    println!("  {} Get value type:", "•".blue());
+    status_println!("  {} Get value type:", "•".blue());
     println!("    {}", "xqpath type '.users' -f data.json".dimmed());
     println!();
 
-    println!("  {} Count array elements:", "•".blue());
+    status_println!("  {} Count array elements:", "•".blue());
     println!("    {}", "xqpath count '.users[*]' -f data.json".dimmed());
     println!();
 
     #[cfg(feature = "update")]
     {
         println!("{}", "Update Operations:".bold());
-        println!("  {} Update a field:", "•".blue());
+        status_println!("  {} Update a field:", "•".blue());
         println!(
             "    {}",
             "xqpath set '.version' '\"2.0\"' -f config.yaml".dimmed()
@@ -1145,19 +2966,19 @@ fn run_examples() -> Result<()> {
     }
 
     println!("{}", "Advanced Features:".bold());
-    println!("  {} Get object keys:", "•".blue());
+    status_println!("  {} Get object keys:", "•".blue());
     println!("    {}", "xqpath keys '.user' -f data.json".dimmed());
     println!();
 
-    println!("  {} Get length:", "•".blue());
+    status_println!("  {} Get length:", "•".blue());
     println!("    {}", "xqpath length '.users' -f data.json".dimmed());
     println!();
 
-    println!("  {} Validate format:", "•".blue());
+    status_println!("  {} Validate format:", "•".blue());
     println!("    {}", "xqpath validate -f data.json".dimmed());
     println!();
 
-    println!("  {} Convert formats:", "•".blue());
+    status_println!("  {} Convert formats:", "•".blue());
     println!("    {}", "xqpath convert json -f config.yaml".dimmed());
     println!(
         "    {}",
@@ -1172,7 +2993,7 @@ fn run_examples() -> Result<()> {
 
         #[cfg(feature = "profiling")]
         {
-            println!("  {} Profile query performance:", "•".magenta());
+            status_println!("  {} Profile query performance:", "•".magenta());
             println!(
                 "    {}",
                 "xqpath profile '.users[*].name' -f data.json".dimmed()
@@ -1187,7 +3008,10 @@ fn run_examples() -> Result<()> {
             );
             println!();
 
-            println!("  {} Monitor real-time performance:", "•".magenta());
+            status_println!(
+                "  {} Monitor real-time performance:",
+                "•".magenta()
+            );
             println!(
                 "    {}",
                 "xqpath monitor '.users[*]' -f data.json -d 30".dimmed()
@@ -1201,7 +3025,7 @@ fn run_examples() -> Result<()> {
 
         #[cfg(feature = "benchmark")]
         {
-            println!("  {} Benchmark query performance:", "•".magenta());
+            status_println!("  {} Benchmark query performance:", "•".magenta());
             println!(
                 "    {}",
                 "xqpath benchmark '.users[*].name' -f data.json".dimmed()
@@ -1220,27 +3044,39 @@ fn run_examples() -> Result<()> {
         }
     }
 
+    println!("{}", "Builtin Functions:".bold());
+    let registry = FunctionRegistry::new();
+    let names: Vec<&str> = registry
+        .list_functions()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    for line in wrap_names(&names, 72) {
+        println!("    {}", line.dimmed());
+    }
+    println!();
+
     println!("{}", "Path Syntax:".bold());
-    println!("  {} Object field access:", "•".green());
+    status_println!("  {} Object field access:", "•".green());
     println!("    {}", ".field, .nested.field".dimmed());
     println!();
 
-    println!("  {} Array element access:", "•".green());
+    status_println!("  {} Array element access:", "•".green());
     println!("    {}", ".array[0], .users[1].name".dimmed());
     println!();
 
-    println!("  {} Wildcard matching:", "•".green());
+    status_println!("  {} Wildcard matching:", "•".green());
     println!("    {}", ".users[*].name    # All user names".dimmed());
     println!("    {}", ".**               # Recursive search".dimmed());
     println!();
 
-    println!("  {} Type filtering:", "•".green());
+    status_println!("  {} Type filtering:", "•".green());
     println!("    {}", ".data | string    # Only string values".dimmed());
     println!("    {}", ".items | array    # Only array values".dimmed());
     println!();
 
     println!("{}", "Output Options:".bold());
-    println!("  {} Format control:", "•".yellow());
+    status_println!("  {} Format control:", "•".yellow());
     println!("    {}", "--output json     # Force JSON output".dimmed());
     println!("    {}", "--output yaml     # Force YAML output".dimmed());
     println!("    {}", "--pretty          # Pretty-print JSON".dimmed());
@@ -1250,15 +3086,43 @@ fn run_examples() -> Result<()> {
     Ok(())
 }
 
-fn get_output_format(format_name: &str) -> Result<Box<dyn ValueFormat>> {
-    match format_name.to_lowercase().as_str() {
-        "json" => Ok(Box::new(JsonFormat)),
-        "yaml" | "yml" => Ok(Box::new(YamlFormat)),
-        _ => Err(anyhow::anyhow!(
-            "Unsupported output format: {}",
-            format_name
-        )),
+/// 将名称列表按逗号连接并折行，每行不超过 `width` 个字符
+fn wrap_names(names: &[&str], width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for name in names {
+        if !line.is_empty() && line.len() + name.len() + 2 > width {
+            line.push(',');
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push_str(", ");
+        }
+        line.push_str(name);
     }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// CLI 使用的格式注册表，输出格式按名称从中查找
+fn format_registry() -> &'static FormatRegistry {
+    static REGISTRY: OnceLock<FormatRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(FormatRegistry::new)
+}
+
+fn get_output_format(format_name: &str) -> Result<&'static dyn ValueFormat> {
+    let registry = format_registry();
+    registry.get(&format_name.to_lowercase()).ok_or_else(|| {
+        let mut available = registry.list_formats();
+        available.sort_unstable();
+        anyhow::anyhow!(
+            "Unsupported output format: {} (available: {})",
+            format_name,
+            available.join(", ")
+        )
+    })
 }
 
 // v1.4.2 性能分析命令实现
@@ -1268,6 +3132,7 @@ fn run_profile(
     path: &str,
     file: Option<&PathBuf>,
     html: bool,
+    format: ProfileFormat,
     output: Option<&PathBuf>,
     memory: bool,
     hints: bool,
@@ -1276,14 +3141,31 @@ fn run_profile(
 
     let input = read_input(file)?;
 
-    println!("{}", "🔍 Performance Profiling".bold().blue());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    if format == ProfileFormat::Json {
+        let (_result, report) = if memory {
+            query_memory!(input, path)
+                .map_err(|e| anyhow::anyhow!("Memory query failed: {}", e))?
+        } else {
+            profile_complete!(input, path)
+                .map_err(|e| anyhow::anyhow!("Profile query failed: {}", e))?
+        };
+        let json = serde_json::to_string_pretty(&report)?;
+        match output {
+            Some(path) => std::fs::write(path, json + "\n")
+                .context("Failed to write JSON report")?,
+            None => println!("{json}"),
+        }
+        return Ok(());
+    }
+
+    status_println!("{}", "🔍 Performance Profiling".bold().blue());
+    status_println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
     if memory {
         let (_result, memory_report) = query_memory!(input, path)
             .map_err(|e| anyhow::anyhow!("Memory query failed: {}", e))?;
-        println!("✅ Query executed successfully");
-        println!("📊 Memory Analysis:");
+        status_println!("✅ Query executed successfully");
+        status_println!("📊 Memory Analysis:");
         println!(
             "   Peak Memory: {:.2} MB",
             memory_report.peak_memory_bytes as f64 / 1024.0 / 1024.0
@@ -1300,8 +3182,8 @@ fn run_profile(
     } else {
         let (_result, profile) = profile_complete!(input, path)
             .map_err(|e| anyhow::anyhow!("Profile query failed: {}", e))?;
-        println!("✅ Query executed successfully");
-        println!("📊 Performance Metrics:");
+        status_println!("✅ Query executed successfully");
+        status_println!("📊 Performance Metrics:");
         println!("   Execution Time: {:?}", profile.execution_time);
         println!(
             "   Peak Memory: {:.2} MB",
@@ -1310,9 +3192,9 @@ fn run_profile(
         println!("   CPU Usage: {:.1}%", profile.cpu_usage_percent);
 
         if hints && !profile.optimization_hints.is_empty() {
-            println!("\n💡 Optimization Hints:");
+            status_println!("\n💡 Optimization Hints:");
             for hint in &profile.optimization_hints {
-                println!("   • {hint}");
+                status_println!("   • {hint}");
             }
         }
 
@@ -1324,7 +3206,10 @@ fn run_profile(
             std::fs::write(&output_path, profile.to_html())
                 .context("Failed to write HTML report")?;
 
-            println!("\n📄 HTML report saved to: {}", output_path.display());
+            status_println!(
+                "\n📄 HTML report saved to: {}",
+                output_path.display()
+            );
         }
     }
 
@@ -1335,38 +3220,31 @@ fn run_profile(
 fn run_benchmark(
     path: &str,
     file: Option<&PathBuf>,
-    iterations: usize,
-    warmup: usize,
+    config: BenchmarkConfig,
     format: &BenchmarkOutputFormat,
     output: Option<&PathBuf>,
     baseline: Option<&PathBuf>,
+    fail_threshold: Option<f64>,
 ) -> Result<()> {
-    use std::time::Duration;
     use xqpath::{
-        benchmark_query, BenchmarkConfig,
-        BenchmarkOutputFormat as LibBenchmarkFormat, BenchmarkSuite,
+        benchmark_query, BenchmarkOutputFormat as LibBenchmarkFormat,
+        BenchmarkResult, BenchmarkSuite,
     };
 
     let input = read_input(file)?;
 
-    println!("{}", "⚡ Performance Benchmark".bold().yellow());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    status_println!("{}", "⚡ Performance Benchmark".bold().yellow());
+    status_println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
     // 快速基准测试
+    let iterations = config.test_iterations;
     let (_result, benchmark_result) = benchmark_query!(input, path, iterations)
         .map_err(|e| anyhow::anyhow!("Benchmark query failed: {}", e))?;
-    println!("✅ Query executed successfully");
-    println!("📊 Quick Benchmark Results:");
+    status_println!("✅ Query executed successfully");
+    status_println!("📊 Quick Benchmark Results:");
     println!("   {}", benchmark_result.summary());
 
     // 详细基准测试套件
-    let config = BenchmarkConfig {
-        warmup_iterations: warmup,
-        test_iterations: iterations,
-        min_test_time: Duration::from_millis(10),
-        max_test_time: Duration::from_secs(30),
-    };
-
     let mut suite = BenchmarkSuite::with_config(config);
     let input_clone = input.clone();
     let path_clone = path.to_string();
@@ -1380,7 +3258,7 @@ fn run_benchmark(
         .run()
         .map_err(|e| anyhow::anyhow!("Suite run failed: {}", e))?;
 
-    println!("\n📊 Detailed Benchmark Results:");
+    status_println!("\n📊 Detailed Benchmark Results:");
     for result in &results {
         println!("   {}", result.summary());
     }
@@ -1402,13 +3280,51 @@ fn run_benchmark(
             lib_format,
         )
         .map_err(|e| anyhow::anyhow!("Failed to save results: {}", e))?;
-        println!("\n📄 Benchmark results saved to: {}", output_path.display());
+        status_println!(
+            "\n📄 Benchmark results saved to: {}",
+            output_path.display()
+        );
     }
 
-    // 比较基准线
+    // 比较基准线（基线文件为 --format json 保存的结果）
     if let Some(baseline_path) = baseline {
-        println!("\n📈 Baseline comparison not yet implemented");
-        println!("   Baseline file: {}", baseline_path.display());
+        let content = fs::read_to_string(baseline_path).with_context(|| {
+            format!("Failed to read baseline: {}", baseline_path.display())
+        })?;
+        let baseline_results: Vec<BenchmarkResult> =
+            serde_json::from_str(&content).with_context(|| {
+                format!(
+                "Invalid baseline file (expected JSON benchmark results): {}",
+                baseline_path.display()
+            )
+            })?;
+
+        status_println!("\n📈 Baseline Comparison:");
+        for line in
+            BenchmarkSuite::compare_with_baseline(&results, &baseline_results)
+        {
+            println!("   {line}");
+        }
+
+        if let Some(threshold) = fail_threshold {
+            let regressions = BenchmarkSuite::regressions(
+                &results,
+                &baseline_results,
+                threshold,
+            );
+            if !regressions.is_empty() {
+                let details: Vec<String> = regressions
+                    .iter()
+                    .map(|(name, slowdown)| format!("{name} (+{slowdown:.1}%)"))
+                    .collect();
+                return Err(anyhow::anyhow!(
+                    "{} benchmark(s) regressed more than {}%: {}",
+                    regressions.len(),
+                    threshold,
+                    details.join(", ")
+                ));
+            }
+        }
     }
 
     Ok(())
@@ -1428,8 +3344,8 @@ fn run_monitor(
 
     let input = read_input(file)?;
 
-    println!("{}", "📊 Performance Monitor".bold().green());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    status_println!("{}", "📊 Performance Monitor".bold().green());
+    status_println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("Duration: {duration} seconds, Interval: {interval} ms");
     println!("Path: {path}");
     println!();
@@ -1468,7 +3384,7 @@ fn run_monitor(
 
     let final_report = monitor.stop();
 
-    println!("🏁 Final Performance Report:");
+    status_println!("🏁 Final Performance Report:");
     println!("   Total iterations: {iteration}");
     println!("   {}", final_report.summary());
 
@@ -1477,7 +3393,7 @@ fn run_monitor(
     std::fs::write(&report_path, final_report.to_html())
         .context("Failed to write monitor report")?;
 
-    println!("\n📄 Monitor report saved to: {}", report_path.display());
+    status_println!("\n📄 Monitor report saved to: {}", report_path.display());
 
     Ok(())
 }
@@ -1494,8 +3410,8 @@ fn run_debug(
 
     let input = read_input(file)?;
 
-    println!("{}", "🔍 Debug Mode Execution".bold().blue());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    status_println!("{}", "🔍 Debug Mode Execution".bold().blue());
+    status_println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("Path: {path}");
     if let Some(file_path) = file {
         println!("Input: {}", file_path.display());
@@ -1505,18 +3421,20 @@ fn run_debug(
     println!();
 
     if interactive {
-        println!("🎯 Interactive Debug Mode");
+        status_println!("🎯 Interactive Debug Mode");
         println!("Type 'help' for commands, 'quit' to exit");
         // TODO: 实现交互式调试模式
-        println!("⚠️  Interactive mode will be implemented in future version");
+        status_println!(
+            "⚠️  Interactive mode will be implemented in future version"
+        );
         println!();
     }
 
     // 执行调试查询
-    println!("🚀 Executing debug query...");
+    status_println!("🚀 Executing debug query...");
     let result =
         query_debug!(input, path, |debug_info: &xqpath::debug::DebugInfo| {
-            println!("🔍 Debug Info:");
+            status_println!("🔍 Debug Info:");
             if let Some(parse_time) = debug_info.parse_duration {
                 println!("   Parse time: {parse_time:?}");
             }
@@ -1534,8 +3452,8 @@ fn run_debug(
 
     match result {
         Ok(values) => {
-            println!("✅ Query executed successfully");
-            println!("📊 Results: {} value(s) found", values.len());
+            status_println!("✅ Query executed successfully");
+            status_println!("📊 Results: {} value(s) found", values.len());
 
             for (i, value) in values.iter().enumerate() {
                 println!(
@@ -1546,7 +3464,7 @@ fn run_debug(
             }
         }
         Err(e) => {
-            println!("❌ Query failed with error:");
+            status_println!("❌ Query failed with error:");
             println!("   {e}");
 
             // 分析错误并提供建议
@@ -1563,8 +3481,8 @@ fn run_trace(path: &str, file: Option<&PathBuf>, detailed: bool) -> Result<()> {
 
     let input = read_input(file)?;
 
-    println!("{}", "📊 Path Execution Trace".bold().green());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    status_println!("{}", "📊 Path Execution Trace".bold().green());
+    status_println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("Path: {path}");
     if detailed {
         println!("Mode: Detailed trace");
@@ -1574,23 +3492,27 @@ fn run_trace(path: &str, file: Option<&PathBuf>, detailed: bool) -> Result<()> {
     println!();
 
     // 执行跟踪查询
-    println!("🚀 Starting path execution trace...");
+    status_println!("🚀 Starting path execution trace...");
     let result = trace_query!(input, path);
 
+    if detailed {
+        print_trace_steps(&input, path);
+    }
+
     match result {
         Ok((values, stats)) => {
-            println!("✅ Trace completed successfully");
-            println!("📊 Execution Time: {:?}", stats.duration);
-            println!("📊 Final Results: {} value(s)", values.len());
+            status_println!("✅ Trace completed successfully");
+            status_println!("📊 Execution Time: {:?}", stats.duration);
+            status_println!("📊 Final Results: {} value(s)", values.len());
 
             if detailed {
-                println!("\n📋 Detailed Results:");
+                status_println!("\n📋 Detailed Results:");
                 for (i, value) in values.iter().enumerate() {
                     println!("  [{}] Type: {}", i + 1, get_value_type(value));
                     println!("      Value: {}", format_value_preview(value));
                 }
             } else {
-                println!("\n📋 Results Summary:");
+                status_println!("\n📋 Results Summary:");
                 for (i, value) in values.iter().enumerate() {
                     println!(
                         "  [{}] {}: {}",
@@ -1602,7 +3524,7 @@ fn run_trace(path: &str, file: Option<&PathBuf>, detailed: bool) -> Result<()> {
             }
         }
         Err(e) => {
-            println!("❌ Trace failed with error:");
+            status_println!("❌ Trace failed with error:");
             println!("   {e}");
 
             // 分析错误并提供建议
@@ -1613,42 +3535,78 @@ fn run_trace(path: &str, file: Option<&PathBuf>, detailed: bool) -> Result<()> {
     Ok(())
 }
 
+/// 以表格形式打印每个路径段的执行步骤
+#[cfg(feature = "debug")]
+fn print_trace_steps(input: &str, path: &str) {
+    use xqpath::Extractor;
+
+    let Ok(format) = detect_format(input) else {
+        return;
+    };
+    let (Ok(data), Ok(segments)) = (format.parse(input), parse_path(path))
+    else {
+        return;
+    };
+
+    let (_, steps) = Extractor::extract_traced(&data, &segments);
+
+    status_println!("\n🔍 Segment Steps:");
+    println!(
+        "  {:>3}  {:<24} {:>8} {:>8} {:>12}",
+        "#", "Segment", "In", "Out", "Time"
+    );
+    for (i, step) in steps.iter().enumerate() {
+        println!(
+            "  {:>3}  {:<24} {:>8} {:>8} {:>12}",
+            i + 1,
+            step.segment.to_jq_string(),
+            step.input_count,
+            step.output_count,
+            format!("{:?}", step.duration)
+        );
+        if let Some(error) = &step.error {
+            status_println!("       {} {}", "↳".red(), error.red());
+        }
+    }
+    println!();
+}
+
 #[cfg(feature = "debug")]
 fn provide_error_suggestions(path: &str, error: &str) {
-    println!("\n💡 Error Analysis & Suggestions:");
+    status_println!("\n💡 Error Analysis & Suggestions:");
 
     if error.contains("parse") || error.contains("syntax") {
-        println!("   🔍 Parse Error Detected:");
-        println!("   • Check path syntax: {path}");
-        println!("   • Common issues:");
+        status_println!("   🔍 Parse Error Detected:");
+        status_println!("   • Check path syntax: {path}");
+        status_println!("   • Common issues:");
         println!(
             "     - Missing quotes around field names with special characters"
         );
         println!("     - Incorrect array index syntax");
         println!("     - Unmatched brackets or parentheses");
     } else if error.contains("field") || error.contains("key") {
-        println!("   🔍 Field Access Error:");
-        println!("   • Field might not exist in the data");
-        println!("   • Try using optional operator: .field?");
-        println!("   • Check if data structure matches expectation");
+        status_println!("   🔍 Field Access Error:");
+        status_println!("   • Field might not exist in the data");
+        status_println!("   • Try using optional operator: .field?");
+        status_println!("   • Check if data structure matches expectation");
     } else if error.contains("index") || error.contains("array") {
-        println!("   🔍 Array Access Error:");
-        println!("   • Array index might be out of bounds");
-        println!("   • Use wildcard for all elements: [*]");
-        println!("   • Check if the value is actually an array");
+        status_println!("   🔍 Array Access Error:");
+        status_println!("   • Array index might be out of bounds");
+        status_println!("   • Use wildcard for all elements: [*]");
+        status_println!("   • Check if the value is actually an array");
     } else if error.contains("type") {
-        println!("   🔍 Type Error:");
-        println!("   • Operation not supported for this data type");
-        println!("   • Use type filters: | string, | array, | object");
-        println!("   • Check data type before operation");
+        status_println!("   🔍 Type Error:");
+        status_println!("   • Operation not supported for this data type");
+        status_println!("   • Use type filters: | string, | array, | object");
+        status_println!("   • Check data type before operation");
     } else {
-        println!("   🔍 General Error:");
-        println!("   • Try simplifying the path expression");
-        println!("   • Test with shorter path segments");
-        println!("   • Use --verbose for more details");
+        status_println!("   🔍 General Error:");
+        status_println!("   • Try simplifying the path expression");
+        status_println!("   • Test with shorter path segments");
+        status_println!("   • Use --verbose for more details");
     }
 
-    println!("\n📖 For more help, run: xqpath examples");
+    status_println!("\n📖 For more help, run: xqpath examples");
 }
 
 #[cfg(feature = "debug")]
@@ -1666,7 +3624,9 @@ fn get_value_type(value: &Value) -> &'static str {
 #[cfg(feature = "debug")]
 fn format_value_preview(value: &Value) -> String {
     match value {
-        Value::String(s) if s.len() > 50 => format!("\"{}...\"", &s[..47]),
+        Value::String(s) if s.chars().count() > 50 => {
+            format!("\"{}\"", truncate_str(s, 50))
+        }
         Value::Array(arr) => format!("[{} elements]", arr.len()),
         Value::Object(obj) => format!("{{{}keys}}", obj.len()),
         _ => value.to_string(),
@@ -1681,7 +3641,7 @@ fn run_config(action: &ConfigAction) -> Result<()> {
     let mut manager = match ConfigManager::new() {
         Ok(m) => m,
         Err(e) => {
-            eprintln!("❌ 配置管理器初始化失败: {e}");
+            status_eprintln!("❌ 配置管理器初始化失败: {e}");
             return Ok(());
         }
     };
@@ -1689,23 +3649,23 @@ fn run_config(action: &ConfigAction) -> Result<()> {
     match action {
         ConfigAction::Show => {
             let config = manager.get_config();
-            println!("📋 当前配置:");
+            status_println!("📋 当前配置:");
             println!("活动配置文件: {}", manager.get_active_profile());
             println!();
 
             // 显示配置内容（这里使用简化的显示）
-            println!("🔧 调试配置:");
+            status_println!("🔧 调试配置:");
             println!("  level: {}", config.debug.level);
             println!("  output: {}", config.debug.output);
             println!("  timing: {}", config.debug.timing);
 
-            println!("\n⚡ 性能配置:");
+            status_println!("\n⚡ 性能配置:");
             println!("  memory_limit: {}", config.performance.memory_limit);
             println!("  timeout: {}", config.performance.timeout);
             println!("  cache_size: {}", config.performance.cache_size);
             println!("  parallel_jobs: {}", config.performance.parallel_jobs);
 
-            println!("\n🎯 功能配置:");
+            status_println!("\n🎯 功能配置:");
             println!("  colored_output: {}", config.features.colored_output);
             println!(
                 "  interactive_mode: {}",
@@ -1718,33 +3678,33 @@ fn run_config(action: &ConfigAction) -> Result<()> {
             match manager.set_config_value(key, value) {
                 Ok(()) => {
                     if let Ok(()) = manager.save_config() {
-                        println!("✅ 配置已更新: {key} = {value}");
+                        status_println!("✅ 配置已更新: {key} = {value}");
                     } else {
-                        eprintln!("❌ 配置保存失败");
+                        status_eprintln!("❌ 配置保存失败");
                     }
                 }
                 Err(e) => {
-                    eprintln!("❌ 配置设置失败: {e}");
+                    status_eprintln!("❌ 配置设置失败: {e}");
                 }
             }
         }
 
         ConfigAction::Reset => match manager.reset_config() {
             Ok(()) => {
-                println!("🔄 配置已重置为默认值");
+                status_println!("🔄 配置已重置为默认值");
             }
             Err(e) => {
-                eprintln!("❌ 配置重置失败: {e}");
+                status_eprintln!("❌ 配置重置失败: {e}");
             }
         },
 
         ConfigAction::Template { name } => {
             match manager.create_template(name) {
                 Ok(()) => {
-                    println!("📄 配置模板已创建: {name}");
+                    status_println!("📄 配置模板已创建: {name}");
                 }
                 Err(e) => {
-                    eprintln!("❌ 模板创建失败: {e}");
+                    status_eprintln!("❌ 模板创建失败: {e}");
                 }
             }
         }
@@ -1753,20 +3713,20 @@ fn run_config(action: &ConfigAction) -> Result<()> {
             ProfileAction::Create { name } => {
                 match manager.create_profile(name) {
                     Ok(()) => {
-                        println!("📁 配置文件已创建: {name}");
+                        status_println!("📁 配置文件已创建: {name}");
                     }
                     Err(e) => {
-                        eprintln!("❌ 配置文件创建失败: {e}");
+                        status_eprintln!("❌ 配置文件创建失败: {e}");
                     }
                 }
             }
             ProfileAction::Switch { name } => {
                 match manager.switch_profile(name) {
                     Ok(()) => {
-                        println!("🔄 已切换到配置文件: {name}");
+                        status_println!("🔄 已切换到配置文件: {name}");
                     }
                     Err(e) => {
-                        eprintln!("❌ 配置文件切换失败: {e}");
+                        status_eprintln!("❌ 配置文件切换失败: {e}");
                     }
                 }
             }
@@ -1774,23 +3734,26 @@ fn run_config(action: &ConfigAction) -> Result<()> {
                 let profiles = manager.list_profiles();
                 let active = manager.get_active_profile();
 
-                println!("📁 可用的配置文件:");
+                status_println!("📁 可用的配置文件:");
                 for profile in profiles {
                     if profile == active {
-                        println!("  • {} (当前)", profile.green().bold());
+                        status_println!(
+                            "  • {} (当前)",
+                            profile.green().bold()
+                        );
                     } else {
-                        println!("  • {profile}");
+                        status_println!("  • {profile}");
                     }
                 }
             }
         },
 
         ConfigAction::Audit => {
-            println!("📊 配置审计功能开发中...");
+            status_println!("📊 配置审计功能开发中...");
         }
 
         ConfigAction::Migrate => {
-            println!("🔄 配置迁移功能开发中...");
+            status_println!("🔄 配置迁移功能开发中...");
         }
     }
 
@@ -1802,22 +3765,22 @@ fn run_config(action: &ConfigAction) -> Result<()> {
 fn run_interactive_debugger(file: Option<&PathBuf>) -> Result<()> {
     use xqpath::debugger::XQPathDebugger;
 
-    println!("🚀 启动 XQPath 交互式调试器...");
+    status_println!("🚀 启动 XQPath 交互式调试器...");
 
     let mut debugger = XQPathDebugger::new();
 
     // 如果指定了文件，预加载它
     if let Some(file_path) = file {
-        println!("📁 预加载文件: {}", file_path.display());
+        status_println!("📁 预加载文件: {}", file_path.display());
         // 这里需要实现文件预加载逻辑
     }
 
     match debugger.run() {
         Ok(()) => {
-            println!("👋 调试器会话结束");
+            status_println!("👋 调试器会话结束");
         }
         Err(e) => {
-            eprintln!("❌ 调试器错误: {e}");
+            status_eprintln!("❌ 调试器错误: {e}");
         }
     }
 
@@ -0,0 +1,127 @@
+//! 基于全局分配器的内存计数 - 精确统计查询自身的分配
+//!
+//! 需要在可执行程序中将 [`CountingAllocator`] 安装为全局分配器，
+//! 否则计数始终为 0，性能分析会退回到基于进程 RSS 的统计：
+//!
+//! ```rust,ignore
+//! use xqpath::debug::alloc::CountingAllocator;
+//!
+//! #[global_allocator]
+//! static GLOBAL: CountingAllocator = CountingAllocator::new();
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static BASELINE: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static FREED: AtomicUsize = AtomicUsize::new(0);
+
+/// 统计分配/释放字节数的全局分配器包装，底层使用系统分配器
+///
+/// 计数器是进程级的，并发查询的分配会互相叠加。
+#[derive(Debug, Default)]
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    /// 创建分配器，用于 `#[global_allocator]` 静态变量
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// 是否已被安装为全局分配器（至少处理过一次分配）
+    pub fn is_installed() -> bool {
+        INSTALLED.load(Ordering::Relaxed)
+    }
+
+    /// 开始新的统计窗口：以当前堆占用为基线，清零峰值与累计计数
+    pub fn start() {
+        let current = CURRENT.load(Ordering::Relaxed);
+        BASELINE.store(current, Ordering::Relaxed);
+        PEAK.store(current, Ordering::Relaxed);
+        ALLOCATED.store(0, Ordering::Relaxed);
+        FREED.store(0, Ordering::Relaxed);
+    }
+
+    /// 将峰值重置为当前占用，保留基线
+    pub fn reset() {
+        PEAK.store(CURRENT.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
+    /// 统计窗口内相对基线的峰值占用（字节）
+    pub fn peak() -> usize {
+        PEAK.load(Ordering::Relaxed)
+            .saturating_sub(BASELINE.load(Ordering::Relaxed))
+    }
+
+    /// 统计窗口内相对基线的当前净占用（字节）
+    pub fn current() -> usize {
+        CURRENT
+            .load(Ordering::Relaxed)
+            .saturating_sub(BASELINE.load(Ordering::Relaxed))
+    }
+
+    /// 统计窗口内累计分配的字节数
+    pub fn allocated() -> usize {
+        ALLOCATED.load(Ordering::Relaxed)
+    }
+
+    /// 统计窗口内累计释放的字节数
+    pub fn freed() -> usize {
+        FREED.load(Ordering::Relaxed)
+    }
+
+    fn record_alloc(size: usize) {
+        if !INSTALLED.load(Ordering::Relaxed) {
+            INSTALLED.store(true, Ordering::Relaxed);
+        }
+        ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        FREED.fetch_add(size, Ordering::Relaxed);
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::record_dealloc(layout.size());
+            Self::record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
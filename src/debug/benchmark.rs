@@ -21,6 +21,12 @@ pub struct BenchmarkResult {
     pub max_time: Duration,
     /// 标准差
     pub std_dev: Duration,
+    /// 中位数（第 50 百分位）执行时间
+    pub p50_time: Duration,
+    /// 第 95 百分位执行时间
+    pub p95_time: Duration,
+    /// 第 99 百分位执行时间
+    pub p99_time: Duration,
     /// 执行次数
     pub iterations: usize,
     /// 每秒操作数
@@ -29,6 +35,47 @@ pub struct BenchmarkResult {
 
 #[cfg(feature = "benchmark")]
 impl BenchmarkResult {
+    /// 由各次迭代的耗时计算统计结果，没有耗时数据时返回 `None`
+    fn from_times(name: &str, times: &[Duration]) -> Option<Self> {
+        if times.is_empty() {
+            return None;
+        }
+
+        let total_time: Duration = times.iter().sum();
+        let mean_time = total_time / times.len() as u32;
+
+        let variance: f64 = times
+            .iter()
+            .map(|&time| {
+                let diff = time.as_nanos() as f64 - mean_time.as_nanos() as f64;
+                diff * diff
+            })
+            .sum::<f64>()
+            / times.len() as f64;
+        let std_dev = Duration::from_nanos(variance.sqrt() as u64);
+
+        let mut sorted = times.to_vec();
+        sorted.sort_unstable();
+        // 最近秩法：第 p 百分位取排序后第 ceil(p/100 * n) 个值
+        let percentile = |p: usize| {
+            let rank = (p * sorted.len()).div_ceil(100).max(1);
+            sorted[rank - 1]
+        };
+
+        Some(BenchmarkResult {
+            name: name.to_string(),
+            mean_time,
+            min_time: sorted[0],
+            max_time: sorted[sorted.len() - 1],
+            std_dev,
+            p50_time: percentile(50),
+            p95_time: percentile(95),
+            p99_time: percentile(99),
+            iterations: times.len(),
+            ops_per_sec: 1_000_000_000.0 / mean_time.as_nanos() as f64,
+        })
+    }
+
     /// 生成结果摘要
     pub fn summary(&self) -> String {
         format!(
@@ -37,10 +84,14 @@ impl BenchmarkResult {
         )
     }
 
+    /// 平均耗时相对基线的比值，大于 1 表示比基线慢
+    pub fn ratio_to(&self, baseline: &BenchmarkResult) -> f64 {
+        self.mean_time.as_nanos() as f64 / baseline.mean_time.as_nanos() as f64
+    }
+
     /// 与基线比较
     pub fn compare_with(&self, baseline: &BenchmarkResult) -> String {
-        let ratio = self.mean_time.as_nanos() as f64
-            / baseline.mean_time.as_nanos() as f64;
+        let ratio = self.ratio_to(baseline);
 
         if ratio > 1.1 {
             format!("⚠️  {} 比基线慢 {:.1}%", self.name, (ratio - 1.0) * 100.0)
@@ -73,6 +124,8 @@ pub struct BenchmarkSuite {
 pub struct BenchmarkConfig {
     /// 预热次数
     pub warmup_iterations: usize,
+    /// 预热时长；设置后持续预热直到时间耗尽，忽略 `warmup_iterations`
+    pub warmup_time: Option<Duration>,
     /// 测试次数
     pub test_iterations: usize,
     /// 最小测试时间
@@ -86,6 +139,7 @@ impl Default for BenchmarkConfig {
     fn default() -> Self {
         Self {
             warmup_iterations: 10,
+            warmup_time: None,
             test_iterations: 100,
             min_test_time: Duration::from_millis(100),
             max_test_time: Duration::from_secs(10),
@@ -141,9 +195,19 @@ impl BenchmarkSuite {
         name: &str,
         test_fn: &dyn Fn() -> Result<(), Box<dyn std::error::Error>>,
     ) -> Result<BenchmarkResult, Box<dyn std::error::Error>> {
-        // 预热
-        for _ in 0..self.config.warmup_iterations {
-            test_fn()?;
+        // 预热：按时长或按次数
+        match self.config.warmup_time {
+            Some(warmup_time) => {
+                let warmup_start = Instant::now();
+                while warmup_start.elapsed() < warmup_time {
+                    test_fn()?;
+                }
+            }
+            None => {
+                for _ in 0..self.config.warmup_iterations {
+                    test_fn()?;
+                }
+            }
         }
 
         let mut times = Vec::new();
@@ -169,39 +233,8 @@ impl BenchmarkSuite {
             times.push(test_time);
         }
 
-        if times.is_empty() {
-            return Err("没有成功的测试迭代".into());
-        }
-
-        // 计算统计数据
-        let total_time: Duration = times.iter().sum();
-        let mean_time = total_time / times.len() as u32;
-        let min_time = *times.iter().min().unwrap();
-        let max_time = *times.iter().max().unwrap();
-
-        // 计算标准差
-        let variance: f64 = times
-            .iter()
-            .map(|&time| {
-                let diff = time.as_nanos() as f64 - mean_time.as_nanos() as f64;
-                diff * diff
-            })
-            .sum::<f64>()
-            / times.len() as f64;
-        let std_dev = Duration::from_nanos(variance.sqrt() as u64);
-
-        // 计算每秒操作数
-        let ops_per_sec = 1_000_000_000.0 / mean_time.as_nanos() as f64;
-
-        Ok(BenchmarkResult {
-            name: name.to_string(),
-            mean_time,
-            min_time,
-            max_time,
-            std_dev,
-            iterations: times.len(),
-            ops_per_sec,
-        })
+        BenchmarkResult::from_times(name, &times)
+            .ok_or_else(|| "没有成功的测试迭代".into())
     }
 
     /// 与基线结果比较
@@ -222,6 +255,28 @@ impl BenchmarkSuite {
             .collect()
     }
 
+    /// 找出比基线慢超过 `threshold_percent` 的测试，返回名称与变慢的百分比
+    ///
+    /// 基线中不存在的测试不参与比较。
+    pub fn regressions(
+        results: &[BenchmarkResult],
+        baseline: &[BenchmarkResult],
+        threshold_percent: f64,
+    ) -> Vec<(String, f64)> {
+        let baseline_map: HashMap<_, _> =
+            baseline.iter().map(|r| (r.name.as_str(), r)).collect();
+
+        results
+            .iter()
+            .filter_map(|result| {
+                let baseline = baseline_map.get(result.name.as_str())?;
+                let slowdown = (result.ratio_to(baseline) - 1.0) * 100.0;
+                (slowdown > threshold_percent)
+                    .then(|| (result.name.clone(), slowdown))
+            })
+            .collect()
+    }
+
     /// 生成HTML格式的基准测试报告
     pub fn generate_html_report(results: &[BenchmarkResult]) -> String {
         let mut html = String::new();
@@ -267,6 +322,35 @@ impl BenchmarkSuite {
         html
     }
 
+    /// CSV 报告的表头，时间列均以纳秒为单位
+    pub const CSV_HEADER: &'static str = "name,iterations,mean_ns,min_ns,max_ns,std_dev_ns,ops_per_sec,p50_ns,p95_ns,p99_ns";
+
+    /// 生成带表头的 CSV 报告，名称中含逗号、引号或换行时按 RFC 4180 加引号
+    pub fn generate_csv_report(results: &[BenchmarkResult]) -> String {
+        let mut csv = format!("{}\n", Self::CSV_HEADER);
+        for result in results {
+            let name = if result.name.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", result.name.replace('"', "\"\""))
+            } else {
+                result.name.clone()
+            };
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{:.3},{},{},{}\n",
+                name,
+                result.iterations,
+                result.mean_time.as_nanos(),
+                result.min_time.as_nanos(),
+                result.max_time.as_nanos(),
+                result.std_dev.as_nanos(),
+                result.ops_per_sec,
+                result.p50_time.as_nanos(),
+                result.p95_time.as_nanos(),
+                result.p99_time.as_nanos(),
+            ));
+        }
+        csv
+    }
+
     /// 保存基准测试结果到文件
     pub fn save_results_to_file(
         results: &[BenchmarkResult],
@@ -280,21 +364,7 @@ impl BenchmarkSuite {
                 serde_json::to_string_pretty(results)?
             }
             BenchmarkOutputFormat::Html => Self::generate_html_report(results),
-            BenchmarkOutputFormat::Csv => {
-                let mut csv = String::from("name,mean_time_ns,min_time_ns,max_time_ns,std_dev_ns,ops_per_sec\n");
-                for result in results {
-                    csv.push_str(&format!(
-                        "{},{},{},{},{},{}\n",
-                        result.name,
-                        result.mean_time.as_nanos(),
-                        result.min_time.as_nanos(),
-                        result.max_time.as_nanos(),
-                        result.std_dev.as_nanos(),
-                        result.ops_per_sec
-                    ));
-                }
-                csv
-            }
+            BenchmarkOutputFormat::Csv => Self::generate_csv_report(results),
         };
 
         fs::write(filename, content)?;
@@ -327,6 +397,12 @@ struct SerializableBenchmarkResult {
     min_time_ns: u64,
     max_time_ns: u64,
     std_dev_ns: u64,
+    #[serde(default)]
+    p50_time_ns: u64,
+    #[serde(default)]
+    p95_time_ns: u64,
+    #[serde(default)]
+    p99_time_ns: u64,
     iterations: usize,
     ops_per_sec: f64,
 }
@@ -340,12 +416,44 @@ impl From<&BenchmarkResult> for SerializableBenchmarkResult {
             min_time_ns: result.min_time.as_nanos() as u64,
             max_time_ns: result.max_time.as_nanos() as u64,
             std_dev_ns: result.std_dev.as_nanos() as u64,
+            p50_time_ns: result.p50_time.as_nanos() as u64,
+            p95_time_ns: result.p95_time.as_nanos() as u64,
+            p99_time_ns: result.p99_time.as_nanos() as u64,
+            iterations: result.iterations,
+            ops_per_sec: result.ops_per_sec,
+        }
+    }
+}
+
+#[cfg(feature = "benchmark")]
+impl From<SerializableBenchmarkResult> for BenchmarkResult {
+    fn from(result: SerializableBenchmarkResult) -> Self {
+        Self {
+            name: result.name,
+            mean_time: Duration::from_nanos(result.mean_time_ns),
+            min_time: Duration::from_nanos(result.min_time_ns),
+            max_time: Duration::from_nanos(result.max_time_ns),
+            std_dev: Duration::from_nanos(result.std_dev_ns),
+            p50_time: Duration::from_nanos(result.p50_time_ns),
+            p95_time: Duration::from_nanos(result.p95_time_ns),
+            p99_time: Duration::from_nanos(result.p99_time_ns),
             iterations: result.iterations,
             ops_per_sec: result.ops_per_sec,
         }
     }
 }
 
+/// 从 JSON 报告读取基线结果
+#[cfg(feature = "benchmark")]
+impl<'de> Deserialize<'de> for BenchmarkResult {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        SerializableBenchmarkResult::deserialize(deserializer).map(Into::into)
+    }
+}
+
 #[cfg(feature = "benchmark")]
 impl Serialize for BenchmarkResult {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -397,3 +505,167 @@ impl BenchmarkResult {
         "Benchmark feature not enabled".to_string()
     }
 }
+
+#[cfg(all(test, feature = "benchmark"))]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn sample_times() -> Vec<Duration> {
+        (1..=100).map(Duration::from_micros).collect()
+    }
+
+    #[test]
+    fn test_result_percentiles() {
+        let result =
+            BenchmarkResult::from_times("query", &sample_times()).unwrap();
+        assert_eq!(result.iterations, 100);
+        assert_eq!(result.min_time, Duration::from_micros(1));
+        assert_eq!(result.max_time, Duration::from_micros(100));
+        assert_eq!(result.p50_time, Duration::from_micros(50));
+        assert_eq!(result.p95_time, Duration::from_micros(95));
+        assert_eq!(result.p99_time, Duration::from_micros(99));
+
+        let single =
+            BenchmarkResult::from_times("one", &[Duration::from_micros(7)])
+                .unwrap();
+        assert_eq!(single.p50_time, Duration::from_micros(7));
+        assert_eq!(single.p99_time, Duration::from_micros(7));
+        assert!(BenchmarkResult::from_times("none", &[]).is_none());
+    }
+
+    fn counting_suite(
+        config: BenchmarkConfig,
+    ) -> (BenchmarkSuite, Rc<Cell<usize>>) {
+        let calls = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&calls);
+        let mut suite = BenchmarkSuite::with_config(config);
+        suite.add_test("count", move || {
+            counter.set(counter.get() + 1);
+            std::thread::sleep(Duration::from_millis(1));
+            Ok(())
+        });
+        (suite, calls)
+    }
+
+    #[test]
+    fn test_warmup_iterations_and_time() {
+        let (suite, calls) = counting_suite(BenchmarkConfig {
+            warmup_iterations: 3,
+            test_iterations: 2,
+            ..Default::default()
+        });
+        let results = suite.run().unwrap();
+        assert_eq!(results[0].iterations, 2);
+        assert_eq!(calls.get(), 5);
+
+        // 按时长预热时忽略预热次数
+        let (suite, calls) = counting_suite(BenchmarkConfig {
+            warmup_iterations: 0,
+            warmup_time: Some(Duration::from_millis(30)),
+            test_iterations: 2,
+            ..Default::default()
+        });
+        let start = Instant::now();
+        let results = suite.run().unwrap();
+        assert_eq!(results[0].iterations, 2);
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        assert!(calls.get() > 2 + 1, "{}", calls.get());
+    }
+
+    #[test]
+    fn test_csv_report_roundtrip() {
+        let results = vec![
+            BenchmarkResult::from_times("plain", &sample_times()).unwrap(),
+            BenchmarkResult::from_times("a, \"b\"", &sample_times()[..10])
+                .unwrap(),
+        ];
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.csv");
+        BenchmarkSuite::save_results_to_file(
+            &results,
+            path.to_str().unwrap(),
+            BenchmarkOutputFormat::Csv,
+        )
+        .unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+
+        let mut lines = csv.lines();
+        let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(
+            header,
+            vec![
+                "name",
+                "iterations",
+                "mean_ns",
+                "min_ns",
+                "max_ns",
+                "std_dev_ns",
+                "ops_per_sec",
+                "p50_ns",
+                "p95_ns",
+                "p99_ns"
+            ]
+        );
+
+        let rows: Vec<&str> = lines.collect();
+        assert_eq!(rows.len(), 2);
+        let fields: Vec<&str> = rows[0].split(',').collect();
+        assert_eq!(fields.len(), header.len());
+        assert_eq!(fields[0], "plain");
+        assert_eq!(fields[1].parse::<usize>().unwrap(), 100);
+        assert_eq!(fields[2].parse::<u128>().unwrap(), 50_500);
+        assert_eq!(fields[3].parse::<u128>().unwrap(), 1_000);
+        assert_eq!(fields[4].parse::<u128>().unwrap(), 100_000);
+        assert!(fields[6].parse::<f64>().unwrap() > 0.0);
+        assert_eq!(fields[7].parse::<u128>().unwrap(), 50_000);
+        assert_eq!(fields[8].parse::<u128>().unwrap(), 95_000);
+        assert_eq!(fields[9].parse::<u128>().unwrap(), 99_000);
+
+        // 含逗号和引号的名称按 RFC 4180 转义
+        let quoted = rows[1].strip_prefix("\"a, \"\"b\"\"\",").unwrap();
+        assert_eq!(quoted.split(',').count(), header.len() - 1);
+    }
+
+    #[test]
+    fn test_regressions_against_baseline() {
+        let times = |micros: u64| vec![Duration::from_micros(micros)];
+        let baseline = vec![
+            BenchmarkResult::from_times("a", &times(100)).unwrap(),
+            BenchmarkResult::from_times("b", &times(100)).unwrap(),
+        ];
+        let results = vec![
+            BenchmarkResult::from_times("a", &times(104)).unwrap(),
+            BenchmarkResult::from_times("b", &times(150)).unwrap(),
+            BenchmarkResult::from_times("new", &times(999)).unwrap(),
+        ];
+
+        assert!((results[1].ratio_to(&baseline[1]) - 1.5).abs() < 1e-9);
+        let regressions = BenchmarkSuite::regressions(&results, &baseline, 5.0);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].0, "b");
+        assert!((regressions[0].1 - 50.0).abs() < 1e-9);
+        assert_eq!(
+            BenchmarkSuite::regressions(&results, &baseline, 3.0).len(),
+            2
+        );
+        assert!(
+            BenchmarkSuite::regressions(&results, &baseline, 60.0).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_json_includes_percentiles() {
+        let result =
+            BenchmarkResult::from_times("query", &sample_times()).unwrap();
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["p95_time_ns"], 95_000);
+        assert_eq!(json["iterations"], 100);
+
+        let parsed: BenchmarkResult = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.mean_time, result.mean_time);
+        assert_eq!(parsed.p99_time, result.p99_time);
+    }
+}
@@ -12,6 +12,10 @@ pub mod benchmark;
 #[cfg(feature = "profiling")]
 pub mod profiler;
 
+// 基于全局分配器的精确内存统计
+#[cfg(feature = "alloc-profiling")]
+pub mod alloc;
+
 // 未来版本功能模块（预留）
 // #[cfg(feature = "monitoring")]
 // pub mod config;
@@ -79,6 +83,83 @@ impl Default for TimingStats {
     }
 }
 
+/// 查询内存计量器，供 `trace_query!` 统计内存使用
+///
+/// 启用 `profiling` 时读取进程内存变化，否则按数据结构大小尽力估算。
+pub struct MemoryMeter {
+    #[cfg(feature = "profiling")]
+    tracker: profiler::MemoryTracker,
+}
+
+impl MemoryMeter {
+    /// 开始计量
+    pub fn start() -> Self {
+        #[cfg(feature = "profiling")]
+        {
+            let mut tracker = profiler::MemoryTracker::new();
+            tracker.start();
+            Self { tracker }
+        }
+        #[cfg(not(feature = "profiling"))]
+        {
+            Self {}
+        }
+    }
+
+    /// 结束计量，返回 `(memory_used, peak_memory)`（字节）
+    ///
+    /// `parsed` 为解析后的文档，`results` 为查询结果（会被拷贝返回）。
+    pub fn finish(
+        self,
+        parsed: &serde_json::Value,
+        results: &[&serde_json::Value],
+    ) -> (usize, usize) {
+        let estimated = estimate_value_size(parsed)
+            + results
+                .iter()
+                .map(|v| estimate_value_size(v))
+                .sum::<usize>();
+
+        #[cfg(feature = "profiling")]
+        {
+            let mut tracker = self.tracker;
+            let stats = tracker.stop();
+            let used = stats.current_memory.saturating_sub(stats.start_memory);
+            let peak = stats.peak_memory.saturating_sub(stats.start_memory);
+            // 进程内存按页统计，小查询可能没有变化，此时退回估算值
+            if used == 0 && peak == 0 {
+                (estimated, estimated)
+            } else {
+                (used, peak.max(used))
+            }
+        }
+        #[cfg(not(feature = "profiling"))]
+        {
+            (estimated, estimated)
+        }
+    }
+}
+
+/// 估算 JSON 值在内存中占用的字节数
+pub fn estimate_value_size(value: &serde_json::Value) -> usize {
+    use serde_json::Value;
+
+    std::mem::size_of::<Value>()
+        + match value {
+            Value::String(s) => s.capacity(),
+            Value::Array(arr) => arr.iter().map(estimate_value_size).sum(),
+            Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| {
+                    std::mem::size_of::<String>()
+                        + k.capacity()
+                        + estimate_value_size(v)
+                })
+                .sum(),
+            _ => 0,
+        }
+}
+
 /// 调试上下文
 pub struct DebugContext {
     config: DebugConfig,
@@ -7,11 +7,17 @@ use std::collections::HashMap;
 #[cfg(feature = "profiling")]
 use std::time::{Duration, Instant};
 
+#[cfg(feature = "profiling")]
+use serde::{Serialize, Serializer};
+
 /// 性能分析报告
+///
+/// 序列化为 JSON 时执行时间输出为纳秒整数 `execution_time_ns`。
 #[cfg(feature = "profiling")]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ProfileReport {
     /// 执行时间
+    #[serde(rename = "execution_time_ns", serialize_with = "serialize_nanos")]
     pub execution_time: Duration,
     /// 峰值内存使用 (字节)
     pub peak_memory_bytes: usize,
@@ -25,6 +31,14 @@ pub struct ProfileReport {
     pub metrics: HashMap<String, f64>,
 }
 
+#[cfg(feature = "profiling")]
+fn serialize_nanos<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_nanos() as u64)
+}
+
 #[cfg(feature = "profiling")]
 impl Default for ProfileReport {
     fn default() -> Self {
@@ -230,7 +244,7 @@ impl Default for PerformanceMonitor {
 
 /// 内存跟踪器
 #[cfg(feature = "profiling")]
-struct MemoryTracker {
+pub(crate) struct MemoryTracker {
     start_memory: usize,
     peak_memory: usize,
     current_memory: usize,
@@ -238,7 +252,7 @@ struct MemoryTracker {
 
 #[cfg(feature = "profiling")]
 impl MemoryTracker {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             start_memory: 0,
             peak_memory: 0,
@@ -246,15 +260,30 @@ impl MemoryTracker {
         }
     }
 
-    fn start(&mut self) {
+    pub(crate) fn start(&mut self) {
+        #[cfg(feature = "alloc-profiling")]
+        if super::alloc::CountingAllocator::is_installed() {
+            // 使用分配器计数时只统计本次查询自身的分配
+            super::alloc::CountingAllocator::start();
+            self.start_memory = 0;
+            self.current_memory = 0;
+            self.peak_memory = 0;
+            return;
+        }
+
         let current = self.get_memory_usage();
         self.start_memory = current;
         self.current_memory = current;
         self.peak_memory = current;
     }
 
-    fn stop(&mut self) -> MemoryStats {
+    pub(crate) fn stop(&mut self) -> MemoryStats {
         self.current_memory = self.get_memory_usage();
+        #[cfg(feature = "alloc-profiling")]
+        if super::alloc::CountingAllocator::is_installed() {
+            self.peak_memory = super::alloc::CountingAllocator::peak();
+        }
+        self.peak_memory = self.peak_memory.max(self.current_memory);
         MemoryStats {
             start_memory: self.start_memory,
             peak_memory: self.peak_memory,
@@ -272,6 +301,11 @@ impl MemoryTracker {
 
     #[cfg(feature = "profiling")]
     fn get_memory_usage(&self) -> usize {
+        #[cfg(feature = "alloc-profiling")]
+        if super::alloc::CountingAllocator::is_installed() {
+            return super::alloc::CountingAllocator::current();
+        }
+
         // 使用 sysinfo 获取当前进程内存使用
         use sysinfo::{Pid, System};
 
@@ -289,10 +323,10 @@ impl MemoryTracker {
 
 /// 内存统计信息
 #[cfg(feature = "profiling")]
-struct MemoryStats {
-    start_memory: usize,
-    peak_memory: usize,
-    current_memory: usize,
+pub(crate) struct MemoryStats {
+    pub(crate) start_memory: usize,
+    pub(crate) peak_memory: usize,
+    pub(crate) current_memory: usize,
 }
 
 #[cfg(feature = "profiling")]
@@ -488,3 +522,32 @@ impl Default for MemoryProfiler {
         Self::new()
     }
 }
+
+#[cfg(all(test, feature = "profiling"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_report_serialize() {
+        let mut report = ProfileReport {
+            execution_time: Duration::from_micros(1500),
+            peak_memory_bytes: 2048,
+            ..Default::default()
+        };
+        report.add_metric("result_count", 3.0);
+        report.add_hint("use a narrower path");
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "execution_time_ns": 1_500_000,
+                "peak_memory_bytes": 2048,
+                "current_memory_bytes": 0,
+                "cpu_usage_percent": 0.0,
+                "optimization_hints": ["use a narrower path"],
+                "metrics": {"result_count": 3.0}
+            })
+        );
+    }
+}
@@ -35,6 +35,20 @@ mod debug_tests {
         assert_eq!(values.len(), 1);
         assert_eq!(values[0], serde_json::json!("Alice"));
         assert!(stats.duration.as_nanos() > 0);
+        assert!(stats.memory_used > 0);
+        assert!(stats.peak_memory >= stats.memory_used);
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_estimate_value_size() {
+        use crate::debug::estimate_value_size;
+        use serde_json::json;
+
+        let small = estimate_value_size(&json!("a"));
+        let large = estimate_value_size(&json!({"a": ["x".repeat(1000)]}));
+        assert!(small >= std::mem::size_of::<serde_json::Value>());
+        assert!(large > small + 1000);
     }
 
     #[cfg(feature = "debug")]
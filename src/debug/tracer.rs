@@ -1,5 +1,6 @@
 //! 执行路径跟踪器
 
+use crate::parser::path::PathSegment;
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
@@ -20,6 +21,17 @@ pub enum TraceResult {
     Error(String),
 }
 
+/// 单个路径段的执行步骤
+#[derive(Debug, Clone)]
+pub struct TraceStep {
+    pub segment: PathSegment,
+    pub input_count: usize,
+    pub output_count: usize,
+    pub duration: Duration,
+    /// 该段执行失败时的错误信息
+    pub error: Option<String>,
+}
+
 /// 执行路径跟踪器
 pub struct Tracer {
     events: VecDeque<TraceEvent>,
@@ -12,6 +12,18 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::extractor::{extract, ExtractError, Extractor};
+use crate::parser::path::{format_path, parse_path, PathSegment};
+use crate::parser::{
+    evaluate_path_expression, parse_path_expression, ExpressionEvaluator,
+    FunctionRegistry,
+};
+use crate::value::format::{
+    detect_input_format, truncate_str, FormatError, FormatRegistry,
+    ValueFormat, YamlFormat,
+};
+use crate::value::stream::locate_paths;
+
 /// 交互式调试器主结构
 #[derive(Debug)]
 pub struct XQPathDebugger {
@@ -19,6 +31,48 @@ pub struct XQPathDebugger {
     command_history: CommandHistory,
     session: DebugSession,
     query_evaluator: QueryEvaluator,
+    output_format: DebugOutputFormat,
+}
+
+/// 调试器显示结果时使用的格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DebugOutputFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl DebugOutputFormat {
+    /// 按名称解析，支持 `json`、`yaml`、`yml`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    /// 格式名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+        }
+    }
+
+    /// 渲染值；`pretty` 仅影响 JSON，YAML 始终为块格式
+    pub fn render(&self, value: &Value, pretty: bool) -> String {
+        let rendered = match self {
+            Self::Json if pretty => serde_json::to_string_pretty(value)
+                .map_err(|e| FormatError::SerializeError(e.to_string())),
+            Self::Json => serde_json::to_string(value)
+                .map_err(|e| FormatError::SerializeError(e.to_string())),
+            Self::Yaml => YamlFormat.to_string(value),
+        };
+        rendered
+            .map(|text| text.trim_end().to_string())
+            .unwrap_or_else(|_| "Unable to serialize".to_string())
+    }
 }
 
 /// 调试会话，包含断点、监视点等调试状态
@@ -30,6 +84,7 @@ pub struct DebugSession {
     pub variables: VariableScope,
     pub current_data: Option<Value>,
     pub execution_state: ExecutionState,
+    pub execution: Option<StepExecution>,
 }
 
 /// 查询求值器
@@ -37,6 +92,8 @@ pub struct DebugSession {
 pub struct QueryEvaluator {
     pub current_query: Option<String>,
     pub last_result: Option<Value>,
+    /// 最近一次查询的全部结果
+    pub last_results: Vec<Value>,
     pub evaluation_context: EvaluationContext,
 }
 
@@ -141,6 +198,9 @@ pub enum DebugCommand {
     Save {
         file: PathBuf,
     },
+    Export {
+        file: PathBuf,
+    },
     Inspect {
         path: String,
     },
@@ -160,6 +220,9 @@ pub enum DebugCommand {
         id: u32,
     },
     ListWatchPoints,
+    Start {
+        query: String,
+    },
     Continue,
     Step,
     StepInto,
@@ -172,6 +235,9 @@ pub enum DebugCommand {
     },
     ListVariables,
     ShowCallStack,
+    Format {
+        format: Option<DebugOutputFormat>,
+    },
     Reset,
 }
 
@@ -222,6 +288,7 @@ impl XQPathDebugger {
             command_history: CommandHistory::new(),
             session: DebugSession::new(),
             query_evaluator: QueryEvaluator::new(),
+            output_format: DebugOutputFormat::default(),
         }
     }
 
@@ -230,6 +297,75 @@ impl XQPathDebugger {
         println!("🔍 XQPath Interactive Debugger");
         println!("Type ':help' for available commands, ':quit' to exit\n");
 
+        #[cfg(feature = "interactive-debug")]
+        self.run_with_editor()?;
+        #[cfg(not(feature = "interactive-debug"))]
+        self.run_with_stdin();
+
+        println!("Goodbye!");
+        Ok(())
+    }
+
+    /// 使用行编辑器读取输入，支持 Tab 补全路径，历史记录在会话间持久化
+    #[cfg(feature = "interactive-debug")]
+    fn run_with_editor(&mut self) -> DebugResult<()> {
+        use rustyline::error::ReadlineError;
+        use rustyline::history::DefaultHistory;
+        use rustyline::Editor;
+
+        let mut editor = Editor::<DebuggerHelper, DefaultHistory>::new()
+            .map_err(|e| match e {
+                ReadlineError::Io(err) => DebugError::IOError(err),
+                other => DebugError::IOError(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    other.to_string(),
+                )),
+            })?;
+        editor.set_helper(Some(DebuggerHelper::default()));
+
+        let history_path = history_file();
+        if let Some(ref path) = history_path {
+            // 首次运行时历史文件尚不存在，忽略加载失败
+            let _ = editor.load_history(path);
+        }
+
+        loop {
+            // 补全基于最新加载的数据
+            if let Some(helper) = editor.helper_mut() {
+                helper.data.clone_from(&self.session.current_data);
+            }
+
+            match editor.readline("xqpath> ") {
+                Ok(input) => {
+                    let line = input.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    let _ = editor.add_history_entry(line);
+                    if !self.handle_line(line) {
+                        break;
+                    }
+                }
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(err) => {
+                    println!("Input error: {err}, exiting...");
+                    break;
+                }
+            }
+        }
+
+        if let Some(ref path) = history_path {
+            if let Err(err) = editor.save_history(path) {
+                eprintln!("Warning: failed to save history: {err}");
+            }
+        }
+        Ok(())
+    }
+
+    /// 未启用行编辑器时逐行读取标准输入
+    #[cfg(not(feature = "interactive-debug"))]
+    fn run_with_stdin(&mut self) {
         use std::io::{self, Write};
 
         loop {
@@ -238,26 +374,14 @@ impl XQPathDebugger {
 
             let mut input = String::new();
             match io::stdin().read_line(&mut input) {
+                Ok(0) => break,
                 Ok(_) => {
                     let line = input.trim();
                     if line.is_empty() {
                         continue;
                     }
-
-                    // 解析命令并添加到历史
-                    if let Ok(command) = DebugCommand::parse(line) {
-                        self.command_history.add_command(command);
-                    }
-
-                    match self.execute_command(line) {
-                        Ok(should_continue) => {
-                            if !should_continue {
-                                break;
-                            }
-                        }
-                        Err(err) => {
-                            eprintln!("Error: {err}");
-                        }
+                    if !self.handle_line(line) {
+                        break;
                     }
                 }
                 Err(_) => {
@@ -266,9 +390,22 @@ impl XQPathDebugger {
                 }
             }
         }
+    }
 
-        println!("Goodbye!");
-        Ok(())
+    /// 处理一行输入，返回是否继续会话
+    fn handle_line(&mut self, line: &str) -> bool {
+        // 解析命令并添加到历史
+        if let Ok(command) = DebugCommand::parse(line) {
+            self.command_history.add_command(command);
+        }
+
+        match self.execute_command(line) {
+            Ok(should_continue) => should_continue,
+            Err(err) => {
+                eprintln!("Error: {err}");
+                true
+            }
+        }
     }
 
     /// 执行调试命令
@@ -284,6 +421,7 @@ impl XQPathDebugger {
             DebugCommand::Quit => Ok(false),
             DebugCommand::Load { file } => self.load_data_file(file),
             DebugCommand::Save { file } => self.save_data_file(file),
+            DebugCommand::Export { file } => self.export_result(file),
             DebugCommand::Inspect { path } => self.inspect_path(path),
             DebugCommand::Run { query } => self.run_query(query),
             DebugCommand::Evaluate { expression } => {
@@ -303,59 +441,63 @@ impl XQPathDebugger {
             DebugCommand::ListVariables => self.list_variables(),
             DebugCommand::ShowCallStack => self.show_call_stack(),
             DebugCommand::Reset => self.reset_session(),
-            _ => {
-                println!("⚠️  Command not yet implemented");
+            DebugCommand::Format { format } => {
+                match format {
+                    Some(format) => {
+                        self.output_format = format;
+                        println!("✅ Output format set to {}", format.name());
+                    }
+                    None => {
+                        println!(
+                            "📋 Output format: {}",
+                            self.output_format.name()
+                        );
+                    }
+                }
                 Ok(true)
             }
+            DebugCommand::Start { query } => {
+                if self.start_execution(query)? {
+                    self.show_execution_state();
+                }
+                Ok(true)
+            }
+            DebugCommand::Continue => self.resume_execution(true),
+            DebugCommand::Step | DebugCommand::StepInto => {
+                self.step_execution()
+            }
+            DebugCommand::StepOut => self.resume_execution(false),
         }
     }
 
-    /// 加载数据文件
+    /// 加载数据文件，格式由 [`detect_format`] 检测
     fn load_data_file(&mut self, file: PathBuf) -> DebugResult<bool> {
-        match fs::read_to_string(&file) {
-            Ok(content) => {
-                match serde_json::from_str::<Value>(&content) {
-                    Ok(data) => {
-                        self.session.current_data = Some(data.clone());
-                        self.data_inspector.inspect_target = Some(data);
-                        println!("✅ Successfully loaded: {:?}", file);
-                        println!(
-                            "📊 Data type: {}",
-                            self.get_data_type(&self.session.current_data)
-                        );
-                        Ok(true)
-                    }
-                    Err(e) => {
-                        // 尝试YAML解析
-                        match serde_yaml::from_str::<Value>(&content) {
-                            Ok(data) => {
-                                self.session.current_data = Some(data.clone());
-                                self.data_inspector.inspect_target = Some(data);
-                                println!(
-                                    "✅ Successfully loaded YAML: {:?}",
-                                    file
-                                );
-                                println!(
-                                    "📊 Data type: {}",
-                                    self.get_data_type(
-                                        &self.session.current_data
-                                    )
-                                );
-                                Ok(true)
-                            }
-                            Err(_) => {
-                                println!("❌ Failed to parse file as JSON or YAML: {}", e);
-                                Ok(true)
-                            }
-                        }
-                    }
-                }
-            }
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
             Err(e) => {
                 println!("❌ Failed to read file {:?}: {}", file, e);
-                Ok(true)
+                return Ok(true);
+            }
+        };
+
+        let parsed = detect_input_format(&content).and_then(|format| {
+            format.parse(&content).map(|data| (format.name(), data))
+        });
+        match parsed {
+            Ok((format_name, data)) => {
+                self.session.current_data = Some(data.clone());
+                self.data_inspector.inspect_target = Some(data);
+                println!("✅ Successfully loaded {}: {:?}", format_name, file);
+                println!(
+                    "📊 Data type: {}",
+                    self.get_data_type(&self.session.current_data)
+                );
+            }
+            Err(e) => {
+                println!("❌ Failed to parse file {:?}: {}", file, e);
             }
         }
+        Ok(true)
     }
 
     /// 保存数据文件
@@ -376,6 +518,44 @@ impl XQPathDebugger {
         Ok(true)
     }
 
+    /// 导出最近一次查询的结果，格式由文件扩展名决定（默认 JSON）
+    ///
+    /// 单个结果直接写出，多个结果写为数组。
+    fn export_result(&mut self, file: PathBuf) -> DebugResult<bool> {
+        let results = &self.query_evaluator.last_results;
+        if results.is_empty() {
+            println!("❌ No query result to export. Run a query first.");
+            return Ok(true);
+        }
+
+        let registry = FormatRegistry::new();
+        let extension = file.extension().and_then(|ext| ext.to_str());
+        let Some(format) = registry.get(extension.unwrap_or("json")) else {
+            println!(
+                "❌ Unsupported export format: {}",
+                extension.unwrap_or_default()
+            );
+            return Ok(true);
+        };
+
+        let value = match results.as_slice() {
+            [single] => single.clone(),
+            _ => Value::Array(results.clone()),
+        };
+        let content = format
+            .to_string(&value)
+            .map_err(|e| DebugError::EvaluationError(e.to_string()))?;
+
+        fs::write(&file, content)?;
+        println!(
+            "✅ Exported {} result(s) as {} to: {:?}",
+            results.len(),
+            format.name(),
+            file
+        );
+        Ok(true)
+    }
+
     /// 检查路径
     fn inspect_path(&mut self, path: String) -> DebugResult<bool> {
         if let Some(ref data) = self.session.current_data {
@@ -391,12 +571,9 @@ impl XQPathDebugger {
                                 "📊 Type: {}",
                                 self.get_value_type(&value)
                             );
-                            println!(
-                                "📋 Value: {}",
-                                serde_json::to_string_pretty(&value)
-                                    .unwrap_or_else(
-                                        |_| "Unable to serialize".to_string()
-                                    )
+                            print_rendered(
+                                "📋 Value:",
+                                &self.output_format.render(&value, true),
                             );
 
                             // 更新检查器状态
@@ -420,59 +597,235 @@ impl XQPathDebugger {
         Ok(true)
     }
 
-    /// 运行查询
+    /// 运行查询，在命中断点时暂停
     fn run_query(&mut self, query_str: String) -> DebugResult<bool> {
-        if let Some(ref data) = self.session.current_data {
-            let data_str = serde_json::to_string(data)
-                .map_err(|e| DebugError::ParseError(e.to_string()))?;
+        if self.start_execution(query_str)? {
+            self.resume_execution(true)?;
+        }
+        Ok(true)
+    }
 
-            let start_time = std::time::Instant::now();
+    /// 开始逐段执行查询，停在第一个路径段之前
+    ///
+    /// 未加载数据或查询无法解析时返回 false。
+    fn start_execution(&mut self, query_str: String) -> DebugResult<bool> {
+        let Some(ref data) = self.session.current_data else {
+            println!(
+                "❌ No data loaded. Use ':load <file>' to load data first."
+            );
+            return Ok(false);
+        };
 
-            match query!(&data_str, &query_str) {
-                Ok(results) => {
-                    let duration = start_time.elapsed();
+        let execution = match StepExecution::new(&query_str, data) {
+            Ok(execution) => execution,
+            Err(e) => {
+                println!("❌ Query error: {}", e);
+                return Ok(false);
+            }
+        };
 
-                    println!("✅ Query executed successfully");
-                    println!("⏱️  Execution time: {:?}", duration);
-                    println!("📊 Results: {} value(s) found", results.len());
+        self.session.call_stack = CallStack::new();
+        self.session.call_stack.frames.push(StackFrame {
+            function_name: "query".to_string(),
+            query: query_str,
+            variables: HashMap::new(),
+            line: 0,
+        });
+        self.session.execution = Some(execution);
+        self.session.execution_state = ExecutionState::Paused;
+        Ok(true)
+    }
 
-                    for (i, result) in results.iter().enumerate() {
-                        if i < 10 {
-                            // 限制显示前10个结果
-                            println!(
-                                "  [{}] {}: {}",
-                                i + 1,
-                                self.get_value_type(result),
-                                serde_json::to_string(result).unwrap_or_else(
-                                    |_| "Unable to serialize".to_string()
-                                )
-                            );
-                        }
-                    }
+    /// 单步执行一个路径段并显示中间结果
+    fn step_execution(&mut self) -> DebugResult<bool> {
+        if self.session.execution.is_none() {
+            println!("❌ No active execution. Use ':start <query>' first.");
+            return Ok(true);
+        }
 
-                    if results.len() > 10 {
-                        println!(
-                            "  ... and {} more results",
-                            results.len() - 10
-                        );
-                    }
+        self.session.execution_state = ExecutionState::Stepping;
+        if self.advance()? {
+            self.show_execution_state();
+            self.report_breakpoints();
+            self.session.execution_state = ExecutionState::Paused;
+        }
+        Ok(true)
+    }
 
-                    // 更新查询历史
-                    self.query_evaluator.current_query = Some(query_str);
-                    self.query_evaluator.last_result = results.first().cloned();
-                }
-                Err(e) => {
-                    println!("❌ Query error: {}", e);
-                }
+    /// 继续执行直到命中断点或查询结束
+    ///
+    /// `stop_at_breakpoints` 为 false 时（`:step-out`）忽略断点直接执行完毕。
+    fn resume_execution(
+        &mut self,
+        stop_at_breakpoints: bool,
+    ) -> DebugResult<bool> {
+        if self.session.execution.is_none() {
+            println!("❌ No active execution. Use ':start <query>' first.");
+            return Ok(true);
+        }
+
+        self.session.execution_state = ExecutionState::Running;
+        while self.advance()? {
+            if stop_at_breakpoints && self.report_breakpoints() {
+                self.show_execution_state();
+                self.session.execution_state = ExecutionState::Paused;
+                break;
             }
-        } else {
-            println!(
-                "❌ No data loaded. Use ':load <file>' to load data first."
-            );
         }
         Ok(true)
     }
 
+    /// 执行下一个路径段并检查监视点，执行完毕时输出结果并返回 false
+    fn advance(&mut self) -> DebugResult<bool> {
+        let Some(execution) = self.session.execution.as_mut() else {
+            return Ok(false);
+        };
+
+        if let Err(e) = execution.step() {
+            println!("❌ Query error: {}", e);
+            self.stop_execution();
+            return Ok(false);
+        }
+
+        if let Some(frame) = self.session.call_stack.frames.last_mut() {
+            frame.line = execution.position as u32;
+        }
+        self.session.variables.current =
+            Some(Value::Array(execution.results()));
+        self.report_watchpoints();
+
+        if self
+            .session
+            .execution
+            .as_ref()
+            .is_some_and(|e| e.is_finished())
+        {
+            self.finish_execution();
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// 查询执行完毕：输出结果并记录到查询历史
+    fn finish_execution(&mut self) {
+        let Some(execution) = self.session.execution.take() else {
+            return;
+        };
+        let results = execution.results();
+
+        println!("✅ Query executed successfully");
+        println!("⏱️  Execution time: {:?}", execution.elapsed);
+        println!("📊 Results: {} value(s) found", results.len());
+
+        for (i, result) in results.iter().enumerate() {
+            if i < 10 {
+                // 限制显示前10个结果
+                print_rendered(
+                    &format!("  [{}] {}:", i + 1, self.get_value_type(result)),
+                    &self.output_format.render(result, false),
+                );
+            }
+        }
+
+        if results.len() > 10 {
+            println!("  ... and {} more results", results.len() - 10);
+        }
+
+        // 更新查询历史
+        self.query_evaluator.current_query = Some(execution.query);
+        self.query_evaluator.last_result = results.first().cloned();
+        self.query_evaluator.last_results = results;
+        self.stop_execution();
+    }
+
+    /// 结束当前执行并清理调用栈
+    fn stop_execution(&mut self) {
+        self.session.execution = None;
+        self.session.call_stack = CallStack::new();
+        self.session.execution_state = ExecutionState::Stopped;
+    }
+
+    /// 显示当前执行位置及中间结果
+    fn show_execution_state(&self) {
+        let Some(ref execution) = self.session.execution else {
+            return;
+        };
+
+        println!(
+            "➡️  Step {}/{}: {}",
+            execution.position,
+            execution.segments.len(),
+            execution.executed_path()
+        );
+        for (i, (path, value)) in execution.values.iter().enumerate() {
+            if i < 10 {
+                let rendered = self.output_format.render(value, false);
+                let preview = if rendered.contains('\n') {
+                    rendered
+                } else {
+                    truncate_str(&rendered, STEP_PREVIEW_CHARS)
+                };
+                print_rendered(&format!("  {} =", format_path(path)), &preview);
+            }
+        }
+        if execution.values.len() > 10 {
+            println!("  ... and {} more values", execution.values.len() - 10);
+        }
+    }
+
+    /// 检查当前位置命中的断点，有命中时输出并返回 true
+    fn report_breakpoints(&mut self) -> bool {
+        let Some(ref mut execution) = self.session.execution else {
+            return false;
+        };
+
+        execution.hits = breakpoint_hits(&self.session.breakpoints, execution);
+        for (id, path) in &execution.hits {
+            println!("🔴 Breakpoint {} hit at {}", id, path);
+        }
+        !execution.hits.is_empty()
+    }
+
+    /// 在当前中间结果上重新计算监视点，输出发生变化的值
+    fn report_watchpoints(&mut self) {
+        let Some(ref mut execution) = self.session.execution else {
+            return;
+        };
+
+        for watchpoint in &self.session.watch_points {
+            if !watchpoint.enabled {
+                continue;
+            }
+            let Ok(expression) = parse_path_expression(&watchpoint.expression)
+            else {
+                continue;
+            };
+
+            let watched: Vec<Value> = execution
+                .values
+                .iter()
+                .flat_map(|(_, value)| {
+                    evaluate_path_expression(&expression, value)
+                        .unwrap_or_default()
+                })
+                .collect();
+            let watched = Value::Array(watched);
+
+            let previous = execution
+                .watch_values
+                .insert(watchpoint.id, watched.clone());
+            if previous.as_ref() != Some(&watched) {
+                let old = previous
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "(unset)".to_string());
+                println!(
+                    "👁️  Watchpoint {} ({}): {} -> {}",
+                    watchpoint.id, watchpoint.expression, old, watched
+                );
+            }
+        }
+    }
+
     /// 评估表达式
     fn evaluate_expression(&mut self, expression: String) -> DebugResult<bool> {
         // 对于简单实现，我们将表达式作为查询处理
@@ -672,6 +1025,7 @@ impl XQPathDebugger {
         println!("📂 Data Management:");
         println!("  :load <file>             - Load data from JSON/YAML file");
         println!("  :save <file>             - Save current data to file");
+        println!("  :export <file>           - Export the last query result");
         println!();
         println!("🔍 Query & Inspection:");
         println!("  :inspect <path>          - Inspect data at specific path");
@@ -688,17 +1042,34 @@ impl XQPathDebugger {
         println!("  :watch-rm <id>           - Remove watchpoint by ID");
         println!("  :watch-list              - List all watchpoints");
         println!();
+        println!("⏯️  Execution:");
+        println!("  :start <query>           - Start stepping through a query");
+        println!("  :step                    - Execute the next path segment");
+        println!("  :step-into               - Same as :step");
+        println!(
+            "  :step-out                - Run to the end, ignoring breakpoints"
+        );
+        println!("  :continue                - Run until the next breakpoint");
+        println!();
         println!("📊 Debug Info:");
         println!("  :vars                    - List current variables");
         println!("  :stack                   - Show call stack");
         println!("  :reset                   - Reset debugging session");
         println!();
+        println!("🧩 Functions:");
+        let registry = FunctionRegistry::new();
+        for (name, description) in registry.list_functions() {
+            println!("  {:<24} - {}", name, description);
+        }
+        println!();
         println!("🛠️  General:");
+        println!("  :format [json|yaml]      - Show or set the result format");
         println!("  :help                    - Show this help message");
         println!("  :quit                    - Exit the debugger");
         println!();
         println!("💡 Tip: You can also run queries directly without ':run'");
         println!("    Example: .users[*].name");
+        println!("💡 Tip: Press Tab to complete commands and paths");
     }
 }
 
@@ -712,6 +1083,7 @@ impl DebugSession {
             variables: VariableScope::new(),
             current_data: None,
             execution_state: ExecutionState::Stopped,
+            execution: None,
         }
     }
 }
@@ -743,6 +1115,7 @@ impl QueryEvaluator {
         Self {
             current_query: None,
             last_result: None,
+            last_results: Vec::new(),
             evaluation_context: EvaluationContext::new(),
         }
     }
@@ -811,6 +1184,17 @@ impl DebugCommand {
                         ))
                     }
                 }
+                Some(&"export") => {
+                    if let Some(&file) = parts.get(1) {
+                        Ok(DebugCommand::Export {
+                            file: PathBuf::from(file),
+                        })
+                    } else {
+                        Err(DebugError::InvalidCommand(
+                            "export command requires a file path".to_string(),
+                        ))
+                    }
+                }
                 Some(&"inspect") | Some(&"i") => {
                     if let Some(&path) = parts.get(1) {
                         Ok(DebugCommand::Inspect {
@@ -911,6 +1295,34 @@ impl DebugCommand {
                 Some(&"vars") | Some(&"v") => Ok(DebugCommand::ListVariables),
                 Some(&"stack") => Ok(DebugCommand::ShowCallStack),
                 Some(&"reset") => Ok(DebugCommand::Reset),
+                Some(&"format") | Some(&"f") => match parts.get(1) {
+                    Some(name) => DebugOutputFormat::from_name(name)
+                        .map(|format| DebugCommand::Format {
+                            format: Some(format),
+                        })
+                        .ok_or_else(|| {
+                            DebugError::InvalidCommand(format!(
+                                "Unknown output format: {} (expected json or yaml)",
+                                name
+                            ))
+                        }),
+                    None => Ok(DebugCommand::Format { format: None }),
+                },
+                Some(&"start") => {
+                    if parts.len() > 1 {
+                        Ok(DebugCommand::Start {
+                            query: parts[1..].join(" "),
+                        })
+                    } else {
+                        Err(DebugError::InvalidCommand(
+                            "start command requires a query".to_string(),
+                        ))
+                    }
+                }
+                Some(&"continue") | Some(&"c") => Ok(DebugCommand::Continue),
+                Some(&"step") | Some(&"n") => Ok(DebugCommand::Step),
+                Some(&"step-into") | Some(&"si") => Ok(DebugCommand::StepInto),
+                Some(&"step-out") | Some(&"so") => Ok(DebugCommand::StepOut),
                 Some(cmd) => Err(DebugError::InvalidCommand(format!(
                     "Unknown command: {}",
                     cmd
@@ -935,6 +1347,349 @@ impl Default for XQPathDebugger {
     }
 }
 
+/// 单步执行时每个中间结果预览的最大字符数
+const STEP_PREVIEW_CHARS: usize = 120;
+
+/// 输出带标签的渲染结果，多行内容另起一行缩进显示
+fn print_rendered(label: &str, rendered: &str) {
+    if rendered.contains('\n') {
+        println!("{}", label);
+        for line in rendered.lines() {
+            println!("      {}", line);
+        }
+    } else {
+        println!("{} {}", label, rendered);
+    }
+}
+
+/// 逐段执行中的查询，每个路径段是一个执行步骤
+#[derive(Debug, Clone)]
+pub struct StepExecution {
+    pub query: String,
+    pub segments: Vec<PathSegment>,
+    /// 已执行的路径段数量
+    pub position: usize,
+    /// 当前中间结果及其具体路径
+    pub values: Vec<(Vec<PathSegment>, Value)>,
+    /// 最近一次检查命中的断点：(断点编号, 具体路径)
+    pub hits: Vec<(u32, String)>,
+    /// 各步骤累计耗时
+    pub elapsed: std::time::Duration,
+    watch_values: HashMap<u32, Value>,
+    after_recursive: bool,
+}
+
+impl StepExecution {
+    /// 解析查询，以根节点作为初始中间结果
+    pub fn new(query: &str, data: &Value) -> DebugResult<Self> {
+        let segments = parse_path(query)
+            .map_err(|e| DebugError::ParseError(e.to_string()))?;
+        Ok(Self {
+            query: query.to_string(),
+            segments,
+            position: 0,
+            values: vec![(Vec::new(), data.clone())],
+            hits: Vec::new(),
+            elapsed: std::time::Duration::ZERO,
+            watch_values: HashMap::new(),
+            after_recursive: false,
+        })
+    }
+
+    /// 是否已执行完所有路径段
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.segments.len()
+    }
+
+    /// 执行下一个路径段，已执行完毕时返回 false
+    ///
+    /// 与 [`extract`] 语义一致：递归通配符之后跳过类型不匹配的节点。
+    pub fn step(&mut self) -> DebugResult<bool> {
+        let Some(segment) = self.segments.get(self.position) else {
+            return Ok(false);
+        };
+        let start = std::time::Instant::now();
+
+        let mut next = Vec::new();
+        for (path, value) in &self.values {
+            let segment = std::slice::from_ref(segment);
+            let results = match Extractor::extract(value, segment) {
+                Ok(results) => results,
+                Err(
+                    ExtractError::TypeMismatch(_, _)
+                    | ExtractError::IndexOutOfBounds(_, _),
+                ) if self.after_recursive => continue,
+                Err(e) => {
+                    return Err(DebugError::EvaluationError(e.to_string()))
+                }
+            };
+
+            // 通过地址在子树中定位结果，得到其具体路径
+            let suffixes: HashMap<*const Value, Vec<PathSegment>> =
+                locate_paths(value, &results)
+                    .into_iter()
+                    .map(|(suffix, node)| (node as *const Value, suffix))
+                    .collect();
+            for result in results {
+                let suffix = suffixes
+                    .get(&(result as *const Value))
+                    .cloned()
+                    .unwrap_or_default();
+                let mut full_path = path.clone();
+                full_path.extend(suffix);
+                next.push((full_path, result.clone()));
+            }
+        }
+
+        self.after_recursive |= *segment == PathSegment::RecursiveWildcard;
+        self.values = next;
+        self.position += 1;
+        self.elapsed += start.elapsed();
+        Ok(true)
+    }
+
+    /// 已执行部分对应的路径
+    pub fn executed_path(&self) -> String {
+        format_path(&self.segments[..self.position])
+    }
+
+    /// 当前中间结果
+    pub fn results(&self) -> Vec<Value> {
+        self.values.iter().map(|(_, value)| value.clone()).collect()
+    }
+}
+
+/// 计算当前执行位置命中的断点
+///
+/// 断点路径与已执行的路径相同，或按具体路径匹配某个中间结果（`[*]` 匹配
+/// 任意键或索引）时命中；带条件的断点只对条件为真的中间结果命中。
+fn breakpoint_hits(
+    breakpoints: &[Breakpoint],
+    execution: &StepExecution,
+) -> Vec<(u32, String)> {
+    let executed = &execution.segments[..execution.position];
+    let after_filter =
+        matches!(executed.last(), Some(PathSegment::TypeFilter(_)));
+
+    let evaluator = ExpressionEvaluator::new();
+    let mut hits = Vec::new();
+    for breakpoint in breakpoints.iter().filter(|bp| bp.enabled) {
+        let Ok(pattern) = parse_path(&breakpoint.path) else {
+            continue;
+        };
+        let condition = match breakpoint.condition {
+            Some(ref condition) => match parse_path_expression(condition) {
+                Ok(expression) => Some(expression),
+                Err(_) => continue,
+            },
+            None => None,
+        };
+
+        for (path, value) in &execution.values {
+            // 类型过滤不改变具体路径，避免同一位置重复命中
+            let matched = pattern == executed
+                || (!after_filter && path_matches(&pattern, path));
+            // 条件在中间结果上求值，任一结果为真即暂停
+            let satisfied = condition.as_ref().is_none_or(|condition| {
+                evaluator.evaluate(condition, value).is_ok_and(|results| {
+                    results.iter().any(|r| evaluator.is_truthy(r))
+                })
+            });
+            if matched && satisfied {
+                hits.push((breakpoint.id, format_path(path)));
+            }
+        }
+    }
+    hits
+}
+
+/// 判断具体路径是否匹配断点路径模式
+fn path_matches(pattern: &[PathSegment], path: &[PathSegment]) -> bool {
+    fn segment_matches(pattern: &PathSegment, segment: &PathSegment) -> bool {
+        match pattern {
+            PathSegment::Wildcard => {
+                matches!(segment, PathSegment::Field(_) | PathSegment::Index(_))
+            }
+            PathSegment::Optional(inner) => segment_matches(inner, segment),
+            _ => pattern == segment,
+        }
+    }
+
+    pattern.len() == path.len()
+        && pattern
+            .iter()
+            .zip(path)
+            .all(|(pattern, segment)| segment_matches(pattern, segment))
+}
+
+/// 可补全的调试命令名
+const COMMAND_NAMES: &[&str] = &[
+    "help",
+    "quit",
+    "exit",
+    "load",
+    "save",
+    "export",
+    "inspect",
+    "run",
+    "eval",
+    "bp",
+    "bp-rm",
+    "bp-list",
+    "watch",
+    "watch-rm",
+    "watch-list",
+    "vars",
+    "stack",
+    "reset",
+    "format",
+    "start",
+    "continue",
+    "step",
+    "step-into",
+    "step-out",
+];
+
+/// 补全一行输入中光标前的单词
+///
+/// 行首的 `:xxx` 补全为命令名，其余以 `.` 开头的路径按已加载的文档
+/// 补全下一级键或索引。返回替换起始位置与候选列表。
+pub fn complete_input(
+    data: Option<&Value>,
+    line: &str,
+    pos: usize,
+) -> (usize, Vec<String>) {
+    let before = line.get(..pos).unwrap_or(line);
+
+    if let Some(name) = before.strip_prefix(':') {
+        if !name.contains(char::is_whitespace) {
+            let candidates = COMMAND_NAMES
+                .iter()
+                .filter(|command| command.starts_with(name))
+                .map(|command| format!(":{command}"))
+                .collect();
+            return (0, candidates);
+        }
+    }
+
+    let start = before
+        .char_indices()
+        .rev()
+        .find(|(_, c)| !is_path_char(*c))
+        .map_or(0, |(i, c)| i + c.len_utf8());
+
+    match data {
+        Some(data) => (start, complete_path(data, &before[start..])),
+        None => (start, Vec::new()),
+    }
+}
+
+/// 根据文档补全部分路径，返回完整的候选路径
+///
+/// 最后一个 `.` 之后的部分按对象键前缀匹配，最后一个 `[` 之后的部分按数组
+/// 索引前缀匹配；前缀路径命中多个值（如通配符）时合并各自的候选。
+///
+/// ```rust
+/// use serde_json::json;
+/// use xqpath::debugger::complete_path;
+///
+/// let data = json!({"users": [{"name": "Alice", "age": 30}]});
+/// assert_eq!(complete_path(&data, ".us"), vec![".users"]);
+/// assert_eq!(complete_path(&data, ".users[0].n"), vec![".users[0].name"]);
+/// ```
+pub fn complete_path(data: &Value, partial: &str) -> Vec<String> {
+    if !partial.starts_with('.') {
+        return Vec::new();
+    }
+
+    let Some(split) = partial.rfind(['.', '[']) else {
+        return Vec::new();
+    };
+    let (prefix, fragment) = (&partial[..split], &partial[split + 1..]);
+
+    let parents = if prefix.is_empty() {
+        vec![data]
+    } else {
+        match parse_path(prefix).map(|segments| extract(data, &segments)) {
+            Ok(Ok(values)) => values,
+            _ => return Vec::new(),
+        }
+    };
+
+    let mut candidates: Vec<String> = Vec::new();
+    for parent in parents {
+        let next: Vec<String> = match (&partial[split..split + 1], parent) {
+            (".", Value::Object(map)) => map
+                .keys()
+                .filter(|key| key.starts_with(fragment))
+                .map(|key| format!("{prefix}.{key}"))
+                .collect(),
+            ("[", Value::Array(items)) => (0..items.len())
+                .map(|i| i.to_string())
+                .filter(|index| index.starts_with(fragment))
+                .map(|index| format!("{prefix}[{index}]"))
+                .collect(),
+            _ => Vec::new(),
+        };
+        for candidate in next {
+            if !candidates.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        }
+    }
+    candidates
+}
+
+fn is_path_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '[' | ']' | '*')
+}
+
+/// 历史记录文件，可通过 `XQPATH_HISTORY` 环境变量覆盖，默认为 `~/.xqpath_history`
+#[cfg(feature = "interactive-debug")]
+fn history_file() -> Option<PathBuf> {
+    std::env::var_os("XQPATH_HISTORY")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join(".xqpath_history"))
+        })
+}
+
+/// 行编辑器辅助：基于当前加载的数据补全路径和命令
+#[cfg(feature = "interactive-debug")]
+#[derive(Debug, Default)]
+struct DebuggerHelper {
+    data: Option<Value>,
+}
+
+#[cfg(feature = "interactive-debug")]
+impl rustyline::completion::Completer for DebuggerHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(complete_input(self.data.as_ref(), line, pos))
+    }
+}
+
+#[cfg(feature = "interactive-debug")]
+impl rustyline::hint::Hinter for DebuggerHelper {
+    type Hint = String;
+}
+
+#[cfg(feature = "interactive-debug")]
+impl rustyline::highlight::Highlighter for DebuggerHelper {}
+
+#[cfg(feature = "interactive-debug")]
+impl rustyline::validate::Validator for DebuggerHelper {}
+
+#[cfg(feature = "interactive-debug")]
+impl rustyline::Helper for DebuggerHelper {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -971,4 +1726,249 @@ mod tests {
             _ => panic!("Expected Load command"),
         }
     }
+
+    #[test]
+    fn test_complete_path() {
+        let data = serde_json::json!({
+            "users": [
+                {"name": "Alice", "nickname": "Al"},
+                {"name": "Bob", "age": 25}
+            ],
+            "version": 1
+        });
+
+        assert_eq!(complete_path(&data, "."), vec![".users", ".version"]);
+        assert_eq!(complete_path(&data, ".u"), vec![".users"]);
+        assert_eq!(
+            complete_path(&data, ".users["),
+            vec![".users[0]", ".users[1]"]
+        );
+        assert_eq!(
+            complete_path(&data, ".users[0].n"),
+            vec![".users[0].name", ".users[0].nickname"]
+        );
+        assert_eq!(
+            complete_path(&data, ".users[*]."),
+            vec![".users[*].name", ".users[*].nickname", ".users[*].age"]
+        );
+        assert!(complete_path(&data, ".version.").is_empty());
+        assert!(complete_path(&data, ".missing.a").is_empty());
+        assert!(complete_path(&data, "users").is_empty());
+    }
+
+    #[test]
+    fn test_complete_input() {
+        let data = serde_json::json!({"users": [{"name": "Alice"}]});
+
+        let line = ":inspect .users[0].na";
+        assert_eq!(
+            complete_input(Some(&data), line, line.len()),
+            (9, vec![".users[0].name".to_string()])
+        );
+
+        let line = "select(.us";
+        assert_eq!(
+            complete_input(Some(&data), line, line.len()),
+            (7, vec![".users".to_string()])
+        );
+
+        let (start, commands) = complete_input(None, ":bp", 3);
+        assert_eq!(start, 0);
+        assert_eq!(commands, vec![":bp", ":bp-rm", ":bp-list"]);
+
+        assert_eq!(complete_input(None, ".users", 6), (0, Vec::new()));
+    }
+
+    #[test]
+    fn test_step_execution() {
+        let data = serde_json::json!({
+            "users": [{"name": "Alice"}, {"name": "Bob"}]
+        });
+        let mut execution =
+            StepExecution::new(".users[*].name", &data).unwrap();
+        assert_eq!(execution.segments.len(), 3);
+        assert_eq!(execution.values.len(), 1);
+
+        assert!(execution.step().unwrap());
+        assert_eq!(execution.executed_path(), ".users");
+        assert!(execution.step().unwrap());
+        let paths: Vec<String> = execution
+            .values
+            .iter()
+            .map(|(path, _)| format_path(path))
+            .collect();
+        assert_eq!(paths, vec![".users[0]", ".users[1]"]);
+
+        assert!(execution.step().unwrap());
+        assert!(execution.is_finished());
+        assert_eq!(
+            execution.results(),
+            vec![serde_json::json!("Alice"), serde_json::json!("Bob")]
+        );
+        assert!(!execution.step().unwrap());
+    }
+
+    #[test]
+    fn test_breakpoint_pauses_execution() {
+        let mut debugger = XQPathDebugger::new();
+        debugger.session.current_data = Some(serde_json::json!({
+            "users": [{"name": "Alice"}, {"name": "Bob"}]
+        }));
+
+        debugger.execute_command(":bp .users[1]").unwrap();
+        debugger.execute_command(":run .users[*].name").unwrap();
+        let execution = debugger.session.execution.as_ref().unwrap();
+        assert_eq!(execution.position, 2);
+        assert_eq!(execution.hits, vec![(1, ".users[1]".to_string())]);
+
+        debugger.execute_command(":continue").unwrap();
+        assert!(debugger.session.execution.is_none());
+        assert_eq!(
+            debugger.query_evaluator.last_result,
+            Some(serde_json::json!("Alice"))
+        );
+
+        debugger.execute_command(":start .users[0].name").unwrap();
+        debugger.execute_command(":step").unwrap();
+        assert_eq!(debugger.session.execution.as_ref().unwrap().position, 1);
+        debugger.execute_command(":step-out").unwrap();
+        assert!(debugger.session.execution.is_none());
+    }
+
+    #[test]
+    fn test_conditional_breakpoint() {
+        let mut debugger = XQPathDebugger::new();
+        debugger.session.current_data = Some(serde_json::json!({
+            "users": [
+                {"name": "Alice", "active": false},
+                {"name": "Bob", "active": true},
+                {"name": "Carol", "active": true}
+            ]
+        }));
+
+        debugger
+            .execute_command(":bp .users[*] .active == true")
+            .unwrap();
+        assert_eq!(
+            debugger.session.breakpoints[0].condition.as_deref(),
+            Some(".active == true")
+        );
+
+        debugger.execute_command(":run .users[*].name").unwrap();
+        let execution = debugger.session.execution.as_ref().unwrap();
+        assert_eq!(
+            execution.hits,
+            vec![(1, ".users[1]".to_string()), (1, ".users[2]".to_string())]
+        );
+
+        debugger.execute_command(":reset").unwrap();
+        debugger.session.current_data =
+            Some(serde_json::json!({"users": [{"active": false}]}));
+        debugger
+            .execute_command(":bp .users[*] .active == true")
+            .unwrap();
+        debugger.execute_command(":run .users[*].active").unwrap();
+        assert!(debugger.session.execution.is_none());
+    }
+
+    #[test]
+    fn test_load_detects_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let yaml_file = dir.path().join("config.txt");
+        fs::write(&yaml_file, "server:\n  port: 8080\n").unwrap();
+
+        let mut debugger = XQPathDebugger::new();
+        debugger
+            .execute_command(&format!(":load {}", yaml_file.display()))
+            .unwrap();
+        assert_eq!(
+            debugger.session.current_data,
+            Some(serde_json::json!({"server": {"port": 8080}}))
+        );
+
+        let empty_file = dir.path().join("empty.json");
+        fs::write(&empty_file, "").unwrap();
+        debugger
+            .execute_command(&format!(":load {}", empty_file.display()))
+            .unwrap();
+        assert!(debugger.session.current_data.is_some());
+    }
+
+    #[test]
+    fn test_export_last_result() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut debugger = XQPathDebugger::new();
+
+        let missing = dir.path().join("missing.json");
+        debugger
+            .execute_command(&format!(":export {}", missing.display()))
+            .unwrap();
+        assert!(!missing.exists());
+
+        debugger.session.current_data = Some(serde_json::json!({
+            "users": [{"name": "Alice"}, {"name": "Bob"}]
+        }));
+        debugger.execute_command(".users[*].name").unwrap();
+
+        let json_file = dir.path().join("names.json");
+        debugger
+            .execute_command(&format!(":export {}", json_file.display()))
+            .unwrap();
+        let exported: Value =
+            serde_json::from_str(&fs::read_to_string(&json_file).unwrap())
+                .unwrap();
+        assert_eq!(exported, serde_json::json!(["Alice", "Bob"]));
+
+        debugger.execute_command(".users[0]").unwrap();
+        let yaml_file = dir.path().join("user.yml");
+        debugger
+            .execute_command(&format!(":export {}", yaml_file.display()))
+            .unwrap();
+        assert_eq!(fs::read_to_string(&yaml_file).unwrap(), "name: Alice\n");
+    }
+
+    #[test]
+    fn test_output_format() {
+        let value = serde_json::json!({"server": {"port": 8080}});
+        assert_eq!(
+            DebugOutputFormat::Json.render(&value, false),
+            r#"{"server":{"port":8080}}"#
+        );
+        assert_eq!(
+            DebugOutputFormat::Yaml.render(&value, false),
+            "server:\n  port: 8080"
+        );
+
+        let mut debugger = XQPathDebugger::new();
+        assert_eq!(debugger.output_format, DebugOutputFormat::Json);
+        debugger.execute_command(":format yaml").unwrap();
+        assert_eq!(debugger.output_format, DebugOutputFormat::Yaml);
+        debugger.execute_command(":format").unwrap();
+        assert_eq!(debugger.output_format, DebugOutputFormat::Yaml);
+        assert!(debugger.execute_command(":format toml").is_err());
+        debugger.execute_command(":format JSON").unwrap();
+        assert_eq!(debugger.output_format, DebugOutputFormat::Json);
+    }
+
+    #[test]
+    fn test_step_preview_multibyte() {
+        let mut debugger = XQPathDebugger::new();
+        let text = format!("{}{}", "a".repeat(118), "😀中".repeat(20));
+        debugger.session.current_data =
+            Some(serde_json::json!({"items": [{"text": text}]}));
+
+        debugger.execute_command(":start .items[0].text").unwrap();
+        for _ in 0..2 {
+            debugger.execute_command(":step").unwrap();
+        }
+        debugger.execute_command(":format yaml").unwrap();
+        debugger.execute_command(":step").unwrap();
+        assert_eq!(
+            debugger.query_evaluator.last_result,
+            Some(Value::String(text))
+        );
+
+        let (start, candidates) = complete_input(None, "中文", 1);
+        assert_eq!((start, candidates), (0, Vec::<String>::new()));
+    }
 }
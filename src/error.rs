@@ -0,0 +1,129 @@
+//! 统一的库错误类型
+//!
+//! 库的公开函数（[`crate::extract()`]、[`crate::parser::path::parse_path`]、
+//! 更新函数、格式检测、JSON Patch 以及 [`crate::query_str`] 等查询入口）
+//! 统一返回 [`XqpathError`]，可按类别匹配具体的模块错误；
+//! 需要精确错误类型时可直接使用 [`crate::Extractor`]、`Updater`、
+//! [`crate::ExpressionEvaluator`]、[`crate::ExpressionParser`]、
+//! [`crate::JsonPatch`] 等类型的方法。
+//! 所有模块错误都实现了到 [`XqpathError`] 的 `From`，可在调用方用 `?` 汇总。
+//! `query!` 等宏为保持兼容仍返回 `Box<dyn Error>`。
+
+use crate::extractor::ExtractError;
+use crate::parser::evaluation::EvaluationError;
+use crate::parser::path::ParseError;
+#[cfg(feature = "update")]
+use crate::updater::UpdateError;
+use crate::value::format::FormatError;
+use crate::value::patch::PatchError;
+use std::fmt;
+
+/// 统一的库错误类型，汇总各模块的错误以便按类别匹配
+#[derive(Debug, Clone)]
+pub enum XqpathError {
+    /// 路径或表达式解析错误
+    Parse(ParseError),
+    /// 路径提取错误
+    Extract(ExtractError),
+    /// 表达式求值错误
+    Evaluation(EvaluationError),
+    /// 数据格式检测、解析或序列化错误
+    Format(FormatError),
+    /// 更新错误
+    #[cfg(feature = "update")]
+    Update(UpdateError),
+    /// JSON Patch 应用错误
+    Patch(PatchError),
+}
+
+/// 使用统一错误类型的结果
+pub type XqpathResult<T> = Result<T, XqpathError>;
+
+impl fmt::Display for XqpathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XqpathError::Parse(e) => write!(f, "{e}"),
+            XqpathError::Extract(e) => write!(f, "{e}"),
+            XqpathError::Evaluation(e) => write!(f, "{e}"),
+            XqpathError::Format(e) => write!(f, "{e}"),
+            #[cfg(feature = "update")]
+            XqpathError::Update(e) => write!(f, "{e}"),
+            XqpathError::Patch(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// 显示内容直接取自内部错误，因此 `source` 转发内部错误的来源，
+/// 避免错误链中同一条信息出现两次
+impl std::error::Error for XqpathError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            XqpathError::Parse(e) => e.source(),
+            XqpathError::Extract(e) => e.source(),
+            XqpathError::Evaluation(e) => e.source(),
+            XqpathError::Format(e) => e.source(),
+            #[cfg(feature = "update")]
+            XqpathError::Update(e) => e.source(),
+            XqpathError::Patch(e) => e.source(),
+        }
+    }
+}
+
+impl From<ParseError> for XqpathError {
+    fn from(e: ParseError) -> Self {
+        XqpathError::Parse(e)
+    }
+}
+
+impl From<ExtractError> for XqpathError {
+    fn from(e: ExtractError) -> Self {
+        XqpathError::Extract(e)
+    }
+}
+
+impl From<EvaluationError> for XqpathError {
+    fn from(e: EvaluationError) -> Self {
+        XqpathError::Evaluation(e)
+    }
+}
+
+impl From<FormatError> for XqpathError {
+    fn from(e: FormatError) -> Self {
+        XqpathError::Format(e)
+    }
+}
+
+#[cfg(feature = "update")]
+impl From<UpdateError> for XqpathError {
+    fn from(e: UpdateError) -> Self {
+        XqpathError::Update(e)
+    }
+}
+
+impl From<PatchError> for XqpathError {
+    fn from(e: PatchError) -> Self {
+        XqpathError::Patch(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::path::parse_path;
+    use crate::value::format::detect_format;
+
+    #[test]
+    fn test_from_conversions() {
+        let parse_err: XqpathError = parse_path("[").unwrap_err();
+        assert!(matches!(parse_err, XqpathError::Parse(_)));
+
+        let format_err: XqpathError = detect_format("").err().unwrap();
+        assert!(matches!(format_err, XqpathError::Format(_)));
+
+        let eval_err: XqpathError =
+            EvaluationError::UnknownFunction("nope".to_string()).into();
+        assert!(matches!(eval_err, XqpathError::Evaluation(_)));
+        // 显示内容已取自内部错误，叶子错误没有更深的来源
+        assert!(std::error::Error::source(&eval_err).is_none());
+    }
+}
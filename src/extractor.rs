@@ -1,3 +1,6 @@
+#[cfg(feature = "debug")]
+use crate::debug::tracer::TraceStep;
+use crate::error::{XqpathError, XqpathResult};
 use crate::parser::path::PathSegment;
 use crate::value::json::JsonPath;
 use serde_json::Value;
@@ -43,24 +46,154 @@ impl Extractor {
     pub fn extract<'a>(
         root: &'a Value,
         path: &[PathSegment],
+    ) -> Result<Vec<&'a Value>, ExtractError> {
+        Self::extract_with_depth(root, path, None)
+    }
+
+    /// 按路径提取，递归通配符 `**` 最多下降 `max_depth` 层
+    ///
+    /// 深度 0 只匹配节点自身，深度 1 额外包含直接子节点；`None` 表示不限制。
+    pub fn extract_with_depth<'a>(
+        root: &'a Value,
+        path: &[PathSegment],
+        max_depth: Option<usize>,
     ) -> Result<Vec<&'a Value>, ExtractError> {
         if path.is_empty() {
             return Ok(vec![root]);
         }
 
+        #[cfg(feature = "logging")]
+        let _span = tracing::debug_span!(
+            "xqpath_query",
+            path = %crate::parser::path::format_path(path)
+        )
+        .entered();
+
         let mut current_values = vec![root];
+        let mut after_recursive = false;
 
         for segment in path {
-            current_values = Self::apply_segment(current_values, segment)?;
+            let result = Self::apply_segment(
+                current_values,
+                segment,
+                max_depth,
+                after_recursive,
+            );
+            #[cfg(feature = "logging")]
+            match &result {
+                Ok(values) => tracing::trace!(
+                    segment = %segment.to_jq_string(),
+                    matches = values.len(),
+                    "segment applied"
+                ),
+                Err(e) => tracing::debug!(
+                    segment = %segment.to_jq_string(),
+                    error = %e,
+                    "segment failed"
+                ),
+            }
+            current_values = result?;
+            after_recursive |= *segment == PathSegment::RecursiveWildcard;
         }
 
+        #[cfg(feature = "logging")]
+        tracing::debug!(matches = current_values.len(), "query finished");
+
         Ok(current_values)
     }
 
+    /// 按路径提取，同时记录每个路径段的输入/输出数量与耗时
+    ///
+    /// 出错时仍返回已执行的步骤，最后一步带有错误信息。
+    #[cfg(feature = "debug")]
+    pub fn extract_traced<'a>(
+        root: &'a Value,
+        path: &[PathSegment],
+    ) -> (Result<Vec<&'a Value>, ExtractError>, Vec<TraceStep>) {
+        let mut steps = Vec::with_capacity(path.len());
+        let mut current_values = vec![root];
+        let mut after_recursive = false;
+
+        for segment in path {
+            let input_count = current_values.len();
+            let start = std::time::Instant::now();
+            let result = Self::apply_segment(
+                current_values,
+                segment,
+                None,
+                after_recursive,
+            );
+            let duration = start.elapsed();
+            after_recursive |= *segment == PathSegment::RecursiveWildcard;
+
+            match result {
+                Ok(values) => {
+                    steps.push(TraceStep {
+                        segment: segment.clone(),
+                        input_count,
+                        output_count: values.len(),
+                        duration,
+                        error: None,
+                    });
+                    current_values = values;
+                }
+                Err(e) => {
+                    steps.push(TraceStep {
+                        segment: segment.clone(),
+                        input_count,
+                        output_count: 0,
+                        duration,
+                        error: Some(e.to_string()),
+                    });
+                    return (Err(e), steps);
+                }
+            }
+        }
+
+        (Ok(current_values), steps)
+    }
+
+    /// 检查路径在通配符展开的每个分支上都存在
+    ///
+    /// 与 [`extract`] 的“任一匹配即存在”不同，`[*]` 展开出的每个元素都必须
+    /// 匹配剩余路径（空容器视为满足）；其余路径段只要求至少有一个匹配。
+    pub fn contains_path(
+        root: &Value,
+        path: &[PathSegment],
+    ) -> Result<bool, ExtractError> {
+        let Some((segment, rest)) = path.split_first() else {
+            return Ok(true);
+        };
+
+        if *segment == PathSegment::Wildcard {
+            if !matches!(root, Value::Object(_) | Value::Array(_)) {
+                return Ok(false);
+            }
+            for child in Self::extract_wildcard(root)? {
+                if !Self::contains_path(child, rest)? {
+                    return Ok(false);
+                }
+            }
+            return Ok(true);
+        }
+
+        for value in Self::apply_segment(vec![root], segment, None, false)? {
+            if Self::contains_path(value, rest)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     /// 应用单个路径段到当前值集合
+    ///
+    /// 递归通配符之后的数组长度各异，`skip_out_of_bounds` 为真时跳过
+    /// 索引越界的数组（类似 jq 的 `.. | .[n]?`），而不是报错。
     fn apply_segment<'a>(
         values: Vec<&'a Value>,
         segment: &PathSegment,
+        max_depth: Option<usize>,
+        skip_out_of_bounds: bool,
     ) -> Result<Vec<&'a Value>, ExtractError> {
         let mut results = Vec::new();
 
@@ -70,13 +203,17 @@ impl Extractor {
                     results.extend(Self::extract_field(value, field_name)?);
                 }
                 PathSegment::Index(index) => {
-                    results.extend(Self::extract_index(value, *index)?);
+                    match Self::extract_index(value, *index) {
+                        Err(ExtractError::IndexOutOfBounds(_, _))
+                            if skip_out_of_bounds => {}
+                        result => results.extend(result?),
+                    }
                 }
                 PathSegment::Wildcard => {
                     results.extend(Self::extract_wildcard(value)?);
                 }
                 PathSegment::RecursiveWildcard => {
-                    results.extend(Self::extract_recursive(value)?);
+                    results.extend(Self::extract_recursive(value, max_depth)?);
                 }
                 PathSegment::TypeFilter(type_name) => {
                     results.extend(Self::apply_type_filter(
@@ -84,6 +221,16 @@ impl Extractor {
                         type_name,
                     )?);
                 }
+                PathSegment::Optional(inner) => {
+                    if let Ok(values) = Self::apply_segment(
+                        vec![value],
+                        inner,
+                        max_depth,
+                        skip_out_of_bounds,
+                    ) {
+                        results.extend(values);
+                    }
+                }
             }
         }
 
@@ -103,10 +250,7 @@ impl Extractor {
                     Ok(vec![]) // 字段不存在时返回空结果而不是错误
                 }
             }
-            _ => Err(ExtractError::TypeMismatch(
-                "object".to_string(),
-                Self::get_value_type_name(value).to_string(),
-            )),
+            _ => Ok(vec![]), // 非对象类型返回空结果，与表达式求值器一致
         }
     }
 
@@ -123,14 +267,14 @@ impl Extractor {
                     Err(ExtractError::IndexOutOfBounds(index, arr.len()))
                 }
             }
-            _ => Err(ExtractError::TypeMismatch(
-                "array".to_string(),
-                Self::get_value_type_name(value).to_string(),
-            )),
+            _ => Ok(vec![]), // 非数组类型返回空结果，与表达式求值器一致
         }
     }
 
     /// 提取通配符匹配的所有值
+    ///
+    /// 数组按下标顺序；对象按映射的迭代顺序：启用 `update`（即 serde_json 的
+    /// `preserve_order`）时为插入顺序，否则按键排序。两种情况下结果在多次运行间都是稳定的。
     fn extract_wildcard(value: &Value) -> Result<Vec<&Value>, ExtractError> {
         match value {
             Value::Object(map) => Ok(map.values().collect()),
@@ -139,19 +283,30 @@ impl Extractor {
         }
     }
 
-    /// 递归提取所有匹配的值
-    fn extract_recursive(value: &Value) -> Result<Vec<&Value>, ExtractError> {
+    /// 递归提取所有匹配的值，`max_depth` 限制下降的层数
+    fn extract_recursive(
+        value: &Value,
+        max_depth: Option<usize>,
+    ) -> Result<Vec<&Value>, ExtractError> {
         let mut results = vec![value]; // 包含当前值本身
 
+        if max_depth == Some(0) {
+            return Ok(results);
+        }
+        let child_depth = max_depth.map(|depth| depth - 1);
+
         match value {
             Value::Object(map) => {
                 for field_value in map.values() {
-                    results.extend(Self::extract_recursive(field_value)?);
+                    results.extend(Self::extract_recursive(
+                        field_value,
+                        child_depth,
+                    )?);
                 }
             }
             Value::Array(arr) => {
                 for item in arr {
-                    results.extend(Self::extract_recursive(item)?);
+                    results.extend(Self::extract_recursive(item, child_depth)?);
                 }
             }
             _ => {} // 叶子节点，不需要递归
@@ -192,11 +347,29 @@ impl Extractor {
 }
 
 /// 便利函数，直接从根值提取路径
+///
+/// 结果按文档顺序返回；通配符展开对象时遵循对象键的迭代顺序
+/// （启用 `update` 时为插入顺序，否则按键排序），多次运行结果一致。
 pub fn extract<'a>(
     root: &'a Value,
     path: &[PathSegment],
-) -> Result<Vec<&'a Value>, ExtractError> {
-    Extractor::extract(root, path)
+) -> XqpathResult<Vec<&'a Value>> {
+    Extractor::extract(root, path).map_err(XqpathError::from)
+}
+
+/// 便利函数，检查路径在通配符展开的每个分支上都存在
+pub fn contains_path(root: &Value, path: &[PathSegment]) -> XqpathResult<bool> {
+    Extractor::contains_path(root, path).map_err(XqpathError::from)
+}
+
+/// 便利函数，按路径提取并限制递归通配符的下降深度
+pub fn extract_with_depth<'a>(
+    root: &'a Value,
+    path: &[PathSegment],
+    max_depth: Option<usize>,
+) -> XqpathResult<Vec<&'a Value>> {
+    Extractor::extract_with_depth(root, path, max_depth)
+        .map_err(XqpathError::from)
 }
 
 /// 提取器配置选项
@@ -208,6 +381,8 @@ pub struct ExtractorConfig {
     pub ignore_type_mismatches: bool,
     /// 最大递归深度（防止无限递归）
     pub max_recursion_depth: usize,
+    /// 递归通配符 `**` 最多下降的层数，`None` 表示不限制
+    pub max_wildcard_depth: Option<usize>,
 }
 
 impl Default for ExtractorConfig {
@@ -216,6 +391,7 @@ impl Default for ExtractorConfig {
             ignore_missing_paths: true,
             ignore_type_mismatches: false,
             max_recursion_depth: 1000,
+            max_wildcard_depth: None,
         }
     }
 }
@@ -302,11 +478,17 @@ impl ConfigurableExtractor {
                             "Maximum recursion depth exceeded in recursive wildcard".to_string(),
                         ));
                     }
-                    Extractor::extract_recursive(value)
+                    Extractor::extract_recursive(
+                        value,
+                        self.config.max_wildcard_depth,
+                    )
                 }
                 PathSegment::TypeFilter(type_name) => {
                     Extractor::apply_type_filter(vec![value], type_name)
                 }
+                PathSegment::Optional(inner) => Ok(self
+                    .apply_segment_with_config(vec![value], inner, depth)
+                    .unwrap_or_default()),
             };
 
             match segment_result {
@@ -424,12 +606,27 @@ mod tests {
 
     #[test]
     fn test_extract_wildcard() {
-        let data = json!({"a": 1, "b": 2, "c": 3});
+        let data: Value =
+            serde_json::from_str(r#"{"b": 2, "c": 3, "a": 1}"#).unwrap();
         let path = parse_path("*").unwrap();
 
         let result = extract(&data, &path).unwrap();
-        assert_eq!(result.len(), 3);
-        // 注意：结果顺序可能不确定
+        // preserve_order 下保持插入顺序，否则按键排序
+        #[cfg(feature = "update")]
+        assert_eq!(result, vec![&json!(2), &json!(3), &json!(1)]);
+        #[cfg(not(feature = "update"))]
+        assert_eq!(result, vec![&json!(1), &json!(2), &json!(3)]);
+
+        // 多次执行结果一致
+        for _ in 0..10 {
+            assert_eq!(extract(&data, &path).unwrap(), result);
+        }
+
+        let data = json!([3, 1, 2]);
+        assert_eq!(
+            extract(&data, &path).unwrap(),
+            vec![&json!(3), &json!(1), &json!(2)]
+        );
     }
 
     #[test]
@@ -468,6 +665,7 @@ mod tests {
             ignore_missing_paths: true,
             ignore_type_mismatches: true,
             max_recursion_depth: 100,
+            max_wildcard_depth: None,
         };
         let extractor = ConfigurableExtractor::new(config);
 
@@ -490,4 +688,159 @@ mod tests {
         assert_eq!(result.len(), 1);
         assert_eq!(result[0], &json!("hello"));
     }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_extract_traced_steps() {
+        let data = json!({"users": [{"name": "Alice"}, {"name": "Bob"}]});
+        let path = parse_path(".users[*].name").unwrap();
+
+        let (result, steps) = Extractor::extract_traced(&data, &path);
+        assert_eq!(result.unwrap().len(), 2);
+        let counts: Vec<(usize, usize)> = steps
+            .iter()
+            .map(|s| (s.input_count, s.output_count))
+            .collect();
+        assert_eq!(counts, vec![(1, 1), (1, 2), (2, 2)]);
+        assert!(steps.iter().all(|s| s.error.is_none()));
+
+        let path = parse_path(".users[5]").unwrap();
+        let (result, steps) = Extractor::extract_traced(&data, &path);
+        assert!(result.is_err());
+        assert_eq!(steps.len(), 2);
+        assert!(steps[1].error.is_some());
+    }
+
+    #[test]
+    fn test_recursive_wildcard_depth() {
+        let data = json!({"a": {"b": {"c": 1}}, "d": [2]});
+        let path = parse_path("**").unwrap();
+
+        let all = extract(&data, &path).unwrap();
+        assert_eq!(all.len(), 6);
+        assert_eq!(extract_with_depth(&data, &path, None).unwrap(), all);
+
+        let root_only = extract_with_depth(&data, &path, Some(0)).unwrap();
+        assert_eq!(root_only, vec![&data]);
+
+        // 深度 1：根节点加上直接子节点
+        let shallow = extract_with_depth(&data, &path, Some(1)).unwrap();
+        assert_eq!(shallow, vec![&data, &data["a"], &data["d"]]);
+
+        let two = extract_with_depth(&data, &path, Some(2)).unwrap();
+        assert_eq!(two.len(), 5);
+        assert!(!two.contains(&&json!(1)));
+
+        let extractor = ConfigurableExtractor::new(ExtractorConfig {
+            max_wildcard_depth: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(extractor.extract(&data, &path).unwrap(), shallow);
+    }
+
+    #[test]
+    fn test_optional_segment() {
+        let data = json!({"a": "text", "b": [1, 2]});
+
+        assert!(extract(&data, &parse_path(".b[5]").unwrap()).is_err());
+        assert!(extract(&data, &parse_path(".a.b?").unwrap())
+            .unwrap()
+            .is_empty());
+        assert!(extract(&data, &parse_path(".b[5]?").unwrap())
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            extract(&data, &parse_path(".b[1]?").unwrap()).unwrap(),
+            vec![&json!(2)]
+        );
+
+        let strict = ConfigurableExtractor::default();
+        assert!(strict.extract(&data, &parse_path(".a.b").unwrap()).is_err());
+        assert!(strict
+            .extract(&data, &parse_path(".a.b?").unwrap())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_contains_path() {
+        let data = json!({
+            "users": [
+                {"name": "Alice", "email": "a@example.com"},
+                {"name": "Bob"}
+            ],
+            "groups": [],
+            "name": "root"
+        });
+        let check =
+            |path: &str| contains_path(&data, &parse_path(path).unwrap());
+
+        assert!(check(".users[*].name").unwrap());
+        assert!(!check(".users[*].email").unwrap());
+        assert!(!extract(&data, &parse_path(".users[*].email").unwrap())
+            .unwrap()
+            .is_empty());
+
+        // 空容器中没有不满足的分支
+        assert!(check(".groups[*].id").unwrap());
+        assert!(!check(".missing[*].id").unwrap());
+        assert!(!check(".name[*]").unwrap());
+        assert!(check(".users[0].email").unwrap());
+        assert!(!check(".users[1].email").unwrap());
+    }
+
+    #[cfg(feature = "logging")]
+    #[test]
+    fn test_logging_events() {
+        use std::sync::{Arc, Mutex};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        /// 只记录 span 名称与事件级别的订阅者
+        #[derive(Clone, Default)]
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let name = span.metadata().name();
+                self.0.lock().unwrap().push(format!("span {name}"));
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                let level = event.metadata().level();
+                self.0.lock().unwrap().push(format!("event {level}"));
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let recorder = Recorder::default();
+        let data = json!({"a": [1, 2]});
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let path = parse_path(".a[*]").unwrap();
+            extract(&data, &path).unwrap();
+            assert!(extract(&data, &parse_path(".a[5]").unwrap()).is_err());
+        });
+
+        let records = recorder.0.lock().unwrap();
+        assert_eq!(
+            *records,
+            vec![
+                "event TRACE",
+                "span xqpath_query",
+                "event TRACE",
+                "event TRACE",
+                "event DEBUG",
+                "event TRACE",
+                "span xqpath_query",
+                "event TRACE",
+                "event DEBUG",
+            ]
+        );
+    }
 }
@@ -77,8 +77,10 @@
 mod macros;
 
 // 核心模块
+pub mod error;
 pub mod extractor;
 pub mod parser;
+pub mod query;
 #[cfg(feature = "update")]
 pub mod updater;
 pub mod value;
@@ -95,13 +97,17 @@ pub mod config;
 pub mod debugger;
 
 // 重新导出主要类型和函数
+pub use error::{XqpathError, XqpathResult};
 pub use extractor::{
-    extract, ConfigurableExtractor, ExtractError, Extractor, ExtractorConfig,
+    contains_path, extract, extract_with_depth, ConfigurableExtractor,
+    ExtractError, Extractor, ExtractorConfig,
 };
+pub use query::{query_bytes, query_into, query_str, query_stream, Query};
 
 #[cfg(feature = "update")]
 pub use updater::{
-    update, ConfigurableUpdater, UpdateError, Updater, UpdaterConfig,
+    delete, merge_values, rename_key, update, ConfigurableUpdater, UpdateError,
+    Updater, UpdaterConfig,
 };
 
 // 调试功能导出
@@ -114,7 +120,9 @@ pub use debug::{
 pub use debug::logger::{Logger, LoggerConfig};
 
 #[cfg(feature = "debug")]
-pub use debug::tracer::{ExecutionSummary, TraceEvent, TraceResult, Tracer};
+pub use debug::tracer::{
+    ExecutionSummary, TraceEvent, TraceResult, TraceStep, Tracer,
+};
 
 #[cfg(feature = "debug")]
 pub use debug::reporter::{
@@ -125,6 +133,9 @@ pub use debug::reporter::{
 #[cfg(feature = "profiling")]
 pub use debug::profiler::{MemoryProfiler, PerformanceMonitor, ProfileReport};
 
+#[cfg(feature = "alloc-profiling")]
+pub use debug::alloc::CountingAllocator;
+
 #[cfg(feature = "benchmark")]
 pub use debug::benchmark::{
     BenchmarkConfig, BenchmarkOutputFormat, BenchmarkResult, BenchmarkSuite,
@@ -136,27 +147,44 @@ pub use config::{ConfigError, ConfigManager, ConfigResult, XQPathConfig};
 
 // v1.4.3 交互式调试器功能导出
 pub use debugger::{
-    Breakpoint, DataInspector, DebugCommand, DebugError, DebugResult,
-    DebugSession, QueryEvaluator, WatchPoint, XQPathDebugger,
+    Breakpoint, DataInspector, DebugCommand, DebugError, DebugOutputFormat,
+    DebugResult, DebugSession, QueryEvaluator, WatchPoint, XQPathDebugger,
 };
 
 pub use parser::{
-    ast::{ComparisonOp, ExpressionComplexity, LogicalOp, PathExpression},
+    ast::{
+        analyze_path_complexity, ArithmeticOp, ComparisonOp,
+        ExpressionComplexity, LogicalOp, PathExpression,
+    },
     evaluation::{
         evaluate_path_expression, EvaluationError, ExpressionEvaluator,
     },
     functions::{AdvancedBuiltinFunction, BuiltinFunction, FunctionRegistry},
+    jsonpath::parse_jsonpath,
     parsing::{parse_path_expression, ExpressionParser},
-    path::{parse_path, ParseError, PathSegment},
+    path::{format_jsonpath, format_path, parse_path, ParseError, PathSegment},
 };
 
+pub use value::diff::{diff_values, Difference};
+
 pub use value::format::{
-    detect_format, FormatError, FormatRegistry, JsonFormat, ValueFormat,
-    YamlFormat,
+    detect_format, detect_format_strict, parse_bytes, strip_bom, truncate_str,
+    FormatError, FormatRegistry, JsonFormat, ValueFormat, YamlFormat,
 };
 
+pub use value::flatten::{flatten_object, unflatten_object};
 pub use value::json::{JsonPath, JsonSupport};
-pub use value::yaml::{YamlFormatter, YamlSpecialValues, YamlSupport};
+pub use value::patch::{
+    apply_json_patch, apply_merge_patch, parse_json_pointer, JsonPatch,
+    PatchError, PatchOperation,
+};
+pub use value::stream::{
+    dotted_path, leaf_paths, path_to_value, stream_value, value_to_path,
+    walk_paths, PathWalk, ValueStream,
+};
+pub use value::yaml::{
+    YamlFormatter, YamlQuoteStyle, YamlSpecialValues, YamlSupport,
+};
 
 // Note: Macros are automatically available when using the crate
 
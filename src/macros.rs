@@ -400,6 +400,143 @@ macro_rules! query_as_type {
     }};
 }
 
+/// 便利宏，用于提取路径匹配的所有值并逐个转换为指定类型
+///
+/// 默认为“全有或全无”：任一元素转换失败时返回错误。
+/// 在末尾加上 `skip` 时改为跳过无法转换的元素，只返回转换成功的值。
+///
+/// # 参数
+/// - `$data`: 输入的数据字符串（JSON 或 YAML 格式）
+/// - `$path`: 路径表达式字符串
+/// - `$type`: 目标元素类型
+/// - `skip`（可选）: 跳过无法转换的元素
+///
+/// # 返回值
+/// 返回 `Result<Vec<$type>, Box<dyn std::error::Error>>`
+///
+/// # 示例
+/// ```rust
+/// use xqpath::query_all_as_type;
+///
+/// let json = r#"{"users": [{"age": 30}, {"age": 25}, {"age": "n/a"}]}"#;
+///
+/// // 全有或全无：第三个元素无法转换为 u32，整体失败
+/// assert!(query_all_as_type!(json, "users[*].age", u32).is_err());
+///
+/// // 跳过无法转换的元素
+/// let ages: Vec<u32> = query_all_as_type!(json, "users[*].age", u32, skip).unwrap();
+/// assert_eq!(ages, vec![30, 25]);
+/// ```
+#[macro_export]
+macro_rules! query_all_as_type {
+    ($data:expr, $path:expr, $type:ty) => {{
+        use serde_json::from_value;
+        use $crate::extractor::extract;
+        use $crate::parser::path::parse_path;
+        use $crate::value::format::detect_format;
+
+        (|| -> Result<Vec<$type>, Box<dyn std::error::Error>> {
+            let format = detect_format(&$data)?;
+            let parsed = format.parse(&$data)?;
+            let path = parse_path($path)?;
+            let values = extract(&parsed, &path)?;
+
+            let mut typed_values = Vec::with_capacity(values.len());
+            for (index, value) in values.into_iter().enumerate() {
+                let typed_value = from_value::<$type>(value.clone())
+                    .map_err(|e| format!("element {index}: {e}"))?;
+                typed_values.push(typed_value);
+            }
+            Ok(typed_values)
+        })()
+    }};
+    ($data:expr, $path:expr, $type:ty, skip) => {{
+        use serde_json::from_value;
+        use $crate::extractor::extract;
+        use $crate::parser::path::parse_path;
+        use $crate::value::format::detect_format;
+
+        (|| -> Result<Vec<$type>, Box<dyn std::error::Error>> {
+            let format = detect_format(&$data)?;
+            let parsed = format.parse(&$data)?;
+            let path = parse_path($path)?;
+            let values = extract(&parsed, &path)?;
+
+            Ok(values
+                .into_iter()
+                .filter_map(|value| from_value::<$type>(value.clone()).ok())
+                .collect())
+        })()
+    }};
+}
+
+/// 便利宏，用于将路径处的单个对象反序列化为映射类型
+///
+/// 路径必须恰好匹配一个对象；匹配非对象、多个值或没有匹配时返回错误。
+/// 目标类型可由调用处推断，也可作为第三个参数显式指定。
+///
+/// # 参数
+/// - `$data`: 输入的数据字符串（JSON 或 YAML 格式）
+/// - `$path`: 路径表达式字符串
+/// - `$type`（可选）: 目标映射类型，如 `HashMap<String, u16>`
+///
+/// # 返回值
+/// 返回 `Result<$type, Box<dyn std::error::Error>>`
+///
+/// # 示例
+/// ```rust
+/// use std::collections::HashMap;
+/// use xqpath::query_map;
+///
+/// let yaml = r#"
+/// ports:
+///   http: 80
+///   https: 443
+/// "#;
+///
+/// let ports: HashMap<String, u16> = query_map!(yaml, "ports").unwrap();
+/// assert_eq!(ports["https"], 443);
+/// ```
+#[macro_export]
+macro_rules! query_map {
+    ($data:expr, $path:expr) => {{
+        use $crate::extractor::extract;
+        use $crate::parser::path::parse_path;
+        use $crate::value::format::detect_format;
+
+        (|| -> Result<_, Box<dyn std::error::Error>> {
+            let format = detect_format(&$data)?;
+            let parsed = format.parse(&$data)?;
+            let path = parse_path($path)?;
+            let values = extract(&parsed, &path)?;
+
+            match values.as_slice() {
+                [value] if value.is_object() => {
+                    Ok(serde_json::from_value((*value).clone())?)
+                }
+                [value] => Err(format!(
+                    "expected an object at '{}', found {}",
+                    $path,
+                    $crate::value::json::JsonSupport::get_type_name(value)
+                )
+                .into()),
+                [] => Err(format!("path '{}' not found", $path).into()),
+                _ => Err(format!(
+                    "path '{}' matched {} values, expected exactly one object",
+                    $path,
+                    values.len()
+                )
+                .into()),
+            }
+        })()
+    }};
+    ($data:expr, $path:expr, $type:ty) => {{
+        let result: Result<$type, Box<dyn std::error::Error>> =
+            $crate::query_map!($data, $path);
+        result
+    }};
+}
+
 /// 便利宏，用于从结构化数据中查询多个路径
 ///
 /// # 参数
@@ -487,6 +624,42 @@ macro_rules! exists_all {
     }};
 }
 
+/// 便利宏，用于检查通配符展开的每个元素是否都包含指定路径
+///
+/// `exists!` 在任一元素匹配时即返回 true，本宏要求 `[*]` 展开的每个分支都匹配，
+/// 适合校验所有记录都包含必填字段。
+///
+/// # 参数
+/// - `$data`: 输入的数据字符串（JSON 或 YAML 格式）
+/// - `$path`: 路径表达式字符串
+///
+/// # 返回值
+/// 返回 `Result<bool, Box<dyn std::error::Error>>`
+///
+/// # 示例
+/// ```rust
+/// use xqpath::{exists, exists_all_elements};
+///
+/// let json = r#"{"users": [{"email": "a@example.com"}, {"name": "Bob"}]}"#;
+/// assert!(exists!(json, "users[*].email").unwrap());
+/// assert!(!exists_all_elements!(json, "users[*].email").unwrap());
+/// ```
+#[macro_export]
+macro_rules! exists_all_elements {
+    ($data:expr, $path:expr) => {{
+        use $crate::extractor::contains_path;
+        use $crate::parser::path::parse_path;
+        use $crate::value::format::detect_format;
+
+        (|| -> Result<bool, Box<dyn std::error::Error>> {
+            let format = detect_format(&$data)?;
+            let parsed = format.parse(&$data)?;
+            let path = parse_path($path)?;
+            Ok(contains_path(&parsed, &path)?)
+        })()
+    }};
+}
+
 /// 便利宏，用于检查结构化数据中是否存在任意一个指定路径
 ///
 /// # 参数
@@ -629,6 +802,126 @@ macro_rules! query_length {
     }};
 }
 
+/// 便利宏，用于获取路径处对象的键（按字典序排序）或数组的索引
+///
+/// 路径必须恰好匹配一个对象或数组；匹配其他类型、多个值或没有匹配时返回错误。
+///
+/// # 参数
+/// - `$data`: 输入的数据字符串（JSON 或 YAML 格式）
+/// - `$path`: 路径表达式字符串
+///
+/// # 返回值
+/// 返回 `Result<Vec<String>, Box<dyn std::error::Error>>`
+///
+/// # 示例
+/// ```rust
+/// use xqpath::keys;
+///
+/// let json = r#"{"config": {"port": 80, "host": "localhost"}, "tags": ["a", "b"]}"#;
+/// assert_eq!(keys!(json, "config").unwrap(), vec!["host", "port"]);
+/// assert_eq!(keys!(json, "tags").unwrap(), vec!["0", "1"]);
+/// assert!(keys!(json, "config.port").is_err());
+/// ```
+#[macro_export]
+macro_rules! keys {
+    ($data:expr, $path:expr) => {{
+        use $crate::extractor::extract;
+        use $crate::parser::path::parse_path;
+        use $crate::value::format::detect_format;
+
+        (|| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+            let format = detect_format(&$data)?;
+            let parsed = format.parse(&$data)?;
+            let path = parse_path($path)?;
+            let values = extract(&parsed, &path)?;
+
+            match values.as_slice() {
+                [serde_json::Value::Object(obj)] => {
+                    let mut keys: Vec<String> = obj.keys().cloned().collect();
+                    keys.sort();
+                    Ok(keys)
+                }
+                [serde_json::Value::Array(arr)] => {
+                    Ok((0..arr.len()).map(|i| i.to_string()).collect())
+                }
+                [value] => Err(format!(
+                    "expected an object or array at '{}', found {}",
+                    $path,
+                    $crate::value::json::JsonSupport::get_type_name(value)
+                )
+                .into()),
+                [] => Err(format!("path '{}' not found", $path).into()),
+                _ => Err(format!(
+                    "path '{}' matched {} values, expected exactly one container",
+                    $path,
+                    values.len()
+                )
+                .into()),
+            }
+        })()
+    }};
+}
+
+/// 便利宏，用于以 `(键, 值)` 对的形式遍历路径处的对象
+///
+/// 路径必须恰好匹配一个对象；匹配非对象、多个值或没有匹配时返回错误。
+///
+/// # 参数
+/// - `$data`: 输入的数据字符串（JSON 或 YAML 格式）
+/// - `$path`: 路径表达式字符串
+///
+/// # 返回值
+/// 返回 `Result<Vec<(String, serde_json::Value)>, Box<dyn std::error::Error>>`
+///
+/// # 示例
+/// ```rust
+/// use serde_json::json;
+/// use xqpath::entries;
+///
+/// let yaml = r#"
+/// servers:
+///   web: 80
+/// "#;
+///
+/// let servers = entries!(yaml, "servers").unwrap();
+/// assert_eq!(servers, vec![("web".to_string(), json!(80))]);
+/// ```
+#[macro_export]
+macro_rules! entries {
+    ($data:expr, $path:expr) => {{
+        use $crate::extractor::extract;
+        use $crate::parser::path::parse_path;
+        use $crate::value::format::detect_format;
+
+        (|| -> Result<Vec<(String, serde_json::Value)>, Box<dyn std::error::Error>> {
+            let format = detect_format(&$data)?;
+            let parsed = format.parse(&$data)?;
+            let path = parse_path($path)?;
+            let values = extract(&parsed, &path)?;
+
+            match values.as_slice() {
+                [serde_json::Value::Object(obj)] => Ok(obj
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect()),
+                [value] => Err(format!(
+                    "expected an object at '{}', found {}",
+                    $path,
+                    $crate::value::json::JsonSupport::get_type_name(value)
+                )
+                .into()),
+                [] => Err(format!("path '{}' not found", $path).into()),
+                _ => Err(format!(
+                    "path '{}' matched {} values, expected exactly one object",
+                    $path,
+                    values.len()
+                )
+                .into()),
+            }
+        })()
+    }};
+}
+
 // ===== 调试宏 (v1.4.1+) =====
 
 /// 带调试信息的查询宏
@@ -724,12 +1017,12 @@ macro_rules! trace_query {
         use $crate::extractor::extract;
         use $crate::parser::path::parse_path;
         use $crate::value::format::detect_format;
-        use $crate::debug::TimingStats;
+        use $crate::debug::{MemoryMeter, TimingStats};
         use std::time::Instant;
 
         (|| -> Result<(Vec<serde_json::Value>, TimingStats), Box<dyn std::error::Error>> {
             let start_time = Instant::now();
-            let _start_memory = 0; // TODO: 实际内存跟踪
+            let meter = MemoryMeter::start();
 
             let format = detect_format(&$data)?;
             let parsed = format.parse(&$data)?;
@@ -737,7 +1030,7 @@ macro_rules! trace_query {
             let values = extract(&parsed, &path)?;
 
             let duration = start_time.elapsed();
-            let memory_used = 0; // TODO: 计算实际内存使用
+            let (memory_used, peak_memory) = meter.finish(&parsed, &values);
 
             let owned_values: Vec<serde_json::Value> =
                 values.into_iter().map(|v| v.clone()).collect();
@@ -745,7 +1038,7 @@ macro_rules! trace_query {
             let stats = TimingStats {
                 duration,
                 memory_used,
-                peak_memory: memory_used,
+                peak_memory,
             };
 
             Ok((owned_values, stats))
@@ -838,6 +1131,9 @@ macro_rules! query_with_profile {
 ///
 /// ⚠️ **注意**: 此宏仅在启用 `profiling` feature 时可用
 ///
+/// 启用 `alloc-profiling` 并安装 `CountingAllocator` 为全局分配器后，
+/// `peak_memory_bytes` 为查询自身的分配峰值，否则为进程 RSS。
+///
 /// # 示例
 /// ```rust
 /// #[cfg(feature = "profiling")]
@@ -918,6 +1214,7 @@ macro_rules! benchmark_query {
             // 设置基准测试
             let config = BenchmarkConfig {
                 warmup_iterations: ($iterations as f64 * 0.1) as usize,
+                warmup_time: None,
                 test_iterations: $iterations,
                 min_test_time: Duration::from_millis(10),
                 max_test_time: Duration::from_secs(30),
@@ -1121,6 +1418,49 @@ user:
         assert_eq!(missing, None);
     }
 
+    #[test]
+    fn test_query_all_as_type_macro() {
+        let json = r#"{"users": [{"age": 30}, {"age": 25}, {"name": "x"}]}"#;
+
+        let ages: Vec<u32> =
+            query_all_as_type!(json, "users[*].age", u32).unwrap();
+        assert_eq!(ages, vec![30, 25]);
+
+        let mixed = r#"{"values": [1, "two", 3]}"#;
+        assert!(query_all_as_type!(mixed, "values[*]", i64).is_err());
+
+        let numbers: Vec<i64> =
+            query_all_as_type!(mixed, "values[*]", i64, skip).unwrap();
+        assert_eq!(numbers, vec![1, 3]);
+
+        let empty: Vec<String> =
+            query_all_as_type!(json, "missing[*]", String).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_query_map_macro() {
+        use std::collections::{BTreeMap, HashMap};
+
+        let yaml = r#"
+ports:
+  http: 80
+  https: 443
+hosts: [a, b]
+"#;
+
+        let ports: HashMap<String, u16> = query_map!(yaml, "ports").unwrap();
+        assert_eq!(ports.len(), 2);
+        assert_eq!(ports["http"], 80);
+
+        let sorted = query_map!(yaml, "ports", BTreeMap<String, u16>).unwrap();
+        assert_eq!(sorted.keys().collect::<Vec<_>>(), vec!["http", "https"]);
+
+        assert!(query_map!(yaml, "hosts", HashMap<String, String>).is_err());
+        assert!(query_map!(yaml, "hosts[*]", HashMap<String, String>).is_err());
+        assert!(query_map!(yaml, "missing", HashMap<String, u16>).is_err());
+    }
+
     #[test]
     fn test_query_multi_macro() {
         let json = r#"{"user": {"name": "Alice", "age": 30, "email": "alice@example.com"}}"#;
@@ -1203,4 +1543,44 @@ user:
         let missing_length = query_length!(json, "groups").unwrap();
         assert_eq!(missing_length, None);
     }
+
+    #[test]
+    fn test_keys_macro() {
+        let yaml = "config:\n  port: 80\n  host: localhost\ntags: [a, b, c]\nname: test\n";
+
+        assert_eq!(keys!(yaml, "config").unwrap(), vec!["host", "port"]);
+        assert_eq!(keys!(yaml, "tags").unwrap(), vec!["0", "1", "2"]);
+        assert!(keys!(yaml, "name").is_err());
+        assert!(keys!(yaml, "missing").is_err());
+        assert!(keys!(yaml, "tags[*]").is_err());
+    }
+
+    #[test]
+    fn test_entries_macro() {
+        let json = r#"{"servers": {"web": {"port": 80}, "db": {"port": 5432}}, "tags": ["a"]}"#;
+
+        let mut servers = entries!(json, "servers").unwrap();
+        servers.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            servers,
+            vec![
+                ("db".to_string(), json!({"port": 5432})),
+                ("web".to_string(), json!({"port": 80})),
+            ]
+        );
+
+        assert!(entries!(json, "tags").is_err());
+        assert!(entries!(json, "missing").is_err());
+        assert!(entries!(json, "servers[*]").is_err());
+    }
+
+    #[test]
+    fn test_exists_all_elements_macro() {
+        let yaml = "users:\n  - name: Alice\n    email: a@example.com\n  - name: Bob\n";
+
+        assert!(exists_all_elements!(yaml, "users[*].name").unwrap());
+        assert!(!exists_all_elements!(yaml, "users[*].email").unwrap());
+        assert!(exists!(yaml, "users[*].email").unwrap());
+        assert!(!exists_all_elements!(yaml, "groups[*].name").unwrap());
+    }
 }
@@ -1,4 +1,6 @@
 use super::expression::PathExpression;
+use crate::error::XqpathResult;
+use crate::parser::parsing::parse_path_expression;
 use crate::parser::path::PathSegment;
 
 /// 表达式复杂度分析（用于性能优化）
@@ -28,11 +30,12 @@ impl PathExpression {
     ) -> ExpressionComplexity {
         match self {
             PathExpression::Segments(segments) => {
-                let has_wildcards =
-                    segments.iter().any(|s| matches!(s, PathSegment::Wildcard));
-                let has_recursive_wildcards = segments
-                    .iter()
-                    .any(|s| matches!(s, PathSegment::RecursiveWildcard));
+                let has_wildcards = segments.iter().any(|s| {
+                    matches!(unwrap_optional(s), PathSegment::Wildcard)
+                });
+                let has_recursive_wildcards = segments.iter().any(|s| {
+                    matches!(unwrap_optional(s), PathSegment::RecursiveWildcard)
+                });
 
                 ExpressionComplexity {
                     depth: current_depth + 1,
@@ -171,7 +174,8 @@ impl PathExpression {
                 }
             }
 
-            PathExpression::Comparison { left, right, .. } => {
+            PathExpression::Comparison { left, right, .. }
+            | PathExpression::Arithmetic { left, right, .. } => {
                 let left_complexity =
                     left.analyze_complexity_with_depth(current_depth + 1);
                 let right_complexity =
@@ -274,3 +278,44 @@ impl PathExpression {
             || complexity.has_recursive_wildcards
     }
 }
+
+/// 去掉可选路径段的包装，返回实际的路径段
+fn unwrap_optional(segment: &PathSegment) -> &PathSegment {
+    match segment {
+        PathSegment::Optional(inner) => unwrap_optional(inner),
+        other => other,
+    }
+}
+
+/// 解析路径表达式并分析其复杂度，便于在大数据上执行前评估查询开销
+pub fn analyze_path_complexity(
+    path: &str,
+) -> XqpathResult<ExpressionComplexity> {
+    Ok(parse_path_expression(path)?.analyze_complexity())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_path_complexity() {
+        let complexity = analyze_path_complexity(".a | .b[*] | .c").unwrap();
+        assert_eq!(complexity.pipe_count, 2);
+        assert_eq!(complexity.comma_branches, 1);
+        assert!(complexity.has_wildcards);
+        assert!(!complexity.has_recursive_wildcards);
+
+        let complexity = analyze_path_complexity(".a, .b, .c").unwrap();
+        assert_eq!(complexity.comma_branches, 3);
+        assert_eq!(complexity.pipe_count, 0);
+
+        assert!(
+            analyze_path_complexity("**.name")
+                .unwrap()
+                .has_recursive_wildcards
+        );
+        assert!(analyze_path_complexity(".a[*]?").unwrap().has_wildcards);
+        assert!(analyze_path_complexity(".a[").is_err());
+    }
+}
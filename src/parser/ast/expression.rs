@@ -1,9 +1,11 @@
-use super::operators::{ComparisonOp, LogicalOp};
+use super::operators::{ArithmeticOp, ComparisonOp, LogicalOp};
 use crate::parser::path::PathSegment;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 /// 路径表达式抽象语法树
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PathExpression {
     /// 简单路径段序列（向后兼容原有语法）
     Segments(Vec<PathSegment>),
@@ -43,6 +45,13 @@ pub enum PathExpression {
         right: Box<PathExpression>,
     },
 
+    /// 算术操作: left op right
+    Arithmetic {
+        left: Box<PathExpression>,
+        op: ArithmeticOp,
+        right: Box<PathExpression>,
+    },
+
     /// 逻辑操作: left op right 或 not expr
     Logical {
         op: LogicalOp,
@@ -95,28 +104,117 @@ impl PathExpression {
         }
     }
 
+    /// 表达式（含所有子表达式）中是否调用了指定函数
+    pub fn calls_function(&self, function: &str) -> bool {
+        match self {
+            PathExpression::FunctionCall { name, args } => {
+                name == function
+                    || args.iter().any(|arg| arg.calls_function(function))
+            }
+            PathExpression::Pipe { left, right }
+            | PathExpression::Comparison { left, right, .. }
+            | PathExpression::Arithmetic { left, right, .. } => {
+                left.calls_function(function) || right.calls_function(function)
+            }
+            PathExpression::Comma(exprs)
+            | PathExpression::Logical {
+                operands: exprs, ..
+            } => exprs.iter().any(|e| e.calls_function(function)),
+            PathExpression::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+            } => {
+                condition.calls_function(function)
+                    || then_expr.calls_function(function)
+                    || else_expr
+                        .as_ref()
+                        .is_some_and(|e| e.calls_function(function))
+            }
+            PathExpression::TryCatch {
+                try_expr,
+                catch_expr,
+            } => {
+                try_expr.calls_function(function)
+                    || catch_expr
+                        .as_ref()
+                        .is_some_and(|e| e.calls_function(function))
+            }
+            PathExpression::Optional(expr) => expr.calls_function(function),
+            PathExpression::Segments(_)
+            | PathExpression::Literal(_)
+            | PathExpression::Identity => false,
+        }
+    }
+
     /// 获取表达式的字符串表示（用于调试）
     pub fn as_string(&self) -> String {
+        self.to_jq_string()
+    }
+
+    /// 将表达式还原为 jq 风格的源字符串，必要时添加括号以保证可被重新解析
+    pub fn to_jq_string(&self) -> String {
+        self.render(precedence::COMMA)
+    }
+
+    /// 表达式自身的优先级，与解析器的层级一一对应
+    fn precedence(&self) -> u8 {
         match self {
-            PathExpression::Segments(segments) => segments
-                .iter()
-                .map(|s| match s {
-                    PathSegment::Field(name) => format!(".{name}"),
-                    PathSegment::Index(idx) => format!("[{idx}]"),
-                    PathSegment::Wildcard => "*".to_string(),
-                    PathSegment::RecursiveWildcard => "**".to_string(),
-                    PathSegment::TypeFilter(typ) => format!("| {typ}"),
-                })
-                .collect::<Vec<_>>()
-                .join(""),
+            PathExpression::Comma(_) => precedence::COMMA,
+            PathExpression::Conditional { .. }
+            | PathExpression::TryCatch { .. } => precedence::CONDITIONAL,
+            PathExpression::Logical { op, .. } => match op {
+                LogicalOp::Or => precedence::OR,
+                LogicalOp::And => precedence::AND,
+                LogicalOp::Not => precedence::NOT,
+            },
+            PathExpression::Comparison { .. } => precedence::COMPARISON,
+            PathExpression::Pipe { .. } => precedence::PIPE,
+            PathExpression::Arithmetic { op, .. } => match op {
+                ArithmeticOp::Add | ArithmeticOp::Subtract => {
+                    precedence::ADDITIVE
+                }
+                ArithmeticOp::Multiply
+                | ArithmeticOp::Divide
+                | ArithmeticOp::Modulo => precedence::MULTIPLICATIVE,
+            },
+            PathExpression::Segments(_)
+            | PathExpression::Literal(_)
+            | PathExpression::Identity
+            | PathExpression::FunctionCall { .. }
+            | PathExpression::Optional(_) => precedence::PRIMARY,
+        }
+    }
 
-            PathExpression::Pipe { left, right } => {
-                format!("{} | {}", left.as_string(), right.as_string())
+    /// 在给定的最低优先级上下文中渲染，优先级不足时加括号
+    fn render(&self, min_precedence: u8) -> String {
+        let rendered = self.render_unwrapped();
+        if self.precedence() < min_precedence {
+            format!("({rendered})")
+        } else {
+            rendered
+        }
+    }
+
+    fn render_unwrapped(&self) -> String {
+        match self {
+            PathExpression::Segments(segments) => {
+                if segments.is_empty() {
+                    ".".to_string()
+                } else {
+                    segments.iter().map(|s| s.to_jq_string()).collect()
+                }
             }
 
+            PathExpression::Pipe { left, right } => format!(
+                "{} | {}",
+                left.render(precedence::PIPE),
+                right.render(precedence::ADDITIVE)
+            ),
+
             PathExpression::Comma(exprs) => exprs
                 .iter()
-                .map(|e| e.as_string())
+                .map(|e| e.render(precedence::CONDITIONAL))
                 .collect::<Vec<_>>()
                 .join(", "),
 
@@ -125,14 +223,18 @@ impl PathExpression {
 
             PathExpression::Identity => ".".to_string(),
 
+            PathExpression::FunctionCall { name, args }
+                if name.starts_with('@') && args.is_empty() =>
+            {
+                name.clone()
+            }
+
             PathExpression::FunctionCall { name, args } => {
-                if args.is_empty() {
-                    format!("{name}()")
-                } else {
-                    let arg_strings: Vec<String> =
-                        args.iter().map(|arg| arg.as_string()).collect();
-                    format!("{}({})", name, arg_strings.join(", "))
-                }
+                let arg_strings: Vec<String> = args
+                    .iter()
+                    .map(|arg| arg.render(precedence::COMMA))
+                    .collect();
+                format!("{}({})", name, arg_strings.join("; "))
             }
 
             PathExpression::Conditional {
@@ -140,20 +242,17 @@ impl PathExpression {
                 then_expr,
                 else_expr,
             } => {
+                let mut rendered = format!(
+                    "if {} then {}",
+                    condition.render(precedence::OR),
+                    then_expr.render(precedence::OR)
+                );
                 if let Some(else_expr) = else_expr {
-                    format!(
-                        "if {} then {} else {} end",
-                        condition.as_string(),
-                        then_expr.as_string(),
-                        else_expr.as_string()
-                    )
-                } else {
-                    format!(
-                        "if {} then {} end",
-                        condition.as_string(),
-                        then_expr.as_string()
-                    )
+                    rendered.push_str(" else ");
+                    rendered.push_str(&else_expr.render(precedence::OR));
                 }
+                rendered.push_str(" end");
+                rendered
             }
 
             PathExpression::Comparison { left, op, right } => {
@@ -165,28 +264,55 @@ impl PathExpression {
                     ComparisonOp::GreaterThan => ">",
                     ComparisonOp::GreaterThanOrEqual => ">=",
                 };
-                format!("{} {} {}", left.as_string(), op_str, right.as_string())
+                format!(
+                    "{} {} {}",
+                    left.render(precedence::COMPARISON),
+                    op_str,
+                    right.render(precedence::PIPE)
+                )
             }
 
-            PathExpression::Logical { op, operands } => match op {
-                LogicalOp::And => operands
-                    .iter()
-                    .map(|e| e.as_string())
-                    .collect::<Vec<_>>()
-                    .join(" and "),
-                LogicalOp::Or => operands
+            PathExpression::Arithmetic { left, op, right } => {
+                let op_str = match op {
+                    ArithmeticOp::Add => "+",
+                    ArithmeticOp::Subtract => "-",
+                    ArithmeticOp::Multiply => "*",
+                    ArithmeticOp::Divide => "/",
+                    ArithmeticOp::Modulo => "%",
+                };
+                // 左结合：右操作数需要更高的优先级
+                let precedence = self.precedence();
+                format!(
+                    "{} {} {}",
+                    left.render(precedence),
+                    op_str,
+                    right.render(precedence + 1)
+                )
+            }
+
+            PathExpression::Logical { op, operands } => {
+                let (keyword, first_prec, rest_prec) = match op {
+                    LogicalOp::And => ("and", precedence::AND, precedence::NOT),
+                    LogicalOp::Or => ("or", precedence::OR, precedence::AND),
+                    LogicalOp::Not => {
+                        return match operands.as_slice() {
+                            [operand] => format!(
+                                "not {}",
+                                operand.render(precedence::COMPARISON)
+                            ),
+                            _ => "not (invalid)".to_string(),
+                        };
+                    }
+                };
+                operands
                     .iter()
-                    .map(|e| e.as_string())
+                    .enumerate()
+                    .map(|(i, e)| {
+                        e.render(if i == 0 { first_prec } else { rest_prec })
+                    })
                     .collect::<Vec<_>>()
-                    .join(" or "),
-                LogicalOp::Not => {
-                    if operands.len() == 1 {
-                        format!("not {}", operands[0].as_string())
-                    } else {
-                        "not (invalid)".to_string()
-                    }
-                }
-            },
+                    .join(&format!(" {keyword} "))
+            }
 
             PathExpression::TryCatch {
                 try_expr,
@@ -195,23 +321,98 @@ impl PathExpression {
                 if let Some(catch_expr) = catch_expr {
                     format!(
                         "try {} catch {}",
-                        try_expr.as_string(),
-                        catch_expr.as_string()
+                        try_expr.render(precedence::OR),
+                        catch_expr.render(precedence::OR)
                     )
                 } else {
-                    format!("try {}", try_expr.as_string())
+                    format!("try {}", try_expr.render(precedence::OR))
                 }
             }
 
             PathExpression::Optional(expr) => {
-                format!("{}?", expr.as_string())
+                format!("{}?", expr.render(precedence::PRIMARY))
             }
         }
     }
 }
 
+/// 渲染时使用的优先级（数值越大结合越紧）
+mod precedence {
+    pub const COMMA: u8 = 0;
+    pub const CONDITIONAL: u8 = 1;
+    pub const OR: u8 = 2;
+    pub const AND: u8 = 3;
+    pub const NOT: u8 = 4;
+    pub const COMPARISON: u8 = 5;
+    pub const PIPE: u8 = 6;
+    pub const ADDITIVE: u8 = 7;
+    pub const MULTIPLICATIVE: u8 = 8;
+    pub const PRIMARY: u8 = 9;
+}
+
 impl std::fmt::Display for PathExpression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parsing::parse_path_expression;
+
+    #[test]
+    fn test_to_jq_string() {
+        let expr = parse_path_expression(".users[*].name | select(.age > 30)")
+            .unwrap();
+        assert_eq!(expr.to_jq_string(), ".users[*].name | select(.age > 30)");
+        assert_eq!(expr.to_string(), expr.to_jq_string());
+    }
+
+    #[test]
+    fn test_calls_function() {
+        let expr =
+            parse_path_expression("if .a then (.b | input()) else 1 end")
+                .unwrap();
+        assert!(expr.calls_function("input"));
+        assert!(!expr.calls_function("inputs"));
+        assert!(!parse_path_expression(".input")
+            .unwrap()
+            .calls_function("input"));
+    }
+
+    #[test]
+    fn test_to_jq_string_roundtrip() {
+        let sources = [
+            ".",
+            ".a.b[0]",
+            ".a | .b | .c",
+            ".a, .b | length",
+            ".a | (.b, .c)",
+            "(.a | .b) == 1",
+            ".a == (.b == .c)",
+            "not .a and (.b or .c)",
+            "if .a then .b else (if .c then 1 end) end",
+            "try .a catch \"fallback\"",
+            ".items | map(.x)?",
+            "(.a | .b)?",
+            "sort_by(.a; .b)",
+            "[1, 2]",
+            ".a + .b * 2",
+            "(.a + .b) * 2",
+            ".args | @sh",
+            ".a - (.b - 1)",
+            ".a / 2 % 3",
+            ".a | .b + 1",
+            "(.a | .b) + 1",
+            ".price * 1.5 > 10",
+        ];
+
+        for source in sources {
+            let expr = parse_path_expression(source).unwrap();
+            let rendered = expr.to_jq_string();
+            let reparsed = parse_path_expression(&rendered)
+                .unwrap_or_else(|e| panic!("{source} -> {rendered}: {e}"));
+            assert_eq!(reparsed, expr, "{source} -> {rendered}");
+        }
+    }
+}
@@ -2,6 +2,6 @@ pub mod complexity;
 pub mod expression;
 pub mod operators;
 
-pub use complexity::ExpressionComplexity;
+pub use complexity::{analyze_path_complexity, ExpressionComplexity};
 pub use expression::PathExpression;
-pub use operators::{ComparisonOp, LogicalOp};
+pub use operators::{ArithmeticOp, ComparisonOp, LogicalOp};
@@ -1,5 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 /// 比较操作符
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ComparisonOp {
     /// 等于 ==
     Equal,
@@ -15,8 +18,25 @@ pub enum ComparisonOp {
     GreaterThanOrEqual,
 }
 
+/// 算术操作符
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArithmeticOp {
+    /// 加法 +（字符串/数组拼接，对象合并）
+    Add,
+    /// 减法 -（数组移除元素，对象移除键）
+    Subtract,
+    /// 乘法 *
+    Multiply,
+    /// 除法 /
+    Divide,
+    /// 取模 %
+    Modulo,
+}
+
 /// 逻辑操作符
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum LogicalOp {
     /// 逻辑与 and / &&
     And,
@@ -0,0 +1,281 @@
+//! 算术操作求值 - 遵循 jq 的重载语义
+
+use crate::parser::{
+    ast::ArithmeticOp, evaluation::EvaluationError,
+    functions::basic::float_to_value,
+};
+use crate::value::json::JsonSupport;
+use crate::value::patch::remove_key;
+use serde_json::Value;
+
+/// 对两个值应用算术操作
+///
+/// - `+`：数字相加，字符串/数组拼接，对象浅合并（右侧优先），`null` 为单位元
+/// - `-`：数字相减，数组移除右侧出现的元素，对象移除给定的键
+/// - `*`：数字相乘，字符串乘以数字重复字符串，对象递归深度合并
+/// - `/`、`%`：数字运算；`/` 还可以用分隔符切分字符串
+pub fn apply_arithmetic(
+    left: &Value,
+    op: &ArithmeticOp,
+    right: &Value,
+) -> Result<Value, EvaluationError> {
+    match op {
+        ArithmeticOp::Add => add(left, right),
+        ArithmeticOp::Subtract => subtract(left, right),
+        ArithmeticOp::Multiply => multiply(left, right),
+        ArithmeticOp::Divide => divide(left, right),
+        ArithmeticOp::Modulo => modulo(left, right),
+    }
+}
+
+fn add(left: &Value, right: &Value) -> Result<Value, EvaluationError> {
+    match (left, right) {
+        (Value::Null, other) | (other, Value::Null) => Ok(other.clone()),
+        (Value::Number(_), Value::Number(_)) => {
+            Ok(numeric(left, right, i64::checked_add, |l, r| l + r))
+        }
+        (Value::String(l), Value::String(r)) => {
+            Ok(Value::String(format!("{l}{r}")))
+        }
+        (Value::Array(l), Value::Array(r)) => {
+            Ok(Value::Array(l.iter().chain(r).cloned().collect()))
+        }
+        (Value::Object(l), Value::Object(r)) => {
+            let mut merged = l.clone();
+            merged.extend(r.iter().map(|(k, v)| (k.clone(), v.clone())));
+            Ok(Value::Object(merged))
+        }
+        _ => Err(incompatible(left, right, "added")),
+    }
+}
+
+fn subtract(left: &Value, right: &Value) -> Result<Value, EvaluationError> {
+    match (left, right) {
+        (Value::Number(_), Value::Number(_)) => {
+            Ok(numeric(left, right, i64::checked_sub, |l, r| l - r))
+        }
+        (Value::Array(l), Value::Array(r)) => Ok(Value::Array(
+            l.iter().filter(|item| !r.contains(item)).cloned().collect(),
+        )),
+        (Value::Object(l), Value::String(key)) => {
+            let mut remaining = l.clone();
+            remove_key(&mut remaining, key);
+            Ok(Value::Object(remaining))
+        }
+        (Value::Object(l), Value::Array(keys))
+            if keys.iter().all(Value::is_string) =>
+        {
+            let mut remaining = l.clone();
+            for key in keys.iter().filter_map(Value::as_str) {
+                remove_key(&mut remaining, key);
+            }
+            Ok(Value::Object(remaining))
+        }
+        _ => Err(incompatible(left, right, "subtracted")),
+    }
+}
+
+/// 字符串重复结果的最大字节数
+const MAX_REPEAT_BYTES: usize = 1 << 28;
+
+/// 字符串乘以数字：与 jq 一致，次数不大于 0（或为 NaN）时返回 null，
+/// 小数次数向下取整且至少一次；结果超过 [`MAX_REPEAT_BYTES`] 时报错
+fn repeat_string(s: &str, times: f64) -> Result<Value, EvaluationError> {
+    if times.is_nan() || times <= 0.0 {
+        return Ok(Value::Null);
+    }
+
+    // 浮点数转换为 usize 时饱和，超大次数不会回绕
+    let count = (times as usize).max(1);
+    match s.len().checked_mul(count) {
+        Some(len) if len <= MAX_REPEAT_BYTES => {
+            Ok(Value::String(s.repeat(count)))
+        }
+        _ => Err(EvaluationError::Message(format!(
+            "Repeating a string of {} bytes {times} times exceeds the \
+             {MAX_REPEAT_BYTES} byte limit",
+            s.len()
+        ))),
+    }
+}
+
+fn multiply(left: &Value, right: &Value) -> Result<Value, EvaluationError> {
+    match (left, right) {
+        (Value::Number(_), Value::Number(_)) => {
+            Ok(numeric(left, right, i64::checked_mul, |l, r| l * r))
+        }
+        (Value::String(s), Value::Number(n))
+        | (Value::Number(n), Value::String(s)) => {
+            repeat_string(s, n.as_f64().unwrap_or(0.0))
+        }
+        (Value::Object(_), Value::Object(_)) => Ok(deep_merge(left, right)),
+        _ => Err(incompatible(left, right, "multiplied")),
+    }
+}
+
+/// 递归合并两个对象：两侧同名键都是对象时继续合并，否则右侧覆盖左侧
+fn deep_merge(left: &Value, right: &Value) -> Value {
+    match (left, right) {
+        (Value::Object(l), Value::Object(r)) => {
+            let mut merged = l.clone();
+            for (key, value) in r {
+                let merged_value = match merged.get(key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => value.clone(),
+                };
+                merged.insert(key.clone(), merged_value);
+            }
+            Value::Object(merged)
+        }
+        _ => right.clone(),
+    }
+}
+
+fn divide(left: &Value, right: &Value) -> Result<Value, EvaluationError> {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => {
+            let divisor = r.as_f64().unwrap_or(0.0);
+            if divisor == 0.0 {
+                return Err(EvaluationError::Message(format!(
+                    "{l} and {r} cannot be divided because the divisor is zero"
+                )));
+            }
+            Ok(float_to_value(l.as_f64().unwrap_or(0.0) / divisor))
+        }
+        (Value::String(l), Value::String(r)) => {
+            let parts: Vec<Value> = if l.is_empty() {
+                vec![]
+            } else {
+                l.split(r.as_str()).map(Value::from).collect()
+            };
+            Ok(Value::Array(parts))
+        }
+        _ => Err(incompatible(left, right, "divided")),
+    }
+}
+
+fn modulo(left: &Value, right: &Value) -> Result<Value, EvaluationError> {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => {
+            // 与 jq 一致，取模前截断为整数
+            let dividend = l.as_f64().unwrap_or(0.0) as i64;
+            let divisor = r.as_f64().unwrap_or(0.0) as i64;
+            if divisor == 0 {
+                return Err(EvaluationError::Message(format!(
+                    "{l} and {r} cannot be divided because the divisor is zero"
+                )));
+            }
+            Ok(Value::from(dividend.wrapping_rem(divisor)))
+        }
+        _ => Err(incompatible(left, right, "divided")),
+    }
+}
+
+/// 数字运算：两侧均为整数且不溢出时保持整数，否则按浮点计算
+fn numeric(
+    left: &Value,
+    right: &Value,
+    integer_op: fn(i64, i64) -> Option<i64>,
+    float_op: fn(f64, f64) -> f64,
+) -> Value {
+    if let (Some(l), Some(r)) = (left.as_i64(), right.as_i64()) {
+        if let Some(result) = integer_op(l, r) {
+            return Value::from(result);
+        }
+    }
+    let l = left.as_f64().unwrap_or(0.0);
+    let r = right.as_f64().unwrap_or(0.0);
+    float_to_value(float_op(l, r))
+}
+
+fn incompatible(left: &Value, right: &Value, verb: &str) -> EvaluationError {
+    EvaluationError::Message(format!(
+        "{} ({}) and {} ({}) cannot be {verb}",
+        JsonSupport::get_type_name(left),
+        left,
+        JsonSupport::get_type_name(right),
+        right
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_add() {
+        let add = |l, r| apply_arithmetic(&l, &ArithmeticOp::Add, &r).unwrap();
+        assert_eq!(add(json!(1), json!(2)), json!(3));
+        assert_eq!(add(json!(1.5), json!(2)), json!(3.5));
+        assert_eq!(add(json!("a"), json!("b")), json!("ab"));
+        assert_eq!(add(json!([1]), json!([2])), json!([1, 2]));
+        assert_eq!(
+            add(json!({"a": 1, "b": 1}), json!({"b": 2})),
+            json!({"a": 1, "b": 2})
+        );
+        assert_eq!(add(json!(null), json!("x")), json!("x"));
+        assert!(apply_arithmetic(&json!("a"), &ArithmeticOp::Add, &json!(1))
+            .is_err());
+    }
+
+    #[test]
+    fn test_multiply() {
+        let mul =
+            |l, r| apply_arithmetic(&l, &ArithmeticOp::Multiply, &r).unwrap();
+        assert_eq!(mul(json!(3), json!(4)), json!(12));
+        assert_eq!(mul(json!(0.5), json!(3)), json!(1.5));
+        assert_eq!(mul(json!("ab"), json!(3)), json!("ababab"));
+        assert_eq!(mul(json!(2), json!("x")), json!("xx"));
+        assert_eq!(mul(json!("ab"), json!(0)), json!(null));
+        assert_eq!(
+            mul(
+                json!({"a": {"b": 1, "c": {"d": 1}}, "e": 1}),
+                json!({"a": {"c": {"f": 2}}, "e": {"g": 3}})
+            ),
+            json!({"a": {"b": 1, "c": {"d": 1, "f": 2}}, "e": {"g": 3}})
+        );
+        assert!(apply_arithmetic(
+            &json!([1]),
+            &ArithmeticOp::Multiply,
+            &json!(2)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_subtract_divide_modulo() {
+        let apply = |l, op, r| apply_arithmetic(&l, &op, &r);
+        assert_eq!(
+            apply(json!([1, 2, 3, 2]), ArithmeticOp::Subtract, json!([2]))
+                .unwrap(),
+            json!([1, 3])
+        );
+        assert_eq!(
+            apply(json!({"a": 1, "b": 2}), ArithmeticOp::Subtract, json!("a"))
+                .unwrap(),
+            json!({"b": 2})
+        );
+        assert_eq!(
+            apply(json!(7), ArithmeticOp::Divide, json!(2)).unwrap(),
+            json!(3.5)
+        );
+        assert_eq!(
+            apply(json!("a,b"), ArithmeticOp::Divide, json!(",")).unwrap(),
+            json!(["a", "b"])
+        );
+        assert_eq!(
+            apply(json!(7.9), ArithmeticOp::Modulo, json!(3)).unwrap(),
+            json!(1)
+        );
+        assert!(apply(json!(1), ArithmeticOp::Divide, json!(0)).is_err());
+        assert!(apply(json!(1), ArithmeticOp::Modulo, json!(0)).is_err());
+        // 溢出时退回浮点运算
+        assert_eq!(
+            apply(json!(i64::MAX), ArithmeticOp::Subtract, json!(-1))
+                .unwrap()
+                .as_f64(),
+            Some(i64::MAX as f64 + 1.0)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::{XqpathError, XqpathResult};
 use crate::parser::{
     ast::{ComparisonOp, LogicalOp, PathExpression},
     functions::FunctionRegistry,
@@ -5,12 +6,16 @@ use crate::parser::{
 };
 use serde_json::Value;
 use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::sync::Mutex;
 
-use super::error::EvaluationError;
+use super::{arithmetic::apply_arithmetic, error::EvaluationError};
 
 /// 表达式求值器
 pub struct ExpressionEvaluator {
     function_registry: FunctionRegistry,
+    /// 供 `input`/`inputs` 读取的后续输入文档
+    inputs: Mutex<VecDeque<Value>>,
 }
 
 impl Default for ExpressionEvaluator {
@@ -22,11 +27,41 @@ impl Default for ExpressionEvaluator {
 impl ExpressionEvaluator {
     /// 创建新的求值器
     pub fn new() -> Self {
+        Self::with_function_registry(FunctionRegistry::new())
+    }
+
+    /// 使用自定义函数注册表创建求值器
+    pub fn with_function_registry(function_registry: FunctionRegistry) -> Self {
         Self {
-            function_registry: FunctionRegistry::new(),
+            function_registry,
+            inputs: Mutex::new(VecDeque::new()),
         }
     }
 
+    /// 设置 `input`/`inputs` 可读取的输入文档队列
+    pub fn with_inputs(self, inputs: impl IntoIterator<Item = Value>) -> Self {
+        *self.inputs.lock().unwrap_or_else(|e| e.into_inner()) =
+            inputs.into_iter().collect();
+        self
+    }
+
+    /// 取出下一个尚未消费的输入文档
+    pub fn next_input(&self) -> Option<Value> {
+        self.inputs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front()
+    }
+
+    /// 取出全部剩余的输入文档
+    pub fn remaining_inputs(&self) -> Vec<Value> {
+        self.inputs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain(..)
+            .collect()
+    }
+
     /// 对给定值评估路径表达式
     pub fn evaluate(
         &self,
@@ -139,6 +174,24 @@ impl ExpressionEvaluator {
                 Ok(vec![Value::Bool(result)])
             }
 
+            PathExpression::Arithmetic { left, op, right } => {
+                // 算术操作：与 jq 一致，对两侧结果做笛卡尔积
+                let left_results = self.evaluate(left, value)?;
+                let right_results = self.evaluate(right, value)?;
+
+                let mut results = Vec::new();
+                for right_value in &right_results {
+                    for left_value in &left_results {
+                        results.push(apply_arithmetic(
+                            left_value,
+                            op,
+                            right_value,
+                        )?);
+                    }
+                }
+                Ok(results)
+            }
+
             PathExpression::Logical { op, operands } => {
                 // 逻辑操作：operand1 op operand2 或 not operand
                 match op {
@@ -221,6 +274,88 @@ impl ExpressionEvaluator {
         }
     }
 
+    /// 评估表达式，但最多产生 `limit` 个结果
+    ///
+    /// 管道与逗号在凑够结果后立即停止求值后续分支，
+    /// 供 `first(expr)`、`nth(n; expr)` 等只需要前几个结果的函数提前终止。
+    pub fn evaluate_limited(
+        &self,
+        expression: &PathExpression,
+        value: &Value,
+        limit: usize,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let mut results = Vec::new();
+        self.evaluate_into(expression, value, limit, &mut results)?;
+        Ok(results)
+    }
+
+    /// 将结果追加到 `out`，直到 `out` 中有 `limit` 个值
+    fn evaluate_into(
+        &self,
+        expression: &PathExpression,
+        value: &Value,
+        limit: usize,
+        out: &mut Vec<Value>,
+    ) -> Result<(), EvaluationError> {
+        if out.len() >= limit {
+            return Ok(());
+        }
+
+        match expression {
+            PathExpression::Pipe { .. } => {
+                // 左结合的管道链展开为各阶段，逐个值向后传递
+                let mut stages = Vec::new();
+                let mut current = expression;
+                while let PathExpression::Pipe { left, right } = current {
+                    stages.push(right.as_ref());
+                    current = left;
+                }
+                stages.push(current);
+                stages.reverse();
+                self.evaluate_stages(&stages, value, limit, out)
+            }
+            PathExpression::Comma(expressions) => {
+                for expr in expressions {
+                    self.evaluate_into(expr, value, limit, out)?;
+                    if out.len() >= limit {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+            _ => {
+                let remaining = limit - out.len();
+                out.extend(
+                    self.evaluate(expression, value)?
+                        .into_iter()
+                        .take(remaining),
+                );
+                Ok(())
+            }
+        }
+    }
+
+    fn evaluate_stages(
+        &self,
+        stages: &[&PathExpression],
+        value: &Value,
+        limit: usize,
+        out: &mut Vec<Value>,
+    ) -> Result<(), EvaluationError> {
+        let (first, rest) = stages.split_first().expect("non-empty pipeline");
+        if rest.is_empty() {
+            return self.evaluate_into(first, value, limit, out);
+        }
+
+        for intermediate in self.evaluate(first, value)? {
+            self.evaluate_stages(rest, &intermediate, limit, out)?;
+            if out.len() >= limit {
+                break;
+            }
+        }
+        Ok(())
+    }
+
     /// 判断值是否为真值（jq-style truthiness）
     pub fn is_truthy(&self, value: &Value) -> bool {
         match value {
@@ -361,6 +496,11 @@ impl ExpressionEvaluator {
                     Ok(vec![])
                 }
             }
+
+            PathSegment::Optional(inner) => {
+                // 可选路径段，出错时返回空结果
+                Ok(Self::evaluate_segment(inner, value).unwrap_or_default())
+            }
         }
     }
 
@@ -403,9 +543,11 @@ impl ExpressionEvaluator {
 pub fn evaluate_path_expression(
     expression: &PathExpression,
     value: &Value,
-) -> Result<Vec<Value>, EvaluationError> {
+) -> XqpathResult<Vec<Value>> {
     let evaluator = ExpressionEvaluator::new();
-    evaluator.evaluate(expression, value)
+    evaluator
+        .evaluate(expression, value)
+        .map_err(XqpathError::from)
 }
 
 #[cfg(test)]
@@ -1,8 +1,22 @@
+use super::basic::float_to_value;
 use super::AdvancedBuiltinFunction;
+#[cfg(feature = "update")]
+use crate::parser::path::PathSegment;
 use crate::parser::{EvaluationError, ExpressionEvaluator, PathExpression};
+#[cfg(feature = "update")]
+use crate::updater::Updater;
+use crate::value::json::JsonSupport;
+#[cfg(feature = "update")]
+use crate::value::stream::locate_paths;
+use crate::value::stream::{leaf_paths, path_to_value, walk_paths};
 use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 
-/// map 函数 - 对数组每个元素应用表达式
+/// map 函数 - 对数组每个元素（或对象的每个值）应用表达式
+///
+/// 与 jq 的 `[.[] | f]` 一致：对象按键的顺序遍历值，结果总是数组。
 pub struct MapFunction;
 
 impl AdvancedBuiltinFunction for MapFunction {
@@ -23,23 +37,82 @@ impl AdvancedBuiltinFunction for MapFunction {
             ));
         }
 
+        let items: Vec<&Value> = match input {
+            Value::Array(arr) => arr.iter().collect(),
+            Value::Object(map) => map.values().collect(),
+            other => {
+                return Err(EvaluationError::InvalidArguments(format!(
+                    "map can only be applied to arrays or objects, got {}",
+                    JsonSupport::get_type_name(other)
+                )))
+            }
+        };
+
+        let mut results = Vec::new();
+        for item in items {
+            results.extend(evaluator.evaluate(&args[0], item)?);
+        }
+        Ok(vec![Value::Array(results)])
+    }
+
+    fn description(&self) -> &str {
+        "Applies an expression to each element of an array (or value of an object) and collects the results"
+    }
+}
+
+/// map_values 函数 - 对对象的每个值（或数组的每个元素）应用表达式，保留原有键
+///
+/// 每个值只取表达式的第一个结果，无结果时移除该键或元素。
+pub struct MapValuesFunction;
+
+impl AdvancedBuiltinFunction for MapValuesFunction {
+    fn name(&self) -> &str {
+        "map_values"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if args.len() != 1 {
+            return Err(EvaluationError::InvalidArguments(
+                "map_values function takes exactly one expression argument"
+                    .to_string(),
+            ));
+        }
+
         match input {
+            Value::Object(map) => {
+                let mut results = serde_json::Map::new();
+                for (key, value) in map {
+                    let value_results = evaluator.evaluate(&args[0], value)?;
+                    if let Some(first) = value_results.into_iter().next() {
+                        results.insert(key.clone(), first);
+                    }
+                }
+                Ok(vec![Value::Object(results)])
+            }
             Value::Array(arr) => {
                 let mut results = Vec::new();
                 for item in arr {
                     let item_results = evaluator.evaluate(&args[0], item)?;
-                    results.extend(item_results);
+                    if let Some(first) = item_results.into_iter().next() {
+                        results.push(first);
+                    }
                 }
                 Ok(vec![Value::Array(results)])
             }
             _ => Err(EvaluationError::InvalidArguments(
-                "map can only be applied to arrays".to_string(),
+                "map_values can only be applied to objects or arrays"
+                    .to_string(),
             )),
         }
     }
 
     fn description(&self) -> &str {
-        "Applies an expression to each element of an array and collects the results"
+        "Applies an expression to each value of an object or array, keeping the keys"
     }
 }
 
@@ -101,6 +174,167 @@ impl AdvancedBuiltinFunction for SelectFunction {
     }
 }
 
+/// 计算数组每个元素的真值：无参数时判断元素本身，有参数时判断条件表达式的第一个结果
+fn element_truthiness(
+    function_name: &str,
+    args: &[PathExpression],
+    evaluator: &ExpressionEvaluator,
+    input: &Value,
+) -> Result<Vec<bool>, EvaluationError> {
+    if args.len() > 1 {
+        return Err(EvaluationError::InvalidArguments(format!(
+            "{function_name} function takes at most one expression argument"
+        )));
+    }
+
+    let Value::Array(arr) = input else {
+        return Err(EvaluationError::InvalidArguments(format!(
+            "{function_name} can only be applied to arrays"
+        )));
+    };
+
+    arr.iter()
+        .map(|item| match args.first() {
+            Some(condition) => Ok(evaluator
+                .evaluate(condition, item)?
+                .first()
+                .is_some_and(|v| evaluator.is_truthy(v))),
+            None => Ok(evaluator.is_truthy(item)),
+        })
+        .collect()
+}
+
+/// any 函数 - 判断数组中是否存在真值元素（或满足条件的元素），空数组返回 false
+pub struct AnyFunction;
+
+impl AdvancedBuiltinFunction for AnyFunction {
+    fn name(&self) -> &str {
+        "any"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let results = element_truthiness("any", args, evaluator, input)?;
+        Ok(vec![Value::Bool(results.into_iter().any(|b| b))])
+    }
+
+    fn description(&self) -> &str {
+        "Returns true if any array element (or condition result) is truthy"
+    }
+}
+
+/// all 函数 - 判断数组元素是否全部为真值（或全部满足条件），空数组返回 true
+pub struct AllFunction;
+
+impl AdvancedBuiltinFunction for AllFunction {
+    fn name(&self) -> &str {
+        "all"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let results = element_truthiness("all", args, evaluator, input)?;
+        Ok(vec![Value::Bool(results.into_iter().all(|b| b))])
+    }
+
+    fn description(&self) -> &str {
+        "Returns true if all array elements (or condition results) are truthy"
+    }
+}
+
+/// 排序使用的值比较：null 最小，同类型的数字、字符串、布尔值按自然顺序比较
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(n1), Value::Number(n2)) => n1
+            .as_f64()
+            .unwrap_or(0.0)
+            .partial_cmp(&n2.as_f64().unwrap_or(0.0))
+            .unwrap_or(Ordering::Equal),
+        (Value::String(s1), Value::String(s2)) => s1.cmp(s2),
+        (Value::Bool(b1), Value::Bool(b2)) => b1.cmp(b2),
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Less,
+        (_, Value::Null) => Ordering::Greater,
+        _ => Ordering::Equal,
+    }
+}
+
+/// 计算元素的排序键向量：依次收集所有键表达式的全部结果
+///
+/// `sort_by(.a, .b)` 与 `sort_by(.a; .b)` 都会得到 `[.a, .b]`。
+fn sort_key_vector(
+    key_exprs: &[PathExpression],
+    evaluator: &ExpressionEvaluator,
+    item: &Value,
+) -> Result<Vec<Value>, EvaluationError> {
+    let mut keys = Vec::new();
+    for expr in key_exprs {
+        keys.extend(evaluator.evaluate(expr, item)?);
+    }
+    Ok(keys)
+}
+
+/// 按字典序逐项比较排序键向量，前缀较短者在前
+fn compare_key_vectors(a: &[Value], b: &[Value]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| compare_values(x, y))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+/// 将分组键转换为用于判等的字符串
+fn group_key_string(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "null".to_string(),
+        _ => {
+            serde_json::to_string(key).unwrap_or_else(|_| "unknown".to_string())
+        }
+    }
+}
+
+/// 按键表达式分组，返回按键排序的 `(键, 元素)` 列表
+///
+/// 每个元素的键只求值一次并随分组保存，排序直接使用保存的键。
+fn group_items(
+    key_expr: &PathExpression,
+    evaluator: &ExpressionEvaluator,
+    arr: &[Value],
+) -> Result<Vec<(Value, Vec<Value>)>, EvaluationError> {
+    let mut groups: Vec<(Value, Vec<Value>)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for item in arr {
+        let key = evaluator
+            .evaluate(key_expr, item)?
+            .into_iter()
+            .next()
+            .unwrap_or(Value::Null);
+
+        match positions.entry(group_key_string(&key)) {
+            Entry::Occupied(entry) => groups[*entry.get()].1.push(item.clone()),
+            Entry::Vacant(entry) => {
+                entry.insert(groups.len());
+                groups.push((key, vec![item.clone()]));
+            }
+        }
+    }
+
+    groups.sort_by(|a, b| compare_values(&a.0, &b.0));
+    Ok(groups)
+}
+
 /// sort 函数 - 简单排序数组
 pub struct SortFunction;
 
@@ -124,23 +358,7 @@ impl AdvancedBuiltinFunction for SortFunction {
         match input {
             Value::Array(arr) => {
                 let mut sorted_arr = arr.clone();
-                sorted_arr.sort_by(|a, b| {
-                    // 简单的排序逻辑，按值类型优先级排序
-                    use std::cmp::Ordering;
-                    match (a, b) {
-                        (Value::Number(n1), Value::Number(n2)) => n1
-                            .as_f64()
-                            .unwrap_or(0.0)
-                            .partial_cmp(&n2.as_f64().unwrap_or(0.0))
-                            .unwrap_or(Ordering::Equal),
-                        (Value::String(s1), Value::String(s2)) => s1.cmp(s2),
-                        (Value::Bool(b1), Value::Bool(b2)) => b1.cmp(b2),
-                        (Value::Null, Value::Null) => Ordering::Equal,
-                        (Value::Null, _) => Ordering::Less,
-                        (_, Value::Null) => Ordering::Greater,
-                        _ => Ordering::Equal,
-                    }
-                });
+                sorted_arr.sort_by(compare_values);
                 Ok(vec![Value::Array(sorted_arr)])
             }
             _ => Err(EvaluationError::InvalidArguments(
@@ -168,42 +386,25 @@ impl AdvancedBuiltinFunction for SortByFunction {
         evaluator: &ExpressionEvaluator,
         input: &Value,
     ) -> Result<Vec<Value>, EvaluationError> {
-        if args.len() != 1 {
+        if args.is_empty() {
             return Err(EvaluationError::InvalidArguments(
-                "sort_by function takes exactly one expression argument"
+                "sort_by function takes at least one expression argument"
                     .to_string(),
             ));
         }
 
         match input {
             Value::Array(arr) => {
-                let mut indexed_items: Vec<(Value, Value)> = Vec::new();
+                let mut indexed_items: Vec<(Value, Vec<Value>)> = Vec::new();
 
                 // 计算每个元素的排序键
                 for item in arr {
-                    let key_results = evaluator.evaluate(&args[0], item)?;
-                    let sort_key =
-                        key_results.first().cloned().unwrap_or(Value::Null);
+                    let sort_key = sort_key_vector(args, evaluator, item)?;
                     indexed_items.push((item.clone(), sort_key));
                 }
 
                 // 按排序键排序
-                indexed_items.sort_by(|a, b| {
-                    use std::cmp::Ordering;
-                    match (&a.1, &b.1) {
-                        (Value::Number(n1), Value::Number(n2)) => n1
-                            .as_f64()
-                            .unwrap_or(0.0)
-                            .partial_cmp(&n2.as_f64().unwrap_or(0.0))
-                            .unwrap_or(Ordering::Equal),
-                        (Value::String(s1), Value::String(s2)) => s1.cmp(s2),
-                        (Value::Bool(b1), Value::Bool(b2)) => b1.cmp(b2),
-                        (Value::Null, Value::Null) => Ordering::Equal,
-                        (Value::Null, _) => Ordering::Less,
-                        (_, Value::Null) => Ordering::Greater,
-                        _ => Ordering::Equal,
-                    }
-                });
+                indexed_items.sort_by(|a, b| compare_key_vectors(&a.1, &b.1));
 
                 let sorted_items: Vec<Value> =
                     indexed_items.into_iter().map(|(item, _)| item).collect();
@@ -216,7 +417,175 @@ impl AdvancedBuiltinFunction for SortByFunction {
     }
 
     fn description(&self) -> &str {
-        "Sorts array elements by the result of applying the given expression"
+        "Sorts array elements by the results of one or more key expressions"
+    }
+}
+
+/// sort_desc 函数 - 一次排序得到降序数组（相等元素保持原有顺序）
+pub struct SortDescFunction;
+
+impl AdvancedBuiltinFunction for SortDescFunction {
+    fn name(&self) -> &str {
+        "sort_desc"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        _evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if !args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "sort_desc function takes no arguments".to_string(),
+            ));
+        }
+
+        match input {
+            Value::Array(arr) => {
+                let mut sorted_arr = arr.clone();
+                sorted_arr.sort_by(|a, b| compare_values(b, a));
+                Ok(vec![Value::Array(sorted_arr)])
+            }
+            _ => Err(EvaluationError::InvalidArguments(
+                "sort_desc can only be applied to arrays".to_string(),
+            )),
+        }
+    }
+
+    fn description(&self) -> &str {
+        "Sorts array elements in descending order"
+    }
+}
+
+/// sort_by_desc 函数 - 按表达式结果降序排序
+pub struct SortByDescFunction;
+
+impl AdvancedBuiltinFunction for SortByDescFunction {
+    fn name(&self) -> &str {
+        "sort_by_desc"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "sort_by_desc function takes at least one expression argument"
+                    .to_string(),
+            ));
+        }
+
+        match input {
+            Value::Array(arr) => {
+                let mut keyed_items = arr
+                    .iter()
+                    .map(|item| {
+                        Ok((sort_key_vector(args, evaluator, item)?, item))
+                    })
+                    .collect::<Result<Vec<_>, EvaluationError>>()?;
+
+                keyed_items.sort_by(|a, b| compare_key_vectors(&b.0, &a.0));
+
+                Ok(vec![Value::Array(
+                    keyed_items
+                        .into_iter()
+                        .map(|(_, item)| item.clone())
+                        .collect(),
+                )])
+            }
+            _ => Err(EvaluationError::InvalidArguments(
+                "sort_by_desc can only be applied to arrays".to_string(),
+            )),
+        }
+    }
+
+    fn description(&self) -> &str {
+        "Sorts array elements by one or more key expressions in descending order"
+    }
+}
+
+/// 选出键最小（`Ordering::Less`）或最大（`Ordering::Greater`）的元素，平局取第一个
+fn select_extreme_by(
+    function_name: &str,
+    target: Ordering,
+    args: &[PathExpression],
+    evaluator: &ExpressionEvaluator,
+    input: &Value,
+) -> Result<Vec<Value>, EvaluationError> {
+    if args.is_empty() {
+        return Err(EvaluationError::InvalidArguments(format!(
+            "{function_name} function takes at least one expression argument"
+        )));
+    }
+
+    let Value::Array(arr) = input else {
+        return Err(EvaluationError::InvalidArguments(format!(
+            "{function_name} can only be applied to arrays"
+        )));
+    };
+
+    let mut best: Option<(Vec<Value>, &Value)> = None;
+    for item in arr {
+        let key = sort_key_vector(args, evaluator, item)?;
+        let replace = match &best {
+            Some((best_key, _)) => {
+                compare_key_vectors(&key, best_key) == target
+            }
+            None => true,
+        };
+        if replace {
+            best = Some((key, item));
+        }
+    }
+
+    Ok(vec![best.map_or(Value::Null, |(_, item)| item.clone())])
+}
+
+/// min_by 函数 - 返回键最小的元素，空数组返回 null
+pub struct MinByFunction;
+
+impl AdvancedBuiltinFunction for MinByFunction {
+    fn name(&self) -> &str {
+        "min_by"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        select_extreme_by("min_by", Ordering::Less, args, evaluator, input)
+    }
+
+    fn description(&self) -> &str {
+        "Returns the array element with the smallest key, or null if empty"
+    }
+}
+
+/// max_by 函数 - 返回键最大的元素，空数组返回 null
+pub struct MaxByFunction;
+
+impl AdvancedBuiltinFunction for MaxByFunction {
+    fn name(&self) -> &str {
+        "max_by"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        select_extreme_by("max_by", Ordering::Greater, args, evaluator, input)
+    }
+
+    fn description(&self) -> &str {
+        "Returns the array element with the largest key, or null if empty"
     }
 }
 
@@ -243,75 +612,10 @@ impl AdvancedBuiltinFunction for GroupByFunction {
 
         match input {
             Value::Array(arr) => {
-                let mut groups: std::collections::HashMap<String, Vec<Value>> =
-                    std::collections::HashMap::new();
-
-                // 按分组键分组
-                for item in arr {
-                    let key_results = evaluator.evaluate(&args[0], item)?;
-                    let group_key =
-                        key_results.first().cloned().unwrap_or(Value::Null);
-                    let key_str = match group_key {
-                        Value::String(s) => s,
-                        Value::Number(n) => n.to_string(),
-                        Value::Bool(b) => b.to_string(),
-                        Value::Null => "null".to_string(),
-                        _ => serde_json::to_string(&group_key)
-                            .unwrap_or_else(|_| "unknown".to_string()),
-                    };
-
-                    groups.entry(key_str).or_default().push(item.clone());
-                }
-
-                // 转换为分组数组
-                let mut group_arrays: Vec<Value> =
-                    groups.into_values().map(Value::Array).collect();
-                group_arrays.sort_by(|a, b| {
-                    // 按第一个元素的分组键排序
-                    match (a, b) {
-                        (Value::Array(arr1), Value::Array(arr2)) => {
-                            if let (Some(first1), Some(first2)) =
-                                (arr1.first(), arr2.first())
-                            {
-                                let key1 = evaluator
-                                    .evaluate(&args[0], first1)
-                                    .ok()
-                                    .and_then(|results| {
-                                        results.first().cloned()
-                                    })
-                                    .unwrap_or(Value::Null);
-                                let key2 = evaluator
-                                    .evaluate(&args[0], first2)
-                                    .ok()
-                                    .and_then(|results| {
-                                        results.first().cloned()
-                                    })
-                                    .unwrap_or(Value::Null);
-
-                                match (&key1, &key2) {
-                                    (Value::String(s1), Value::String(s2)) => {
-                                        s1.cmp(s2)
-                                    }
-                                    (Value::Number(n1), Value::Number(n2)) => {
-                                        n1.as_f64()
-                                            .unwrap_or(0.0)
-                                            .partial_cmp(
-                                                &n2.as_f64().unwrap_or(0.0),
-                                            )
-                                            .unwrap_or(
-                                                std::cmp::Ordering::Equal,
-                                            )
-                                    }
-                                    _ => std::cmp::Ordering::Equal,
-                                }
-                            } else {
-                                std::cmp::Ordering::Equal
-                            }
-                        }
-                        _ => std::cmp::Ordering::Equal,
-                    }
-                });
-
+                let group_arrays = group_items(&args[0], evaluator, arr)?
+                    .into_iter()
+                    .map(|(_, items)| Value::Array(items))
+                    .collect();
                 Ok(vec![Value::Array(group_arrays)])
             }
             _ => Err(EvaluationError::InvalidArguments(
@@ -325,6 +629,48 @@ impl AdvancedBuiltinFunction for GroupByFunction {
     }
 }
 
+/// count_by 函数 - 按表达式结果分组并统计每组数量，返回 `{键: 数量}` 对象
+pub struct CountByFunction;
+
+impl AdvancedBuiltinFunction for CountByFunction {
+    fn name(&self) -> &str {
+        "count_by"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if args.len() != 1 {
+            return Err(EvaluationError::InvalidArguments(
+                "count_by function takes exactly one expression argument"
+                    .to_string(),
+            ));
+        }
+
+        match input {
+            Value::Array(arr) => {
+                let counts = group_items(&args[0], evaluator, arr)?
+                    .into_iter()
+                    .map(|(key, items)| {
+                        (group_key_string(&key), Value::from(items.len()))
+                    })
+                    .collect();
+                Ok(vec![Value::Object(counts)])
+            }
+            _ => Err(EvaluationError::InvalidArguments(
+                "count_by can only be applied to arrays".to_string(),
+            )),
+        }
+    }
+
+    fn description(&self) -> &str {
+        "Counts array elements per group key, returning an object of counts"
+    }
+}
+
 /// unique 函数 - 去除重复元素
 pub struct UniqueFunction;
 
@@ -457,3 +803,444 @@ impl AdvancedBuiltinFunction for ReverseFunction {
         "Reverses the order of elements in an array"
     }
 }
+
+/// paths 函数 - 列出所有节点的路径，带参数时只保留满足条件的节点
+pub struct PathsFunction;
+
+impl AdvancedBuiltinFunction for PathsFunction {
+    fn name(&self) -> &str {
+        "paths"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if args.len() > 1 {
+            return Err(EvaluationError::InvalidArguments(
+                "paths function takes at most one filter expression"
+                    .to_string(),
+            ));
+        }
+
+        let mut results = Vec::new();
+        for (path, node) in walk_paths(input) {
+            if let Some(filter) = args.first() {
+                let is_match = evaluator
+                    .evaluate(filter, node)?
+                    .first()
+                    .is_some_and(|v| evaluator.is_truthy(v));
+                if !is_match {
+                    continue;
+                }
+            }
+            results.push(path_to_value(&path));
+        }
+
+        Ok(results)
+    }
+
+    fn description(&self) -> &str {
+        "Outputs the path of every node, optionally only those matching the given filter"
+    }
+}
+
+/// leaf_paths 函数 - 列出所有标量叶子节点的 `[路径, 值]`
+pub struct LeafPathsFunction;
+
+impl AdvancedBuiltinFunction for LeafPathsFunction {
+    fn name(&self) -> &str {
+        "leaf_paths"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        _evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if !args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "leaf_paths function takes no arguments".to_string(),
+            ));
+        }
+
+        Ok(leaf_paths(input)
+            .map(|(path, leaf)| {
+                Value::Array(vec![path_to_value(&path), leaf.clone()])
+            })
+            .collect())
+    }
+
+    fn description(&self) -> &str {
+        "Outputs [path, value] pairs for every scalar leaf"
+    }
+}
+
+/// del 函数 - 删除路径表达式匹配的所有位置，返回删除后的输入
+///
+/// 参数必须是路径（如 `.a`、`.b[*]`），可以用逗号或多个参数同时删除多处；
+/// 删除根节点时结果为 `null`。
+#[cfg(feature = "update")]
+pub struct DelFunction;
+
+#[cfg(feature = "update")]
+impl DelFunction {
+    /// 收集路径表达式在输入中匹配到的节点
+    fn collect_targets<'a>(
+        expr: &PathExpression,
+        input: &'a Value,
+        targets: &mut Vec<&'a Value>,
+    ) -> Result<(), EvaluationError> {
+        match expr {
+            PathExpression::Identity => targets.push(input),
+            PathExpression::Segments(segments) => {
+                let values = crate::extractor::extract(input, segments)
+                    .map_err(|e| EvaluationError::new(e.to_string()))?;
+                targets.extend(values);
+            }
+            PathExpression::Comma(expressions) => {
+                for expression in expressions {
+                    Self::collect_targets(expression, input, targets)?;
+                }
+            }
+            PathExpression::Optional(inner) => {
+                let mut found = Vec::new();
+                if Self::collect_targets(inner, input, &mut found).is_ok() {
+                    targets.extend(found);
+                }
+            }
+            _ => return Err(EvaluationError::InvalidArguments(
+                "del arguments must be path expressions such as .a or .b[*]"
+                    .to_string(),
+            )),
+        }
+        Ok(())
+    }
+
+    /// 按路径段比较，数组索引按数值排序
+    fn compare_paths(a: &[PathSegment], b: &[PathSegment]) -> Ordering {
+        for (left, right) in a.iter().zip(b) {
+            let ordering = match (left, right) {
+                (PathSegment::Index(x), PathSegment::Index(y)) => x.cmp(y),
+                (PathSegment::Field(x), PathSegment::Field(y)) => x.cmp(y),
+                _ => Ordering::Equal,
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        a.len().cmp(&b.len())
+    }
+}
+
+#[cfg(feature = "update")]
+impl AdvancedBuiltinFunction for DelFunction {
+    fn name(&self) -> &str {
+        "del"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        _evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "del function takes at least one path expression".to_string(),
+            ));
+        }
+
+        let mut targets = Vec::new();
+        for arg in args {
+            Self::collect_targets(arg, input, &mut targets)?;
+        }
+
+        let mut paths: Vec<_> = locate_paths(input, &targets)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        if paths.iter().any(|path| path.is_empty()) {
+            return Ok(vec![Value::Null]);
+        }
+
+        // 从后往前删除，避免删除数组元素后其余索引发生偏移
+        paths.sort_by(|a, b| Self::compare_paths(a, b));
+        let mut result = input.clone();
+        for path in paths.iter().rev() {
+            Updater::delete(&mut result, path)
+                .map_err(|e| EvaluationError::new(e.to_string()))?;
+        }
+
+        Ok(vec![result])
+    }
+
+    fn description(&self) -> &str {
+        "Removes every location matched by the given path expressions"
+    }
+}
+
+/// input 函数 - 读取下一个输入文档
+pub struct InputFunction;
+
+impl AdvancedBuiltinFunction for InputFunction {
+    fn name(&self) -> &str {
+        "input"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        _input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if !args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "input function takes no arguments".to_string(),
+            ));
+        }
+
+        evaluator
+            .next_input()
+            .map(|value| vec![value])
+            .ok_or_else(|| {
+                EvaluationError::Message("No more inputs".to_string())
+            })
+    }
+
+    fn description(&self) -> &str {
+        "Reads the next input document"
+    }
+}
+
+/// inputs 函数 - 以流的形式输出所有剩余的输入文档
+pub struct InputsFunction;
+
+impl AdvancedBuiltinFunction for InputsFunction {
+    fn name(&self) -> &str {
+        "inputs"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        _input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if !args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "inputs function takes no arguments".to_string(),
+            ));
+        }
+
+        Ok(evaluator.remaining_inputs())
+    }
+
+    fn description(&self) -> &str {
+        "Outputs all remaining input documents as a stream"
+    }
+}
+
+/// 不带表达式参数时按数组下标取元素，越界或空数组返回 null
+fn array_element(
+    name: &str,
+    input: &Value,
+    index: impl FnOnce(usize) -> Option<usize>,
+) -> Result<Vec<Value>, EvaluationError> {
+    let Value::Array(arr) = input else {
+        return Err(EvaluationError::InvalidArguments(format!(
+            "{name} without arguments can only be applied to arrays, got {}",
+            JsonSupport::get_type_name(input)
+        )));
+    };
+    let element = index(arr.len()).and_then(|i| arr.get(i));
+    Ok(vec![element.cloned().unwrap_or(Value::Null)])
+}
+
+/// first 函数 - `first()` 取数组首个元素；`first(expr)` 只取表达式的第一个结果
+///
+/// `first(expr)` 在得到第一个结果后即停止求值，没有结果时输出为空。
+pub struct FirstFunction;
+
+impl AdvancedBuiltinFunction for FirstFunction {
+    fn name(&self) -> &str {
+        "first"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        match args {
+            [] => array_element("first", input, |_| Some(0)),
+            [expr] => evaluator.evaluate_limited(expr, input, 1),
+            _ => Err(EvaluationError::InvalidArguments(
+                "first function takes at most one argument".to_string(),
+            )),
+        }
+    }
+
+    fn description(&self) -> &str {
+        "Returns the first array element, or the first output of an expression"
+    }
+}
+
+/// last 函数 - `last()` 取数组最后一个元素；`last(expr)` 取表达式的最后一个结果
+pub struct LastFunction;
+
+impl AdvancedBuiltinFunction for LastFunction {
+    fn name(&self) -> &str {
+        "last"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        match args {
+            [] => array_element("last", input, |len| len.checked_sub(1)),
+            [expr] => {
+                Ok(evaluator.evaluate(expr, input)?.pop().into_iter().collect())
+            }
+            _ => Err(EvaluationError::InvalidArguments(
+                "last function takes at most one argument".to_string(),
+            )),
+        }
+    }
+
+    fn description(&self) -> &str {
+        "Returns the last array element, or the last output of an expression"
+    }
+}
+
+/// nth 函数 - `nth(n)` 取数组第 n 个元素；`nth(n; expr)` 取表达式的第 n 个结果
+///
+/// `nth(n; expr)` 在得到第 n + 1 个结果后即停止求值。
+pub struct NthFunction;
+
+impl AdvancedBuiltinFunction for NthFunction {
+    fn name(&self) -> &str {
+        "nth"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let (index_expr, expr) = match args {
+            [index] => (index, None),
+            [index, expr] => (index, Some(expr)),
+            _ => {
+                return Err(EvaluationError::InvalidArguments(
+                    "nth function takes one or two arguments".to_string(),
+                ))
+            }
+        };
+
+        let n = evaluator
+            .evaluate(index_expr, input)?
+            .first()
+            .and_then(Value::as_u64)
+            .ok_or_else(|| {
+                EvaluationError::InvalidArguments(
+                    "nth index must be a non-negative integer".to_string(),
+                )
+            })? as usize;
+
+        match expr {
+            None => array_element("nth", input, |_| Some(n)),
+            Some(expr) => Ok(evaluator
+                .evaluate_limited(expr, input, n + 1)?
+                .into_iter()
+                .nth(n)
+                .into_iter()
+                .collect()),
+        }
+    }
+
+    fn description(&self) -> &str {
+        "Returns the n-th array element, or the n-th output of an expression"
+    }
+}
+
+/// range 生成的最大元素个数，结果会完整地收集到内存中
+const MAX_RANGE_LEN: f64 = 10_000_000.0;
+
+/// range 函数 - 生成数值流：range(to)、range(from; to)、range(from; to; step)
+pub struct RangeFunction;
+
+impl AdvancedBuiltinFunction for RangeFunction {
+    fn name(&self) -> &str {
+        "range"
+    }
+
+    fn execute_with_expressions(
+        &self,
+        args: &[PathExpression],
+        evaluator: &ExpressionEvaluator,
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let mut numbers = Vec::with_capacity(args.len());
+        for arg in args {
+            let number = evaluator
+                .evaluate(arg, input)?
+                .first()
+                .and_then(Value::as_f64)
+                .filter(|n| n.is_finite())
+                .ok_or_else(|| {
+                    EvaluationError::InvalidArguments(
+                        "range arguments must be finite numbers".to_string(),
+                    )
+                })?;
+            numbers.push(number);
+        }
+
+        let (from, to, step) = match numbers.as_slice() {
+            [to] => (0.0, *to, 1.0),
+            [from, to] => (*from, *to, 1.0),
+            [from, to, step] => (*from, *to, *step),
+            _ => {
+                return Err(EvaluationError::InvalidArguments(
+                    "range function takes one to three arguments".to_string(),
+                ))
+            }
+        };
+        if step == 0.0 {
+            return Err(EvaluationError::InvalidArguments(
+                "range step cannot be zero".to_string(),
+            ));
+        }
+
+        let count = ((to - from) / step).ceil().max(0.0);
+        if count > MAX_RANGE_LEN {
+            return Err(EvaluationError::InvalidArguments(format!(
+                "range would produce {count} values, more than the limit of \
+                 {MAX_RANGE_LEN}"
+            )));
+        }
+
+        // 按下标计算每一项，避免步长累加带来的浮点误差
+        let mut values = Vec::with_capacity(count as usize);
+        for i in 0..=count as u64 {
+            let current = from + step * i as f64;
+            if (step > 0.0 && current >= to) || (step < 0.0 && current <= to) {
+                break;
+            }
+            values.push(float_to_value(current));
+        }
+        Ok(values)
+    }
+
+    fn description(&self) -> &str {
+        "Generates numbers from `from` up to (excluding) `to` by `step`"
+    }
+}
@@ -1,5 +1,6 @@
 use super::BuiltinFunction;
 use crate::parser::EvaluationError;
+use crate::value::yaml::YamlSpecialValues;
 use serde_json::Value;
 
 /// length 函数 - 获取数组/对象/字符串长度
@@ -153,3 +154,961 @@ impl BuiltinFunction for ValuesFunction {
         "Returns all values of an object or array"
     }
 }
+
+/// to_entries 函数 - 将对象转换为 `{"key", "value"}` 数组
+pub struct ToEntriesFunction;
+
+impl BuiltinFunction for ToEntriesFunction {
+    fn name(&self) -> &str {
+        "to_entries"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if !args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "to_entries function takes no arguments".to_string(),
+            ));
+        }
+
+        match input {
+            Value::Object(obj) => {
+                let entries: Vec<Value> = obj
+                    .iter()
+                    .map(|(key, value)| {
+                        let mut entry = serde_json::Map::new();
+                        entry.insert(
+                            "key".to_string(),
+                            Value::String(key.clone()),
+                        );
+                        entry.insert("value".to_string(), value.clone());
+                        Value::Object(entry)
+                    })
+                    .collect();
+                Ok(vec![Value::Array(entries)])
+            }
+            _ => Err(EvaluationError::InvalidArguments(
+                "to_entries can only be applied to objects".to_string(),
+            )),
+        }
+    }
+
+    fn description(&self) -> &str {
+        "Converts an object into an array of {key, value} entries"
+    }
+}
+
+/// from_entries 函数 - 将 `{"key", "value"}` 数组还原为对象
+///
+/// 与 jq 一致，键也可以写作 `k`/`name`，值也可以写作 `v`。
+pub struct FromEntriesFunction;
+
+impl BuiltinFunction for FromEntriesFunction {
+    fn name(&self) -> &str {
+        "from_entries"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if !args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "from_entries function takes no arguments".to_string(),
+            ));
+        }
+
+        let Value::Array(entries) = input else {
+            return Err(EvaluationError::InvalidArguments(
+                "from_entries can only be applied to arrays".to_string(),
+            ));
+        };
+
+        let mut object = serde_json::Map::new();
+        for entry in entries {
+            let field =
+                |names: &[&str]| names.iter().find_map(|name| entry.get(*name));
+
+            let key =
+                match field(&["key", "k", "name"]) {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(key @ (Value::Number(_) | Value::Bool(_))) => {
+                        key.to_string()
+                    }
+                    _ => return Err(EvaluationError::InvalidArguments(
+                        "from_entries requires each entry to have a string key"
+                            .to_string(),
+                    )),
+                };
+            let value = field(&["value", "v"]).cloned().unwrap_or(Value::Null);
+            object.insert(key, value);
+        }
+
+        Ok(vec![Value::Object(object)])
+    }
+
+    fn description(&self) -> &str {
+        "Builds an object from an array of {key, value} entries"
+    }
+}
+
+/// 按 jq 的标志字符串构建正则表达式
+///
+/// 支持 `g`（全局，split 总是全局匹配）、`i`（忽略大小写）、`x`（扩展模式）、
+/// `s`（`.` 匹配换行）；标志为 null 时视为空。
+#[cfg(feature = "regex")]
+pub(crate) fn build_regex(
+    pattern: &str,
+    flags: &Value,
+) -> Result<regex::Regex, EvaluationError> {
+    let flags = match flags {
+        Value::Null => "",
+        Value::String(flags) => flags.as_str(),
+        _ => {
+            return Err(EvaluationError::InvalidArguments(
+                "regex flags must be a string or null".to_string(),
+            ))
+        }
+    };
+
+    let mut builder = regex::RegexBuilder::new(pattern);
+    for flag in flags.chars() {
+        match flag {
+            'g' => {}
+            'i' => {
+                builder.case_insensitive(true);
+            }
+            'x' => {
+                builder.ignore_whitespace(true);
+            }
+            's' => {
+                builder.dot_matches_new_line(true);
+            }
+            other => {
+                return Err(EvaluationError::InvalidArguments(format!(
+                    "unsupported regex flag '{other}'"
+                )))
+            }
+        }
+    }
+
+    builder.build().map_err(|e| {
+        EvaluationError::InvalidArguments(format!("invalid regex: {e}"))
+    })
+}
+
+/// 按正则分割字符串，返回各片段
+#[cfg(feature = "regex")]
+fn regex_split(
+    input: &str,
+    pattern: &str,
+    flags: &Value,
+) -> Result<Vec<Value>, EvaluationError> {
+    let regex = build_regex(pattern, flags)?;
+    Ok(regex
+        .split(input)
+        .map(|part| Value::String(part.to_string()))
+        .collect())
+}
+
+/// split 函数 - 按字面分隔符分割字符串；提供第二个参数（标志）时按正则分割
+pub struct SplitFunction;
+
+impl BuiltinFunction for SplitFunction {
+    fn name(&self) -> &str {
+        "split"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let Value::String(s) = input else {
+            return Err(EvaluationError::InvalidArguments(
+                "split can only be applied to strings".to_string(),
+            ));
+        };
+
+        match args {
+            [Value::String(separator)] => {
+                let parts: Vec<Value> = if separator.is_empty() {
+                    s.chars().map(|c| Value::String(c.to_string())).collect()
+                } else {
+                    s.split(separator.as_str())
+                        .map(|part| Value::String(part.to_string()))
+                        .collect()
+                };
+                Ok(vec![Value::Array(parts)])
+            }
+            #[cfg(feature = "regex")]
+            [Value::String(pattern), flags] => {
+                Ok(vec![Value::Array(regex_split(s, pattern, flags)?)])
+            }
+            #[cfg(not(feature = "regex"))]
+            [Value::String(_), _] => Err(EvaluationError::InvalidArguments(
+                "split with flags requires the `regex` feature".to_string(),
+            )),
+            _ => Err(EvaluationError::InvalidArguments(
+                "split expects a string separator and optional flags"
+                    .to_string(),
+            )),
+        }
+    }
+
+    fn description(&self) -> &str {
+        "Splits a string by a separator, or by a regex when flags are given"
+    }
+}
+
+/// splits 函数 - 按正则分割字符串，逐个输出片段
+#[cfg(feature = "regex")]
+pub struct SplitsFunction;
+
+#[cfg(feature = "regex")]
+impl BuiltinFunction for SplitsFunction {
+    fn name(&self) -> &str {
+        "splits"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let Value::String(s) = input else {
+            return Err(EvaluationError::InvalidArguments(
+                "splits can only be applied to strings".to_string(),
+            ));
+        };
+
+        match args {
+            [Value::String(pattern)] => regex_split(s, pattern, &Value::Null),
+            [Value::String(pattern), flags] => regex_split(s, pattern, flags),
+            _ => Err(EvaluationError::InvalidArguments(
+                "splits expects a regex and optional flags".to_string(),
+            )),
+        }
+    }
+
+    fn description(&self) -> &str {
+        "Splits a string by a regex, emitting each part separately"
+    }
+}
+
+/// 将 jq 风格的捕获引用 `\(.name)` 转换为正则替换模板中的 `${name}`
+///
+/// `$1`、`${name}` 等原生写法保持不变；引用不存在的命名捕获组时报错。
+#[cfg(feature = "regex")]
+fn replacement_template(
+    name: &str,
+    regex: &regex::Regex,
+    replacement: &str,
+) -> Result<String, EvaluationError> {
+    let mut template = String::with_capacity(replacement.len());
+    let mut rest = replacement;
+
+    while let Some(start) = rest.find("\\(.") {
+        template.push_str(&rest[..start]);
+        let after = &rest[start + 3..];
+        let Some(end) = after.find(')') else {
+            return Err(EvaluationError::InvalidArguments(format!(
+                "{name}: unterminated capture reference in replacement"
+            )));
+        };
+
+        let group = &after[..end];
+        if !regex.capture_names().flatten().any(|g| g == group) {
+            return Err(EvaluationError::InvalidArguments(format!(
+                "{name}: unknown capture group '{group}' in replacement"
+            )));
+        }
+        template.push_str(&format!("${{{group}}}"));
+        rest = &after[end + 1..];
+    }
+
+    template.push_str(rest);
+    Ok(template)
+}
+
+/// 按正则替换字符串中的匹配：`global` 为真或标志含 `g` 时替换全部匹配
+#[cfg(feature = "regex")]
+fn regex_replace(
+    name: &str,
+    args: &[Value],
+    input: &Value,
+    global: bool,
+) -> Result<Vec<Value>, EvaluationError> {
+    let Value::String(s) = input else {
+        return Err(EvaluationError::InvalidArguments(format!(
+            "{name} can only be applied to strings"
+        )));
+    };
+
+    let (pattern, replacement, flags) = match args {
+        [Value::String(pattern), Value::String(replacement)] => {
+            (pattern, replacement, &Value::Null)
+        }
+        [Value::String(pattern), Value::String(replacement), flags] => {
+            (pattern, replacement, flags)
+        }
+        _ => {
+            return Err(EvaluationError::InvalidArguments(format!(
+            "{name} expects a regex, a replacement string and optional flags"
+        )))
+        }
+    };
+
+    let regex = build_regex(pattern, flags).map_err(|e| {
+        EvaluationError::InvalidArguments(format!("{name}: {e}"))
+    })?;
+    let template = replacement_template(name, &regex, replacement)?;
+    let global = global || flags.as_str().is_some_and(|f| f.contains('g'));
+    let limit = if global { 0 } else { 1 };
+
+    let replaced = regex.replacen(s, limit, |caps: &regex::Captures| {
+        let mut out = String::new();
+        caps.expand(&template, &mut out);
+        out
+    });
+    Ok(vec![Value::String(replaced.into_owned())])
+}
+
+/// sub 函数 - 用替换字符串替换第一个正则匹配，支持 `$1`、`\(.name)` 捕获引用
+#[cfg(feature = "regex")]
+pub struct SubFunction;
+
+#[cfg(feature = "regex")]
+impl BuiltinFunction for SubFunction {
+    fn name(&self) -> &str {
+        "sub"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        regex_replace(self.name(), args, input, false)
+    }
+
+    fn description(&self) -> &str {
+        "Replaces the first regex match, supporting $1 and \\(.name) captures"
+    }
+}
+
+/// gsub 函数 - 用替换字符串替换全部正则匹配
+#[cfg(feature = "regex")]
+pub struct GsubFunction;
+
+#[cfg(feature = "regex")]
+impl BuiltinFunction for GsubFunction {
+    fn name(&self) -> &str {
+        "gsub"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        regex_replace(self.name(), args, input, true)
+    }
+
+    fn description(&self) -> &str {
+        "Replaces all regex matches, supporting $1 and \\(.name) captures"
+    }
+}
+
+/// 对字符串输入应用去除空白的操作，非字符串输入报错
+fn trim_string(
+    name: &str,
+    args: &[Value],
+    input: &Value,
+    trim: fn(&str) -> &str,
+) -> Result<Vec<Value>, EvaluationError> {
+    if !args.is_empty() {
+        return Err(EvaluationError::InvalidArguments(format!(
+            "{name} function takes no arguments"
+        )));
+    }
+
+    match input {
+        Value::String(s) => Ok(vec![Value::String(trim(s).to_string())]),
+        _ => Err(EvaluationError::InvalidArguments(format!(
+            "{name} can only be applied to strings"
+        ))),
+    }
+}
+
+/// trim 函数 - 去除字符串首尾的 ASCII 空白
+pub struct TrimFunction;
+
+impl BuiltinFunction for TrimFunction {
+    fn name(&self) -> &str {
+        "trim"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        trim_string(self.name(), args, input, |s| {
+            s.trim_matches(|c: char| c.is_ascii_whitespace())
+        })
+    }
+
+    fn description(&self) -> &str {
+        "Removes leading and trailing ASCII whitespace from a string"
+    }
+}
+
+/// ltrim 函数 - 去除字符串开头的 ASCII 空白
+pub struct LtrimFunction;
+
+impl BuiltinFunction for LtrimFunction {
+    fn name(&self) -> &str {
+        "ltrim"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        trim_string(self.name(), args, input, |s| {
+            s.trim_start_matches(|c: char| c.is_ascii_whitespace())
+        })
+    }
+
+    fn description(&self) -> &str {
+        "Removes leading ASCII whitespace from a string"
+    }
+}
+
+/// rtrim 函数 - 去除字符串结尾的 ASCII 空白
+pub struct RtrimFunction;
+
+impl BuiltinFunction for RtrimFunction {
+    fn name(&self) -> &str {
+        "rtrim"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        trim_string(self.name(), args, input, |s| {
+            s.trim_end_matches(|c: char| c.is_ascii_whitespace())
+        })
+    }
+
+    fn description(&self) -> &str {
+        "Removes trailing ASCII whitespace from a string"
+    }
+}
+
+/// 将标量转换为 shell 安全的单词：字符串加单引号，其余标量原样输出
+fn shell_quote(value: &Value) -> Result<String, EvaluationError> {
+    match value {
+        Value::String(s) => Ok(format!("'{}'", s.replace('\'', "'\\''"))),
+        Value::Array(_) => Err(EvaluationError::InvalidArguments(
+            "array can not be escaped for shell".to_string(),
+        )),
+        Value::Object(_) => Err(EvaluationError::InvalidArguments(
+            "object can not be escaped for shell".to_string(),
+        )),
+        scalar => Ok(scalar.to_string()),
+    }
+}
+
+/// @sh 格式过滤器 - 生成可直接拼接进 shell 命令行的参数
+///
+/// 字符串用单引号包裹，内部的 `'` 转义为 `'\''`；
+/// 数组输出为以空格分隔的参数列表，元素必须是标量。
+pub struct ShFormatFunction;
+
+impl BuiltinFunction for ShFormatFunction {
+    fn name(&self) -> &str {
+        "@sh"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if !args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "@sh takes no arguments".to_string(),
+            ));
+        }
+
+        let quoted = match input {
+            Value::Array(items) => items
+                .iter()
+                .map(shell_quote)
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" "),
+            other => shell_quote(other)?,
+        };
+        Ok(vec![Value::String(quoted)])
+    }
+
+    fn description(&self) -> &str {
+        "Quotes a string or array of scalars for use in a shell command line"
+    }
+}
+
+/// tojson 函数 - 将值序列化为紧凑的 JSON 字符串
+pub struct TojsonFunction;
+
+impl BuiltinFunction for TojsonFunction {
+    fn name(&self) -> &str {
+        "tojson"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if !args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "tojson function takes no arguments".to_string(),
+            ));
+        }
+
+        let json = serde_json::to_string(input)
+            .map_err(|e| EvaluationError::new(format!("tojson failed: {e}")))?;
+        Ok(vec![Value::String(json)])
+    }
+
+    fn description(&self) -> &str {
+        "Serializes a value to a compact JSON string"
+    }
+}
+
+/// fromjson 函数 - 将 JSON 字符串解析为值，便于继续访问内嵌的 JSON
+pub struct FromjsonFunction;
+
+impl BuiltinFunction for FromjsonFunction {
+    fn name(&self) -> &str {
+        "fromjson"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if !args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "fromjson function takes no arguments".to_string(),
+            ));
+        }
+
+        match input {
+            Value::String(s) => serde_json::from_str(s)
+                .map(|value| vec![value])
+                .map_err(|e| {
+                    EvaluationError::InvalidArguments(format!(
+                        "fromjson could not parse {s:?}: {e}"
+                    ))
+                }),
+            _ => Err(EvaluationError::InvalidArguments(
+                "fromjson can only be applied to strings".to_string(),
+            )),
+        }
+    }
+
+    fn description(&self) -> &str {
+        "Parses a JSON string into a value"
+    }
+}
+
+/// env 函数 - 读取环境变量
+///
+/// `env()` 返回包含全部环境变量的对象，`env("NAME")` 返回单个变量，
+/// 不存在时返回 null。非 UTF-8 的变量会被忽略。
+#[cfg(feature = "env")]
+pub struct EnvFunction;
+
+#[cfg(feature = "env")]
+impl BuiltinFunction for EnvFunction {
+    fn name(&self) -> &str {
+        "env"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        _input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        match args {
+            [] => {
+                let vars: serde_json::Map<String, Value> = std::env::vars_os()
+                    .filter_map(|(key, value)| {
+                        Some((
+                            key.into_string().ok()?,
+                            value.into_string().ok()?,
+                        ))
+                    })
+                    .map(|(key, value)| (key, Value::String(value)))
+                    .collect();
+                Ok(vec![Value::Object(vars)])
+            }
+            [Value::String(name)] => Ok(vec![std::env::var(name)
+                .map(Value::String)
+                .unwrap_or(Value::Null)]),
+            _ => Err(EvaluationError::InvalidArguments(
+                "env expects no arguments or a variable name".to_string(),
+            )),
+        }
+    }
+
+    fn description(&self) -> &str {
+        "Returns all environment variables, or a single one by name"
+    }
+}
+
+/// 查找目标在字符串（子串，按字符计）或数组（元素或子数组）中出现的所有位置
+///
+/// 输入为 null 时返回 `None`。
+fn find_indices(
+    function_name: &str,
+    args: &[Value],
+    input: &Value,
+) -> Result<Option<Vec<usize>>, EvaluationError> {
+    let [target] = args else {
+        return Err(EvaluationError::InvalidArguments(format!(
+            "{function_name} function takes exactly one argument"
+        )));
+    };
+
+    match (input, target) {
+        (Value::Null, _) => Ok(None),
+        (Value::String(haystack), Value::String(needle)) => {
+            if needle.is_empty() {
+                return Ok(Some(Vec::new()));
+            }
+            let chars: Vec<char> = haystack.chars().collect();
+            let needle: Vec<char> = needle.chars().collect();
+            Ok(Some(
+                chars
+                    .windows(needle.len())
+                    .enumerate()
+                    .filter(|(_, window)| *window == needle.as_slice())
+                    .map(|(i, _)| i)
+                    .collect(),
+            ))
+        }
+        (Value::Array(arr), Value::Array(sub)) => {
+            if sub.is_empty() {
+                return Ok(Some(Vec::new()));
+            }
+            Ok(Some(
+                arr.windows(sub.len())
+                    .enumerate()
+                    .filter(|(_, window)| *window == sub.as_slice())
+                    .map(|(i, _)| i)
+                    .collect(),
+            ))
+        }
+        (Value::Array(arr), element) => Ok(Some(
+            arr.iter()
+                .enumerate()
+                .filter(|(_, item)| *item == element)
+                .map(|(i, _)| i)
+                .collect(),
+        )),
+        _ => Err(EvaluationError::InvalidArguments(format!(
+            "{function_name} can only search strings for substrings or arrays for elements"
+        ))),
+    }
+}
+
+/// index 函数 - 获取目标首次出现的位置，未找到时返回 null
+pub struct IndexFunction;
+
+impl BuiltinFunction for IndexFunction {
+    fn name(&self) -> &str {
+        "index"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let first = find_indices("index", args, input)?
+            .and_then(|indices| indices.first().copied());
+        Ok(vec![first.map_or(Value::Null, |i| Value::Number(i.into()))])
+    }
+
+    fn description(&self) -> &str {
+        "Returns the first index of a substring or element, or null if not found"
+    }
+}
+
+/// indices 函数 - 获取目标出现的所有位置
+pub struct IndicesFunction;
+
+impl BuiltinFunction for IndicesFunction {
+    fn name(&self) -> &str {
+        "indices"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let result = match find_indices("indices", args, input)? {
+            Some(indices) => Value::Array(
+                indices
+                    .into_iter()
+                    .map(|i| Value::Number(i.into()))
+                    .collect(),
+            ),
+            None => Value::Null,
+        };
+        Ok(vec![result])
+    }
+
+    fn description(&self) -> &str {
+        "Returns all indices of a substring or element in a string or array"
+    }
+}
+
+/// 获取数值输入的浮点表示，只接受数字；YAML 的 `.inf` 解析为 ±`f64::MAX`，
+/// 按 jq 的约定视为无穷
+fn numeric_input(
+    function_name: &str,
+    args: &[Value],
+    input: &Value,
+) -> Result<f64, EvaluationError> {
+    if !args.is_empty() {
+        return Err(EvaluationError::InvalidArguments(format!(
+            "{function_name} function takes no arguments"
+        )));
+    }
+
+    YamlSpecialValues::as_f64(input).ok_or_else(|| {
+        EvaluationError::InvalidArguments(format!(
+            "{function_name} can only be applied to numbers"
+        ))
+    })
+}
+
+/// 获取数值分类函数的输入：YAML 的 `.nan` 解析为 `null`，这里视为 NaN，
+/// 使 `isnan` 能识别它，`isnormal`/`isinfinite` 对其返回 false
+fn classified_input(
+    function_name: &str,
+    args: &[Value],
+    input: &Value,
+) -> Result<f64, EvaluationError> {
+    match input {
+        Value::Null if args.is_empty() => Ok(f64::NAN),
+        _ => numeric_input(function_name, args, input),
+    }
+}
+
+/// isnan 函数 - 判断数值是否为 NaN
+pub struct IsNanFunction;
+
+impl BuiltinFunction for IsNanFunction {
+    fn name(&self) -> &str {
+        "isnan"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let number = classified_input("isnan", args, input)?;
+        Ok(vec![Value::Bool(number.is_nan())])
+    }
+
+    fn description(&self) -> &str {
+        "Returns true if the number is NaN (null, as YAML .nan parses to, counts as NaN)"
+    }
+}
+
+/// isinfinite 函数 - 判断数值是否为正负无穷
+pub struct IsInfiniteFunction;
+
+impl BuiltinFunction for IsInfiniteFunction {
+    fn name(&self) -> &str {
+        "isinfinite"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let number = classified_input("isinfinite", args, input)?;
+        Ok(vec![Value::Bool(number.is_infinite())])
+    }
+
+    fn description(&self) -> &str {
+        "Returns true if the number is positive or negative infinity"
+    }
+}
+
+/// isnormal 函数 - 判断数值是否为正规数（非零、非无穷、非 NaN、非次正规数）
+pub struct IsNormalFunction;
+
+impl BuiltinFunction for IsNormalFunction {
+    fn name(&self) -> &str {
+        "isnormal"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let number = classified_input("isnormal", args, input)?;
+        Ok(vec![Value::Bool(number.is_normal())])
+    }
+
+    fn description(&self) -> &str {
+        "Returns true if the number is normal (not zero, infinite, NaN or subnormal)"
+    }
+}
+
+/// 将浮点运算结果转换为 JSON 值：整数值保留为整数，
+/// NaN 与无穷在 JSON 中无法表示，与 jq 一样输出 null
+pub(crate) fn float_to_value(number: f64) -> Value {
+    if number.fract() == 0.0 && number.abs() < i64::MAX as f64 {
+        return Value::from(number as i64);
+    }
+    serde_json::Number::from_f64(number)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+/// 对数值输入应用一元数学运算
+fn apply_math(
+    function_name: &str,
+    args: &[Value],
+    input: &Value,
+    operation: fn(f64) -> f64,
+) -> Result<Vec<Value>, EvaluationError> {
+    let number = numeric_input(function_name, args, input)?;
+    Ok(vec![float_to_value(operation(number))])
+}
+
+/// floor 函数 - 向下取整
+pub struct FloorFunction;
+
+impl BuiltinFunction for FloorFunction {
+    fn name(&self) -> &str {
+        "floor"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        apply_math("floor", args, input, f64::floor)
+    }
+
+    fn description(&self) -> &str {
+        "Rounds a number down to the nearest integer"
+    }
+}
+
+/// ceil 函数 - 向上取整
+pub struct CeilFunction;
+
+impl BuiltinFunction for CeilFunction {
+    fn name(&self) -> &str {
+        "ceil"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        apply_math("ceil", args, input, f64::ceil)
+    }
+
+    fn description(&self) -> &str {
+        "Rounds a number up to the nearest integer"
+    }
+}
+
+/// round 函数 - 四舍五入（.5 远离零）
+pub struct RoundFunction;
+
+impl BuiltinFunction for RoundFunction {
+    fn name(&self) -> &str {
+        "round"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        apply_math("round", args, input, f64::round)
+    }
+
+    fn description(&self) -> &str {
+        "Rounds a number to the nearest integer, halfway cases away from zero"
+    }
+}
+
+/// abs 函数 - 绝对值
+pub struct AbsFunction;
+
+impl BuiltinFunction for AbsFunction {
+    fn name(&self) -> &str {
+        "abs"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        apply_math("abs", args, input, f64::abs)
+    }
+
+    fn description(&self) -> &str {
+        "Returns the absolute value of a number"
+    }
+}
+
+/// sqrt 函数 - 平方根，负数返回 NaN
+pub struct SqrtFunction;
+
+impl BuiltinFunction for SqrtFunction {
+    fn name(&self) -> &str {
+        "sqrt"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        apply_math("sqrt", args, input, f64::sqrt)
+    }
+
+    fn description(&self) -> &str {
+        "Returns the square root of a number (NaN for negative numbers)"
+    }
+}
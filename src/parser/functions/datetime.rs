@@ -0,0 +1,177 @@
+//! 时间函数 - now、todate、strftime（需要启用 `time` feature）
+
+use super::BuiltinFunction;
+use crate::parser::EvaluationError;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+/// 将 Unix 时间戳（秒，可带小数）转换为 UTC 时间
+fn timestamp_to_datetime(
+    name: &str,
+    input: &Value,
+) -> Result<OffsetDateTime, EvaluationError> {
+    let seconds = input.as_f64().ok_or_else(|| {
+        EvaluationError::InvalidArguments(format!(
+            "{name} can only be applied to numbers (seconds since the epoch)"
+        ))
+    })?;
+
+    let nanos = (seconds * 1_000_000_000.0).floor() as i128;
+    OffsetDateTime::from_unix_timestamp_nanos(nanos).map_err(|e| {
+        EvaluationError::InvalidArguments(format!(
+            "{name}: timestamp out of range: {e}"
+        ))
+    })
+}
+
+/// 按 strftime 格式化时间，支持常用的转换说明符
+fn strftime(
+    datetime: &OffsetDateTime,
+    format: &str,
+) -> Result<String, EvaluationError> {
+    let mut output = String::new();
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+
+        let spec = chars.next().ok_or_else(|| {
+            EvaluationError::InvalidArguments(
+                "strftime format ends with a lone '%'".to_string(),
+            )
+        })?;
+        let hour12 = match datetime.hour() % 12 {
+            0 => 12,
+            h => h,
+        };
+        let formatted = match spec {
+            'Y' => datetime.year().to_string(),
+            'y' => format!("{:02}", datetime.year().rem_euclid(100)),
+            'm' => format!("{:02}", u8::from(datetime.month())),
+            'd' => format!("{:02}", datetime.day()),
+            'e' => format!("{:2}", datetime.day()),
+            'H' => format!("{:02}", datetime.hour()),
+            'I' => format!("{hour12:02}"),
+            'M' => format!("{:02}", datetime.minute()),
+            'S' => format!("{:02}", datetime.second()),
+            'p' => if datetime.hour() < 12 { "AM" } else { "PM" }.to_string(),
+            'j' => format!("{:03}", datetime.ordinal()),
+            'a' => datetime.weekday().to_string()[..3].to_string(),
+            'A' => datetime.weekday().to_string(),
+            'b' | 'h' => datetime.month().to_string()[..3].to_string(),
+            'B' => datetime.month().to_string(),
+            'u' => datetime.weekday().number_from_monday().to_string(),
+            'w' => datetime.weekday().number_days_from_sunday().to_string(),
+            's' => datetime.unix_timestamp().to_string(),
+            'Z' => "UTC".to_string(),
+            'z' => "+0000".to_string(),
+            'F' => strftime(datetime, "%Y-%m-%d")?,
+            'T' => strftime(datetime, "%H:%M:%S")?,
+            'D' => strftime(datetime, "%m/%d/%y")?,
+            'n' => "\n".to_string(),
+            't' => "\t".to_string(),
+            '%' => "%".to_string(),
+            other => {
+                return Err(EvaluationError::InvalidArguments(format!(
+                    "unsupported strftime specifier '%{other}'"
+                )))
+            }
+        };
+        output.push_str(&formatted);
+    }
+
+    Ok(output)
+}
+
+/// now 函数 - 当前 Unix 时间戳（秒，带小数）
+pub struct NowFunction;
+
+impl BuiltinFunction for NowFunction {
+    fn name(&self) -> &str {
+        "now"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        _input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if !args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "now function takes no arguments".to_string(),
+            ));
+        }
+
+        let nanos = OffsetDateTime::now_utc().unix_timestamp_nanos();
+        let seconds = nanos as f64 / 1_000_000_000.0;
+        Ok(vec![serde_json::Number::from_f64(seconds)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)])
+    }
+
+    fn description(&self) -> &str {
+        "Returns the current time in seconds since the Unix epoch"
+    }
+}
+
+/// todate 函数 - 将 Unix 时间戳格式化为 ISO 8601 字符串
+pub struct TodateFunction;
+
+impl BuiltinFunction for TodateFunction {
+    fn name(&self) -> &str {
+        "todate"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        if !args.is_empty() {
+            return Err(EvaluationError::InvalidArguments(
+                "todate function takes no arguments".to_string(),
+            ));
+        }
+
+        let datetime = timestamp_to_datetime(self.name(), input)?;
+        Ok(vec![Value::String(strftime(
+            &datetime,
+            "%Y-%m-%dT%H:%M:%SZ",
+        )?)])
+    }
+
+    fn description(&self) -> &str {
+        "Formats a Unix timestamp as an ISO 8601 UTC date-time"
+    }
+}
+
+/// strftime 函数 - 按格式字符串格式化 Unix 时间戳（UTC）
+pub struct StrftimeFunction;
+
+impl BuiltinFunction for StrftimeFunction {
+    fn name(&self) -> &str {
+        "strftime"
+    }
+
+    fn execute(
+        &self,
+        args: &[Value],
+        input: &Value,
+    ) -> Result<Vec<Value>, EvaluationError> {
+        let [Value::String(format)] = args else {
+            return Err(EvaluationError::InvalidArguments(
+                "strftime expects a single format string".to_string(),
+            ));
+        };
+
+        let datetime = timestamp_to_datetime(self.name(), input)?;
+        Ok(vec![Value::String(strftime(&datetime, format)?)])
+    }
+
+    fn description(&self) -> &str {
+        "Formats a Unix timestamp with a strftime format string"
+    }
+}
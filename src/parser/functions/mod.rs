@@ -4,9 +4,13 @@ use std::collections::HashMap;
 
 pub mod advanced;
 pub mod basic;
+#[cfg(feature = "time")]
+pub mod datetime;
 
 pub use advanced::*;
 pub use basic::*;
+#[cfg(feature = "time")]
+pub use datetime::*;
 
 /// 内置函数 trait
 pub trait BuiltinFunction: Send + Sync {
@@ -87,6 +91,22 @@ impl FunctionRegistry {
         self.advanced_functions.get(name).map(|f| f.as_ref())
     }
 
+    /// 列出所有已注册函数（含高级函数）的名称与描述，按名称排序
+    pub fn list_functions(&self) -> Vec<(&str, &str)> {
+        let mut functions: Vec<(&str, &str)> = self
+            .functions
+            .values()
+            .map(|f| (f.name(), f.description()))
+            .chain(
+                self.advanced_functions
+                    .values()
+                    .map(|f| (f.name(), f.description())),
+            )
+            .collect();
+        functions.sort_unstable();
+        functions
+    }
+
     /// 注册内置函数
     fn register_builtin_functions(&mut self) {
         // Phase 1: 基础函数
@@ -94,15 +114,66 @@ impl FunctionRegistry {
         self.register(Box::new(TypeFunction));
         self.register(Box::new(KeysFunction));
         self.register(Box::new(ValuesFunction));
+        self.register(Box::new(ToEntriesFunction));
+        self.register(Box::new(FromEntriesFunction));
+        self.register(Box::new(SplitFunction));
+        #[cfg(feature = "regex")]
+        {
+            self.register(Box::new(SplitsFunction));
+            self.register(Box::new(SubFunction));
+            self.register(Box::new(GsubFunction));
+        }
+        self.register(Box::new(TrimFunction));
+        self.register(Box::new(LtrimFunction));
+        self.register(Box::new(RtrimFunction));
+        self.register(Box::new(TojsonFunction));
+        self.register(Box::new(FromjsonFunction));
+        self.register(Box::new(ShFormatFunction));
+        #[cfg(feature = "env")]
+        self.register(Box::new(EnvFunction));
+        #[cfg(feature = "time")]
+        {
+            self.register(Box::new(NowFunction));
+            self.register(Box::new(TodateFunction));
+            self.register(Box::new(StrftimeFunction));
+        }
+        self.register(Box::new(IndexFunction));
+        self.register(Box::new(IndicesFunction));
+        self.register(Box::new(IsNanFunction));
+        self.register(Box::new(IsInfiniteFunction));
+        self.register(Box::new(IsNormalFunction));
+        self.register(Box::new(FloorFunction));
+        self.register(Box::new(CeilFunction));
+        self.register(Box::new(RoundFunction));
+        self.register(Box::new(AbsFunction));
+        self.register(Box::new(SqrtFunction));
 
         // Phase 3: 高级函数
         self.register_advanced(Box::new(MapFunction));
+        self.register_advanced(Box::new(MapValuesFunction));
         self.register_advanced(Box::new(SelectFunction));
+        self.register_advanced(Box::new(AnyFunction));
+        self.register_advanced(Box::new(AllFunction));
         self.register_advanced(Box::new(SortFunction));
         self.register_advanced(Box::new(SortByFunction));
+        self.register_advanced(Box::new(SortDescFunction));
+        self.register_advanced(Box::new(SortByDescFunction));
+        self.register_advanced(Box::new(MinByFunction));
+        self.register_advanced(Box::new(MaxByFunction));
         self.register_advanced(Box::new(GroupByFunction));
+        self.register_advanced(Box::new(CountByFunction));
         self.register_advanced(Box::new(UniqueFunction));
         self.register_advanced(Box::new(UniqueByFunction));
         self.register_advanced(Box::new(ReverseFunction));
+        self.register_advanced(Box::new(PathsFunction));
+        self.register_advanced(Box::new(LeafPathsFunction));
+        self.register_advanced(Box::new(InputFunction));
+        self.register_advanced(Box::new(InputsFunction));
+        self.register_advanced(Box::new(RangeFunction));
+        self.register_advanced(Box::new(FirstFunction));
+        self.register_advanced(Box::new(LastFunction));
+        self.register_advanced(Box::new(NthFunction));
+        #[cfg(feature = "update")]
+        self.register_advanced(Box::new(DelFunction));
     }
 }
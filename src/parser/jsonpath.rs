@@ -0,0 +1,234 @@
+//! JSONPath 路径方言
+//!
+//! 将常用的 JSONPath 子集（`$`、`.key`、`['key']`、`[n]`、`[*]`、`..`）
+//! 翻译为 [`PathSegment`] 序列，便于从 JSONPath 工具迁移。
+//! 过滤器、脚本表达式、切片和联合等特性不受支持，会返回明确的错误。
+
+use winnow::{
+    ascii::digit1,
+    combinator::{
+        alt, cut_err, delimited, eof, fail, peek, preceded, repeat, terminated,
+    },
+    error::{ContextError, ErrMode, StrContext},
+    token::{any, none_of, take_while},
+    PResult, Parser,
+};
+
+use super::path::{ParseError, ParseResult, PathSegment};
+use crate::error::{XqpathError, XqpathResult};
+
+/// 以给定说明拒绝不支持的语法，错误不再回溯到其他分支
+fn unsupported<'i, O>(
+    message: &'static str,
+) -> impl Parser<&'i str, O, ContextError> {
+    cut_err(fail).context(StrContext::Label(message))
+}
+
+/// 解析点号后的成员名，允许 kubectl 常见的 `-`
+fn parse_member_name(input: &mut &str) -> PResult<PathSegment> {
+    take_while(1.., |c: char| c.is_alphanumeric() || c == '_' || c == '-')
+        .map(|name: &str| PathSegment::Field(name.to_string()))
+        .parse_next(input)
+}
+
+/// 解析单引号或双引号包裹的键名，`\` 转义其后的任意字符
+fn parse_quoted_key(input: &mut &str) -> PResult<String> {
+    let body = |quote: char| {
+        delimited(
+            quote,
+            repeat(0.., alt((preceded('\\', any), none_of([quote, '\\'])))),
+            cut_err(quote)
+                .context(StrContext::Label("Unterminated quoted key")),
+        )
+    };
+    alt((body('\''), body('"'))).parse_next(input)
+}
+
+/// 解析方括号选择器：`['key']`、`["key"]`、`[n]` 或 `[*]`
+fn parse_bracket(input: &mut &str) -> PResult<PathSegment> {
+    let selector = alt((
+        parse_quoted_key.map(PathSegment::Field),
+        '*'.value(PathSegment::Wildcard),
+        preceded(
+            peek(digit1),
+            cut_err(digit1.try_map(str::parse).map(PathSegment::Index))
+                .context(StrContext::Label("Array index is too large")),
+        ),
+        preceded(
+            peek('?'),
+            unsupported("JSONPath filter expressions are not supported"),
+        ),
+        preceded(
+            peek('('),
+            unsupported("JSONPath script expressions are not supported"),
+        ),
+        preceded(
+            peek('-'),
+            unsupported("Negative array indices are not supported"),
+        ),
+        unsupported("Expected a key, index or '*'"),
+    ));
+    let closing = alt((
+        ']'.void(),
+        preceded(
+            peek(':'),
+            unsupported("JSONPath array slices are not supported"),
+        ),
+        preceded(peek(','), unsupported("JSONPath unions are not supported")),
+        unsupported("Unterminated bracket selector"),
+    ));
+
+    preceded('[', terminated(selector, closing)).parse_next(input)
+}
+
+/// 解析一个 JSONPath 步骤；`..` 展开为递归通配符加其后的选择器
+fn parse_step(input: &mut &str) -> PResult<Vec<PathSegment>> {
+    let descendant = alt((
+        '*'.value(PathSegment::Wildcard),
+        parse_bracket,
+        parse_member_name,
+    ))
+    .map(|segment| vec![PathSegment::RecursiveWildcard, segment]);
+    let child = alt(('*'.value(PathSegment::Wildcard), parse_member_name))
+        .map(|segment| vec![segment]);
+
+    alt((
+        preceded(
+            "..",
+            cut_err(descendant).context(StrContext::Label(
+                "Expected a member name after '..'",
+            )),
+        ),
+        preceded(
+            '.',
+            cut_err(child).context(StrContext::Label("Expected a member name")),
+        ),
+        parse_bracket.map(|segment| vec![segment]),
+    ))
+    .parse_next(input)
+}
+
+/// 解析完整的 JSONPath：根 `$` 之后跟任意个步骤
+fn parse_jsonpath_internal(input: &mut &str) -> PResult<Vec<PathSegment>> {
+    alt((
+        '$'.void(),
+        preceded(
+            peek('@'),
+            unsupported(
+                "Relative paths ('@') are only valid inside filters, \
+                 which are not supported",
+            ),
+        ),
+        unsupported("JSONPath must start with '$'"),
+    ))
+    .parse_next(input)?;
+
+    let steps: Vec<Vec<PathSegment>> =
+        repeat(0.., parse_step).parse_next(input)?;
+    cut_err(eof)
+        .context(StrContext::Label(
+            "Unexpected character, expected '.' or '['",
+        ))
+        .parse_next(input)?;
+    Ok(steps.into_iter().flatten().collect())
+}
+
+/// 解析 JSONPath 表达式，如 `$.store.book[0].title`
+///
+/// `$..name` 对应递归通配符加字段，`$..*` 对应递归通配符加通配符。
+pub fn parse_jsonpath(input: &str) -> XqpathResult<Vec<PathSegment>> {
+    jsonpath_segments(input).map_err(XqpathError::from)
+}
+
+fn jsonpath_segments(input: &str) -> ParseResult<Vec<PathSegment>> {
+    let mut rest = input;
+    parse_jsonpath_internal.parse_next(&mut rest).map_err(|e| {
+        let label = match &e {
+            ErrMode::Backtrack(e) | ErrMode::Cut(e) => {
+                e.context().find_map(|c| match c {
+                    StrContext::Label(label) => Some(label.to_string()),
+                    _ => None,
+                })
+            }
+            ErrMode::Incomplete(_) => None,
+        };
+        ParseError {
+            message: label
+                .unwrap_or_else(|| format!("Failed to parse JSONPath: {e:?}")),
+            position: input.len() - rest.len(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::path::{format_jsonpath, parse_path};
+
+    #[test]
+    fn test_parse_jsonpath_subset() {
+        let cases = [
+            ("$", "."),
+            ("$.store.book[0].title", ".store.book[0].title"),
+            ("$['a b'][\"c\"]", r#"."a b".c"#),
+            (r"$['it\'s']", r#"."it's""#),
+            ("$.items[*].id", ".items[*].id"),
+            ("$.items.*", ".items[*]"),
+            ("$..author", ".**.author"),
+            ("$..[0]", ".**[0]"),
+            ("$..*", ".**[*]"),
+            (
+                "$.metadata.labels.app-name",
+                r#".metadata.labels."app-name""#,
+            ),
+        ];
+        for (jsonpath, jq) in cases {
+            let segments = parse_jsonpath(jsonpath).unwrap();
+            assert_eq!(segments, parse_path(jq).unwrap(), "{jsonpath}");
+        }
+    }
+
+    #[test]
+    fn test_parse_jsonpath_roundtrip() {
+        for jsonpath in [
+            "$",
+            "$.a[0]['b c']",
+            "$..name",
+            "$..[2]",
+            "$..*",
+            r"$['\\']",
+        ] {
+            let segments = parse_jsonpath(jsonpath).unwrap();
+            assert_eq!(
+                format_jsonpath(&segments).as_deref(),
+                Some(jsonpath),
+                "{segments:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_jsonpath_unsupported() {
+        let cases = [
+            ("$.book[?(@.price < 10)]", "filter expressions"),
+            ("$.book[(@.length-1)]", "script expressions"),
+            ("$.book[0:2]", "slices"),
+            ("$.book[0,1]", "unions"),
+            ("$.book[-1]", "Negative"),
+            ("@.name", "only valid inside filters"),
+            (".name", "must start with '$'"),
+            ("$.book[0", "Unterminated"),
+            ("$['open", "Unterminated"),
+            ("$.", "member name"),
+            ("$..", "member name after '..'"),
+            ("$..[?(@.isbn)]", "filter expressions"),
+            ("$.a b", "Unexpected character"),
+            ("$[99999999999999999999999]", "too large"),
+            ("$[]", "Expected a key, index or '*'"),
+        ];
+        for (input, expected) in cases {
+            let error = parse_jsonpath(input).unwrap_err().to_string();
+            assert!(error.contains(expected), "{input}: {error}");
+        }
+    }
+}
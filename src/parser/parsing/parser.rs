@@ -1,15 +1,22 @@
 use winnow::{
     ascii::{alpha1, digit1},
-    combinator::{alt, delimited, empty, repeat},
+    combinator::{alt, delimited, empty, opt, repeat},
     token::{take_until, take_while},
     PResult, Parser,
 };
 
+use crate::error::{XqpathError, XqpathResult};
 use crate::parser::{
-    ast::{ComparisonOp, LogicalOp, PathExpression},
-    path::{ParseError, ParseResult, PathSegment},
+    ast::{ArithmeticOp, ComparisonOp, LogicalOp, PathExpression},
+    path::{parse_quoted_field, ParseError, ParseResult, PathSegment},
 };
 use serde_json::Value;
+use std::cell::Cell;
+
+thread_local! {
+    /// 是否正在解析 `paths` 的过滤表达式
+    static IN_FILTER: Cell<bool> = const { Cell::new(false) };
+}
 
 /// 表达式解析器
 pub struct ExpressionParser;
@@ -17,8 +24,24 @@ pub struct ExpressionParser;
 impl ExpressionParser {
     /// 主解析函数：解析完整的路径表达式
     pub fn parse_path_expression(input: &str) -> ParseResult<PathExpression> {
+        Self::parse_complete(input, Self::parse_comma_expression)
+    }
+
+    /// 解析 `paths` 的过滤表达式
+    ///
+    /// 与 jq 一致，过滤表达式中不带前导点的单个标识符（如 `type`）视为无参
+    /// 函数调用，`.type` 仍是字段访问。
+    pub fn parse_filter_expression(input: &str) -> ParseResult<PathExpression> {
+        Self::parse_complete(input, Self::parse_filter_argument)
+    }
+
+    /// 用给定解析器解析整个输入，剩余未解析的字符视为错误
+    fn parse_complete(
+        input: &str,
+        parser: fn(&mut &str) -> PResult<PathExpression>,
+    ) -> ParseResult<PathExpression> {
         let mut input_ref = input;
-        match Self::parse_comma_expression.parse_next(&mut input_ref) {
+        match parser(&mut input_ref) {
             Ok(expr) => {
                 match Self::skip_whitespace.parse_next(&mut input_ref) {
                     Ok(_) => {
@@ -46,6 +69,14 @@ impl ExpressionParser {
         }
     }
 
+    /// 在过滤上下文中解析表达式，结束后恢复之前的上下文
+    fn parse_filter_argument(input: &mut &str) -> PResult<PathExpression> {
+        let previous = IN_FILTER.with(|flag| flag.replace(true));
+        let result = Self::parse_comma_expression.parse_next(input);
+        IN_FILTER.with(|flag| flag.set(previous));
+        result
+    }
+
     /// 解析逗号表达式（最低优先级）
     fn parse_comma_expression(input: &mut &str) -> PResult<PathExpression> {
         let first = Self::parse_conditional_expression.parse_next(input)?;
@@ -210,10 +241,10 @@ impl ExpressionParser {
 
     /// 解析管道表达式
     fn parse_pipe_expression(input: &mut &str) -> PResult<PathExpression> {
-        let mut left = Self::parse_primary_expression.parse_next(input)?;
+        let mut left = Self::parse_additive_expression.parse_next(input)?;
 
         while Self::try_parse_pipe.parse_next(input).is_ok() {
-            let right = Self::parse_primary_expression.parse_next(input)?;
+            let right = Self::parse_additive_expression.parse_next(input)?;
             left = PathExpression::pipe(left, right);
         }
 
@@ -227,6 +258,60 @@ impl ExpressionParser {
         Ok(left)
     }
 
+    /// 解析加减表达式
+    fn parse_additive_expression(input: &mut &str) -> PResult<PathExpression> {
+        let mut left =
+            Self::parse_multiplicative_expression.parse_next(input)?;
+
+        loop {
+            let op = if Self::try_parse_operator('+', input).is_ok() {
+                ArithmeticOp::Add
+            } else if Self::try_parse_operator('-', input).is_ok() {
+                ArithmeticOp::Subtract
+            } else {
+                break;
+            };
+
+            let right =
+                Self::parse_multiplicative_expression.parse_next(input)?;
+            left = PathExpression::Arithmetic {
+                left: Box::new(left),
+                op,
+                right: Box::new(right),
+            };
+        }
+
+        Ok(left)
+    }
+
+    /// 解析乘除取模表达式
+    fn parse_multiplicative_expression(
+        input: &mut &str,
+    ) -> PResult<PathExpression> {
+        let mut left = Self::parse_primary_expression.parse_next(input)?;
+
+        loop {
+            let op = if Self::try_parse_operator('*', input).is_ok() {
+                ArithmeticOp::Multiply
+            } else if Self::try_parse_operator('/', input).is_ok() {
+                ArithmeticOp::Divide
+            } else if Self::try_parse_operator('%', input).is_ok() {
+                ArithmeticOp::Modulo
+            } else {
+                break;
+            };
+
+            let right = Self::parse_primary_expression.parse_next(input)?;
+            left = PathExpression::Arithmetic {
+                left: Box::new(left),
+                op,
+                right: Box::new(right),
+            };
+        }
+
+        Ok(left)
+    }
+
     /// 解析基础表达式（最高优先级）
     fn parse_primary_expression(input: &mut &str) -> PResult<PathExpression> {
         let _ = Self::skip_whitespace.parse_next(input);
@@ -234,6 +319,7 @@ impl ExpressionParser {
         let mut expr = alt((
             Self::parse_literal,
             Self::parse_parenthesized,
+            Self::parse_format_filter,
             Self::parse_function_call,
             Self::parse_path_or_identity,
         ))
@@ -251,9 +337,21 @@ impl ExpressionParser {
 
     /// 解析路径或恒等表达式
     fn parse_path_or_identity(input: &mut &str) -> PResult<PathExpression> {
+        let bare = !input.starts_with('.');
+
         // 先尝试解析路径段
         let segments = Self::parse_path_segments(input)?;
 
+        // 过滤表达式中的裸标识符是无参函数调用
+        if let [PathSegment::Field(name)] = segments.as_slice() {
+            if bare && IN_FILTER.with(Cell::get) {
+                return Ok(PathExpression::FunctionCall {
+                    name: name.clone(),
+                    args: vec![],
+                });
+            }
+        }
+
         // 如果只有一个段，并且是单独的点，则返回 Identity
         if segments.is_empty() {
             // 检查是否为单独的点
@@ -272,6 +370,16 @@ impl ExpressionParser {
         }
     }
 
+    /// 解析格式过滤器 `@name`，表示为名为 `@name` 的无参函数调用
+    fn parse_format_filter(input: &mut &str) -> PResult<PathExpression> {
+        ('@', Self::parse_identifier)
+            .map(|(_, name)| PathExpression::FunctionCall {
+                name: format!("@{name}"),
+                args: vec![],
+            })
+            .parse_next(input)
+    }
+
     /// 解析函数调用
     fn parse_function_call(input: &mut &str) -> PResult<PathExpression> {
         // 函数名（字母开头，后跟字母数字或下划线）
@@ -301,17 +409,24 @@ impl ExpressionParser {
         // 解析参数列表
         let mut args = Vec::new();
 
+        // paths 的参数是过滤表达式
+        let parse_argument = if function_name == "paths" {
+            Self::parse_filter_argument
+        } else {
+            Self::parse_comma_expression
+        };
+
         // 检查是否是空参数列表
         if !input.starts_with(')') {
             // 解析第一个参数
-            args.push(Self::parse_comma_expression.parse_next(input)?);
+            args.push(parse_argument(input)?);
             let _ = Self::skip_whitespace.parse_next(input);
 
-            // 解析后续参数
-            while input.starts_with(',') {
-                ','.parse_next(input)?;
+            // 解析后续参数（jq 风格使用分号分隔）
+            while input.starts_with(',') || input.starts_with(';') {
+                alt((',', ';')).parse_next(input)?;
                 let _ = Self::skip_whitespace.parse_next(input);
-                args.push(Self::parse_comma_expression.parse_next(input)?);
+                args.push(parse_argument(input)?);
                 let _ = Self::skip_whitespace.parse_next(input);
             }
         }
@@ -404,26 +519,15 @@ impl ExpressionParser {
         Ok(PathExpression::Literal(Value::Object(object)))
     }
 
-    /// 解析简单字面量值（用于数组和对象内部）
+    /// 解析数组和对象内部的字面量值（支持嵌套数组和对象）
     fn parse_simple_literal(input: &mut &str) -> PResult<Value> {
         let _ = Self::skip_whitespace.parse_next(input);
-        alt((
-            // 字符串
-            delimited('"', take_until(0.., "\""), '"')
-                .map(|s: &str| Value::String(s.to_string())),
-            // 数字
-            digit1
-                .try_map(|s: &str| s.parse::<i64>())
-                .map(|n| Value::Number(serde_json::Number::from(n))),
-            // 布尔值
-            alt((
-                "true".value(Value::Bool(true)),
-                "false".value(Value::Bool(false)),
-            )),
-            // null
-            "null".value(Value::Null),
-        ))
-        .parse_next(input)
+        Self::parse_literal
+            .verify_map(|expr| match expr {
+                PathExpression::Literal(value) => Some(value),
+                _ => None,
+            })
+            .parse_next(input)
     }
 
     /// 解析字符串字面量
@@ -435,16 +539,19 @@ impl ExpressionParser {
             .parse_next(input)
     }
 
-    /// 解析数字字面量
+    /// 解析数字字面量（整数或小数，可带负号）
     fn parse_number_literal(input: &mut &str) -> PResult<PathExpression> {
-        // 简单的整数解析
-        digit1
-            .try_map(|s: &str| s.parse::<i64>())
-            .map(|n| {
-                PathExpression::Literal(Value::Number(
-                    serde_json::Number::from(n),
-                ))
+        (opt('-'), digit1, opt(('.', digit1)))
+            .recognize()
+            .try_map(|s: &str| {
+                s.parse::<i64>().map(serde_json::Number::from).or_else(|_| {
+                    s.parse::<f64>().map(|f| {
+                        serde_json::Number::from_f64(f)
+                            .unwrap_or_else(|| 0.into())
+                    })
+                })
             })
+            .map(|n| PathExpression::Literal(Value::Number(n)))
             .parse_next(input)
     }
 
@@ -488,13 +595,17 @@ impl ExpressionParser {
             return Ok(vec![]);
         }
 
-        repeat(1.., Self::parse_segment).parse_next(input)
+        let first = Self::parse_segment.parse_next(input)?;
+        let rest: Vec<PathSegment> =
+            repeat(0.., Self::parse_following_segment).parse_next(input)?;
+        Ok(std::iter::once(first).chain(rest).collect())
     }
 
     /// 解析单个路径段（不包括类型过滤器，因为它会与管道操作符冲突）
     fn parse_segment(input: &mut &str) -> PResult<PathSegment> {
         alt((
             Self::parse_recursive_wildcard,
+            Self::parse_dot_wildcard,
             // 注意：在表达式上下文中不解析类型过滤器，避免与管道操作符冲突
             Self::parse_field,
             Self::parse_index,
@@ -503,12 +614,34 @@ impl ExpressionParser {
         .parse_next(input)
     }
 
+    /// 解析路径中第一个段之后的段
+    ///
+    /// 裸 `*` 在这里是乘法操作符（`.c*2`），通配符只能写作 `.*` 或 `[*]`。
+    fn parse_following_segment(input: &mut &str) -> PResult<PathSegment> {
+        alt((
+            Self::parse_recursive_wildcard,
+            Self::parse_dot_wildcard,
+            Self::parse_field,
+            Self::parse_index,
+        ))
+        .parse_next(input)
+    }
+
+    /// 解析 `.*` 形式的通配符
+    fn parse_dot_wildcard(input: &mut &str) -> PResult<PathSegment> {
+        ('.', Self::parse_wildcard)
+            .map(|(_, segment)| segment)
+            .parse_next(input)
+    }
+
     /// 解析字段访问
     fn parse_field(input: &mut &str) -> PResult<PathSegment> {
         alt((
             // 带点的字段访问 .field
             ('.', Self::parse_identifier)
                 .map(|(_, name)| PathSegment::Field(name)),
+            // 带引号的字段访问 ."key with spaces"
+            ('.', parse_quoted_field).map(|(_, name)| PathSegment::Field(name)),
             // 裸字段名
             Self::parse_identifier.map(PathSegment::Field),
         ))
@@ -543,9 +676,11 @@ impl ExpressionParser {
         '*'.value(PathSegment::Wildcard).parse_next(input)
     }
 
-    /// 解析递归通配符
+    /// 解析递归通配符 ** 或 .**
     fn parse_recursive_wildcard(input: &mut &str) -> PResult<PathSegment> {
-        "**".value(PathSegment::RecursiveWildcard).parse_next(input)
+        (opt('.'), "**")
+            .value(PathSegment::RecursiveWildcard)
+            .parse_next(input)
     }
 
     /// 解析类型过滤器（保留为备用，但在表达式解析中不使用）
@@ -657,6 +792,13 @@ impl ExpressionParser {
             .parse_next(input)
     }
 
+    /// 尝试解析单字符算术操作符
+    fn try_parse_operator(operator: char, input: &mut &str) -> PResult<()> {
+        (Self::skip_whitespace, operator, Self::skip_whitespace)
+            .void()
+            .parse_next(input)
+    }
+
     // 比较操作符解析器
     fn try_parse_lte(input: &mut &str) -> PResult<()> {
         (Self::skip_whitespace, "<=", Self::skip_whitespace)
@@ -696,6 +838,6 @@ impl ExpressionParser {
 }
 
 /// 便利函数：解析路径表达式
-pub fn parse_path_expression(input: &str) -> ParseResult<PathExpression> {
-    ExpressionParser::parse_path_expression(input)
+pub fn parse_path_expression(input: &str) -> XqpathResult<PathExpression> {
+    ExpressionParser::parse_path_expression(input).map_err(XqpathError::from)
 }
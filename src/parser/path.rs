@@ -1,12 +1,16 @@
 use winnow::{
     ascii::{alpha1, digit1},
-    combinator::{alt, delimited, repeat},
-    token::take_while,
+    combinator::{alt, delimited, eof, opt, preceded, repeat},
+    token::{any, none_of, take_while},
     PResult, Parser,
 };
 
+use crate::error::{XqpathError, XqpathResult};
+use serde::{Deserialize, Serialize};
+
 /// 路径段枚举，表示路径中的不同组件
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PathSegment {
     /// 字段访问，如 .field
     Field(String),
@@ -18,6 +22,8 @@ pub enum PathSegment {
     RecursiveWildcard,
     /// 类型过滤器，如 | string
     TypeFilter(String),
+    /// 可选路径段，如 .field? ，类型不匹配时返回空结果而不是报错
+    Optional(Box<PathSegment>),
 }
 
 /// 解析结果类型
@@ -53,6 +59,28 @@ fn parse_identifier(input: &mut &str) -> PResult<String> {
         .parse_next(input)
 }
 
+/// 判断字段名能否以裸标识符形式 `.name` 书写
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// 解析带引号的字段名 `"key"`，支持 JSON 字符串转义
+pub(crate) fn parse_quoted_field(input: &mut &str) -> PResult<String> {
+    (
+        '"',
+        repeat::<_, _, (), _, _>(
+            0..,
+            alt((('\\', any).void(), none_of(['"', '\\']).void())),
+        ),
+        '"',
+    )
+        .recognize()
+        .try_map(serde_json::from_str::<String>)
+        .parse_next(input)
+}
+
 /// 解析数字
 fn parse_number(input: &mut &str) -> PResult<usize> {
     digit1.try_map(|s: &str| s.parse()).parse_next(input)
@@ -65,11 +93,13 @@ fn skip_whitespace(input: &mut &str) -> PResult<()> {
         .parse_next(input)
 }
 
-/// 解析字段访问 .field 或裸字段 field
+/// 解析字段访问 .field、."quoted key" 或裸字段 field
 fn parse_field(input: &mut &str) -> PResult<PathSegment> {
     alt((
         // 带点的字段访问 .field
         ('.', parse_identifier).map(|(_, name)| PathSegment::Field(name)),
+        // 带引号的字段访问 ."key with spaces"
+        preceded('.', parse_quoted_field).map(PathSegment::Field),
         // 裸字段名（只在路径开始时或特定上下文中允许）
         parse_identifier.map(PathSegment::Field),
     ))
@@ -107,9 +137,11 @@ fn parse_wildcard(input: &mut &str) -> PResult<PathSegment> {
     '*'.value(PathSegment::Wildcard).parse_next(input)
 }
 
-/// 解析递归通配符 **
+/// 解析递归通配符 ** 或 .**
 fn parse_recursive_wildcard(input: &mut &str) -> PResult<PathSegment> {
-    "**".value(PathSegment::RecursiveWildcard).parse_next(input)
+    (opt('.'), "**")
+        .value(PathSegment::RecursiveWildcard)
+        .parse_next(input)
 }
 
 /// 解析类型过滤器 | type
@@ -124,18 +156,34 @@ fn parse_segment(input: &mut &str) -> PResult<PathSegment> {
     alt((
         parse_recursive_wildcard, // 必须在 wildcard 之前，因为 ** 包含 *
         parse_type_filter,        // 类型过滤器需要较早解析
-        parse_field,
-        parse_index,
-        parse_wildcard,
+        parse_optional_segment,
     ))
     .parse_next(input)
 }
 
+/// 解析字段、索引或通配符，带后缀 ? 时包装为可选路径段
+fn parse_optional_segment(input: &mut &str) -> PResult<PathSegment> {
+    (alt((parse_field, parse_index, parse_wildcard)), opt('?'))
+        .map(|(segment, optional)| match optional {
+            Some(_) => PathSegment::Optional(Box::new(segment)),
+            None => segment,
+        })
+        .parse_next(input)
+}
+
 /// 解析完整路径表达式
 fn parse_path_internal(input: &mut &str) -> PResult<Vec<PathSegment>> {
     // 跳过开头的空白字符
     skip_whitespace.parse_next(input)?;
 
+    // 单独的 `.` 表示根节点
+    if opt(('.', skip_whitespace, eof))
+        .parse_next(input)?
+        .is_some()
+    {
+        return Ok(vec![]);
+    }
+
     // 解析路径段序列
     let segments = repeat(0.., parse_segment).parse_next(input)?;
 
@@ -146,7 +194,26 @@ fn parse_path_internal(input: &mut &str) -> PResult<Vec<PathSegment>> {
 }
 
 /// 公共解析函数
-pub fn parse_path(input: &str) -> ParseResult<Vec<PathSegment>> {
+pub fn parse_path(input: &str) -> XqpathResult<Vec<PathSegment>> {
+    parse_segments(input).map_err(XqpathError::from)
+}
+
+/// 解析简单路径，返回带位置信息的 [`ParseError`]，供库内部使用
+pub(crate) fn parse_segments(input: &str) -> ParseResult<Vec<PathSegment>> {
+    let result = parse_path_inner(input);
+
+    #[cfg(feature = "logging")]
+    match &result {
+        Ok(segments) => {
+            tracing::trace!(input, segments = segments.len(), "path parsed")
+        }
+        Err(e) => tracing::debug!(input, error = %e, "path parse failed"),
+    }
+
+    result
+}
+
+fn parse_path_inner(input: &str) -> ParseResult<Vec<PathSegment>> {
     let mut input_ref = input;
     match parse_path_internal.parse_next(&mut input_ref) {
         Ok(segments) => {
@@ -166,6 +233,118 @@ pub fn parse_path(input: &str) -> ParseResult<Vec<PathSegment>> {
     }
 }
 
+impl PathSegment {
+    /// 解析输入开头的单个路径段，返回该段与剩余未解析的输入
+    ///
+    /// 适合交互式场景中逐段构建路径（如自动补全、逐步导航）：
+    ///
+    /// ```rust
+    /// use xqpath::PathSegment;
+    ///
+    /// let (segment, rest) = PathSegment::parse_single(".a[0].b").unwrap();
+    /// assert_eq!(segment, PathSegment::Field("a".to_string()));
+    /// assert_eq!(rest, "[0].b");
+    /// ```
+    pub fn parse_single(input: &str) -> ParseResult<(PathSegment, &str)> {
+        let mut input_ref = input;
+        let parsed = (skip_whitespace, parse_segment)
+            .map(|(_, segment)| segment)
+            .parse_next(&mut input_ref);
+
+        match parsed {
+            Ok(segment) => Ok((segment, input_ref)),
+            Err(e) => Err(ParseError {
+                message: format!("Failed to parse path segment: {e:?}"),
+                position: input.len() - input_ref.len(),
+            }),
+        }
+    }
+
+    /// 将单个路径段渲染为 jq 风格的字符串
+    ///
+    /// 无法写成标识符的字段名会加引号（如 `."a b"`），
+    /// 保证结果能被 [`parse_path`] 重新解析。
+    pub fn to_jq_string(&self) -> String {
+        match self {
+            PathSegment::Field(name) if is_identifier(name) => {
+                format!(".{name}")
+            }
+            PathSegment::Field(name) => format!(
+                ".{}",
+                serde_json::to_string(name).expect("string serialization")
+            ),
+            PathSegment::Index(idx) => format!("[{idx}]"),
+            PathSegment::Wildcard => "[*]".to_string(),
+            PathSegment::RecursiveWildcard => "**".to_string(),
+            PathSegment::TypeFilter(typ) => format!(" | {typ}"),
+            PathSegment::Optional(inner) => {
+                format!("{}?", inner.to_jq_string())
+            }
+        }
+    }
+}
+
+impl PathSegment {
+    /// 转换为 JSONPath 风格的字符串片段，如 `.name`、`['a b']`、`[0]`
+    ///
+    /// 类型过滤器在 JSONPath 中没有对应写法，返回 `None`。
+    pub fn to_jsonpath_string(&self) -> Option<String> {
+        let rendered = match self {
+            PathSegment::Field(name) if is_identifier(name) => {
+                format!(".{name}")
+            }
+            PathSegment::Field(name) => format!(
+                "['{}']",
+                name.replace('\\', "\\\\").replace('\'', "\\'")
+            ),
+            PathSegment::Index(idx) => format!("[{idx}]"),
+            PathSegment::Wildcard => "[*]".to_string(),
+            PathSegment::RecursiveWildcard => "..".to_string(),
+            PathSegment::TypeFilter(_) => return None,
+            PathSegment::Optional(inner) => return inner.to_jsonpath_string(),
+        };
+        Some(rendered)
+    }
+}
+
+impl std::fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_jq_string())
+    }
+}
+
+/// 将路径段序列格式化为 jq 风格的路径字符串，空路径表示根节点 `.`
+///
+/// 结果可以直接交给 [`parse_path`]（以及 `set`/`del`）重新解析。
+pub fn format_path(segments: &[PathSegment]) -> String {
+    if segments.is_empty() {
+        return ".".to_string();
+    }
+
+    segments.iter().map(PathSegment::to_jq_string).collect()
+}
+
+/// 将路径段序列格式化为 JSONPath 字符串（如 `$.users[0].name`），
+/// 空路径表示根节点 `$`
+///
+/// 路径中包含类型过滤器或以递归通配符结尾时无法表示，返回 `None`。
+pub fn format_jsonpath(segments: &[PathSegment]) -> Option<String> {
+    let mut rendered = String::from("$");
+    let mut after_recursive = false;
+    for segment in segments {
+        let part = segment.to_jsonpath_string()?;
+        // `..` 之后直接跟成员名或 `*`，如 `$..name`、`$..*`
+        let part = match part.strip_prefix('.') {
+            Some(name) if after_recursive => name,
+            _ if after_recursive && part == "[*]" => "*",
+            _ => &part,
+        };
+        rendered.push_str(part);
+        after_recursive = *segment == PathSegment::RecursiveWildcard;
+    }
+    (!after_recursive).then_some(rendered)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,6 +371,31 @@ mod tests {
     fn test_parse_recursive_wildcard() {
         let result = parse_path("**").unwrap();
         assert_eq!(result, vec![PathSegment::RecursiveWildcard]);
+
+        let result = parse_path(".**.name").unwrap();
+        assert_eq!(
+            result,
+            vec![
+                PathSegment::RecursiveWildcard,
+                PathSegment::Field("name".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_optional_segment() {
+        let result = parse_path(".a.b?[0]?").unwrap();
+        assert_eq!(
+            result,
+            vec![
+                PathSegment::Field("a".to_string()),
+                PathSegment::Optional(Box::new(PathSegment::Field(
+                    "b".to_string()
+                ))),
+                PathSegment::Optional(Box::new(PathSegment::Index(0))),
+            ]
+        );
+        assert_eq!(format_path(&result), ".a.b?[0]?");
     }
 
     #[test]
@@ -232,4 +436,127 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_parse_single_segment_by_segment() {
+        let (first, rest) = PathSegment::parse_single(".a[0].b").unwrap();
+        assert_eq!(first, PathSegment::Field("a".to_string()));
+        assert_eq!(rest, "[0].b");
+
+        let (second, rest) = PathSegment::parse_single(rest).unwrap();
+        assert_eq!(second, PathSegment::Index(0));
+        assert_eq!(rest, ".b");
+
+        let (third, rest) = PathSegment::parse_single(rest).unwrap();
+        assert_eq!(third, PathSegment::Field("b".to_string()));
+        assert_eq!(rest, "");
+
+        let (optional, rest) = PathSegment::parse_single("  .x?.y").unwrap();
+        assert_eq!(
+            optional,
+            PathSegment::Optional(Box::new(PathSegment::Field(
+                "x".to_string()
+            )))
+        );
+        assert_eq!(rest, ".y");
+
+        assert!(PathSegment::parse_single("").is_err());
+        assert!(PathSegment::parse_single("[").is_err());
+    }
+
+    #[test]
+    fn test_format_path_roundtrip() {
+        assert_eq!(format_path(&[]), ".");
+        assert_eq!(parse_path(".").unwrap(), vec![]);
+
+        let path = parse_path(".users[0].name").unwrap();
+        assert_eq!(format_path(&path), ".users[0].name");
+        assert_eq!(parse_path(&format_path(&path)).unwrap(), path);
+    }
+
+    #[test]
+    fn test_segment_serde_roundtrip() {
+        let path = parse_path(".users[*].tags[0]** | string").unwrap();
+        let json = serde_json::to_string(&path).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"field":"users"},"wildcard",{"field":"tags"},{"index":0},"recursive_wildcard",{"type_filter":"string"}]"#
+        );
+
+        let restored: Vec<PathSegment> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, path);
+    }
+
+    #[test]
+    fn test_quoted_field() {
+        let path = parse_path(r#"."a b"[0]."x\"y".plain"#).unwrap();
+        assert_eq!(
+            path,
+            vec![
+                PathSegment::Field("a b".to_string()),
+                PathSegment::Index(0),
+                PathSegment::Field("x\"y".to_string()),
+                PathSegment::Field("plain".to_string()),
+            ]
+        );
+        assert_eq!(format_path(&path), r#"."a b"[0]."x\"y".plain"#);
+
+        for name in ["", "1st", "_id", "a-b", "a.b", "键", "tab\t"] {
+            let path = vec![PathSegment::Field(name.to_string())];
+            assert_eq!(parse_path(&format_path(&path)).unwrap(), path);
+        }
+    }
+
+    #[test]
+    fn test_format_path_extracts_original() {
+        use crate::extractor::extract;
+        use crate::value::stream::walk_paths;
+
+        let data = serde_json::json!({
+            "plain": {"nested": [1, {"deep": true}]},
+            "with space": {"a-b": "x", "1st": [null]},
+            "quote\"key": {"": 0},
+            "键": "值"
+        });
+
+        let mut checked = 0;
+        for (path, value) in walk_paths(&data) {
+            let rendered = format_path(&path);
+            let reparsed = parse_path(&rendered).unwrap();
+            assert_eq!(reparsed, path, "{rendered}");
+            assert_eq!(extract(&data, &reparsed).unwrap(), vec![value]);
+            checked += 1;
+        }
+        assert_eq!(checked, 12);
+        assert_eq!(
+            extract(&data, &parse_path(".").unwrap()).unwrap(),
+            vec![&data]
+        );
+    }
+
+    #[test]
+    fn test_format_jsonpath_matches_jq_location() {
+        let cases = [
+            (".users[0].name", "$.users[0].name"),
+            (r#"."a b"[2]"#, "$['a b'][2]"),
+            (r#"."it's"."1st""#, r"$['it\'s']['1st']"),
+            (".items[*].id", "$.items[*].id"),
+            ("**.name", "$..name"),
+            ("**[0]", "$..[0]"),
+            ("**[*]", "$..*"),
+            (".", "$"),
+        ];
+        for (jq, jsonpath) in cases {
+            let segments = parse_path(jq).unwrap();
+            assert_eq!(format_path(&segments), jq);
+            assert_eq!(format_jsonpath(&segments).as_deref(), Some(jsonpath));
+        }
+
+        let filtered = parse_path(".a | string").unwrap();
+        assert_eq!(format_jsonpath(&filtered), None);
+
+        // 单独的 `..` 不是合法的 JSONPath
+        let trailing = parse_path(".a.**").unwrap();
+        assert_eq!(format_jsonpath(&trailing), None);
+    }
 }
@@ -0,0 +1,479 @@
+use crate::error::XqpathResult;
+use crate::extractor::{extract, ExtractError, Extractor};
+use crate::parser::path::{format_path, parse_path, PathSegment};
+use crate::value::format::{detect_format, parse_bytes, FormatError};
+use crate::value::stream::locate_paths;
+use serde::de::{self, Deserializer as _, SeqAccess, Visitor};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+
+/// 检测格式、解析数据并按路径提取，返回匹配值的拷贝
+///
+/// 与 `query!` 宏行为一致，但返回可按类别匹配的 [`crate::XqpathError`]。
+pub fn query_str(input: &str, path: &str) -> XqpathResult<Vec<Value>> {
+    let format = detect_format(input)?;
+    let parsed = format.parse(input)?;
+    let segments = parse_path(path)?;
+    let values = extract(&parsed, &segments)?;
+    Ok(values.into_iter().cloned().collect())
+}
+
+/// 直接从字节输入检测格式、解析并按路径提取
+///
+/// 适合读取大文件的场景，非 UTF-8 输入返回 [`crate::FormatError::InvalidEncoding`]。
+pub fn query_bytes(data: &[u8], path: &str) -> XqpathResult<Vec<Value>> {
+    let (_, parsed) = parse_bytes(data)?;
+    let segments = parse_path(path)?;
+    let values = extract(&parsed, &segments)?;
+    Ok(values.into_iter().cloned().collect())
+}
+
+/// 消费输入并把匹配的子树从文档中移出，而不是逐个克隆
+///
+/// 借用式的 [`extract`] 返回引用，`query!` 宏需要再深拷贝每个匹配值；
+/// 这里调用方交出数据的所有权，匹配值通过 `std::mem::take` 从解析后的文档中取出，
+/// 匹配整个根节点时完全不发生拷贝。代价是原文档在调用后不可再用。
+/// 匹配结果互相嵌套或重复（例如 `**` 同时匹配父节点与子节点）时无法移动，
+/// 此时退回到克隆。
+pub fn query_into(data: String, path: &str) -> XqpathResult<Vec<Value>> {
+    let format = detect_format(&data)?;
+    let mut parsed = format.parse(&data)?;
+    drop(data);
+    let segments = parse_path(path)?;
+
+    let locations = {
+        let matches = extract(&parsed, &segments)?;
+        if let [only] = matches.as_slice() {
+            if std::ptr::eq(*only, &parsed) {
+                return Ok(vec![parsed]);
+            }
+        }
+        match locate_disjoint(&parsed, &matches) {
+            Some(locations) => locations,
+            None => return Ok(matches.into_iter().cloned().collect()),
+        }
+    };
+
+    Ok(locations
+        .iter()
+        .map(|location| take_at(&mut parsed, location))
+        .collect())
+}
+
+/// 查找每个匹配节点在文档中的位置，存在重复、嵌套或找不到的匹配时返回 `None`
+fn locate_disjoint(
+    root: &Value,
+    matches: &[&Value],
+) -> Option<Vec<Vec<PathSegment>>> {
+    let located = locate_paths(root, matches);
+    if located.len() != matches.len() {
+        return None;
+    }
+    // 按文档顺序，嵌套的匹配紧跟在其祖先（或祖先内的其他匹配）之后
+    if located
+        .windows(2)
+        .any(|pair| pair[1].0.starts_with(&pair[0].0))
+    {
+        return None;
+    }
+
+    let mut locations: HashMap<*const Value, Vec<PathSegment>> = located
+        .into_iter()
+        .map(|(path, node)| (node as *const Value, path))
+        .collect();
+    matches
+        .iter()
+        .map(|value| locations.remove(&(*value as *const Value)))
+        .collect()
+}
+
+/// 将指定位置的节点取出，原位置留下 `null`
+fn take_at(root: &mut Value, location: &[PathSegment]) -> Value {
+    let mut node = root;
+    for segment in location {
+        node = match (segment, node) {
+            (PathSegment::Field(key), Value::Object(map)) => map.get_mut(key),
+            (PathSegment::Index(index), Value::Array(arr)) => {
+                arr.get_mut(*index)
+            }
+            _ => None,
+        }
+        .expect("location was recorded from the same document");
+    }
+    std::mem::take(node)
+}
+
+/// 流式处理顶层 JSON 数组：逐个解析元素并按路径提取，匹配值交给回调
+///
+/// 任意时刻只在内存中保留一个数组元素，适合包含海量记录的大文件。
+/// 路径作用于每个元素而不是整个文档；顶层不是数组时返回
+/// [`crate::FormatError`]。成功时返回处理的元素数量。
+///
+/// ```rust
+/// use xqpath::query_stream;
+///
+/// let input = r#"[{"id": 1}, {"id": 2}]"#;
+/// let mut ids = Vec::new();
+/// let count = query_stream(input.as_bytes(), ".id", |v| ids.push(v)).unwrap();
+/// assert_eq!(count, 2);
+/// assert_eq!(ids, vec![1, 2]);
+/// ```
+pub fn query_stream<R, F>(
+    reader: R,
+    path: &str,
+    mut callback: F,
+) -> XqpathResult<usize>
+where
+    R: Read,
+    F: FnMut(Value),
+{
+    let segments = parse_path(path)?;
+    let mut failure = None;
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+
+    let visitor = StreamVisitor {
+        segments: &segments,
+        callback: &mut callback,
+        failure: &mut failure,
+    };
+    let count = match deserializer.deserialize_seq(visitor) {
+        Ok(count) => count,
+        Err(e) => {
+            return Err(match failure {
+                Some(extract_error) => extract_error.into(),
+                None => FormatError::ParseError(e.to_string()).into(),
+            })
+        }
+    };
+    deserializer
+        .end()
+        .map_err(|e| FormatError::ParseError(e.to_string()))?;
+
+    Ok(count)
+}
+
+/// 逐个消费数组元素的访问器，提取失败时记录原始错误并中止解析
+struct StreamVisitor<'a, F> {
+    segments: &'a [PathSegment],
+    callback: &'a mut F,
+    failure: &'a mut Option<ExtractError>,
+}
+
+impl<'de, F: FnMut(Value)> Visitor<'de> for StreamVisitor<'_, F> {
+    type Value = usize;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a JSON array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<usize, A::Error> {
+        let mut count = 0;
+        while let Some(element) = seq.next_element::<Value>()? {
+            match Extractor::extract(&element, self.segments) {
+                Ok(values) => {
+                    values.into_iter().cloned().for_each(&mut *self.callback)
+                }
+                Err(e) => {
+                    let message = e.to_string();
+                    *self.failure = Some(e);
+                    return Err(de::Error::custom(message));
+                }
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// 构建器风格的路径查询，无需解析路径字符串
+///
+/// ```rust
+/// use xqpath::Query;
+/// use serde_json::json;
+///
+/// let data = json!({"users": [{"name": "Alice"}, {"name": "Bob"}]});
+/// let names = Query::new().field("users").wildcard().field("name").run(&data).unwrap();
+/// assert_eq!(names, vec![&json!("Alice"), &json!("Bob")]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    segments: Vec<PathSegment>,
+}
+
+impl Query {
+    /// 创建指向根节点的空查询
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 追加字段访问 `.name`
+    pub fn field(mut self, name: impl Into<String>) -> Self {
+        self.segments.push(PathSegment::Field(name.into()));
+        self
+    }
+
+    /// 追加数组索引 `[index]`
+    pub fn index(mut self, index: usize) -> Self {
+        self.segments.push(PathSegment::Index(index));
+        self
+    }
+
+    /// 追加通配符 `[*]`
+    pub fn wildcard(mut self) -> Self {
+        self.segments.push(PathSegment::Wildcard);
+        self
+    }
+
+    /// 追加递归通配符 `**`
+    pub fn recursive(mut self) -> Self {
+        self.segments.push(PathSegment::RecursiveWildcard);
+        self
+    }
+
+    /// 追加类型过滤器 `| type`
+    pub fn type_filter(mut self, type_name: impl Into<String>) -> Self {
+        self.segments
+            .push(PathSegment::TypeFilter(type_name.into()));
+        self
+    }
+
+    /// 获取已构建的路径段
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// 转换为路径段序列
+    pub fn into_segments(self) -> Vec<PathSegment> {
+        self.segments
+    }
+
+    /// 在给定值上执行查询
+    pub fn run<'a>(
+        &self,
+        root: &'a Value,
+    ) -> Result<Vec<&'a Value>, ExtractError> {
+        Extractor::extract(root, &self.segments)
+    }
+}
+
+impl From<Query> for Vec<PathSegment> {
+    fn from(query: Query) -> Self {
+        query.segments
+    }
+}
+
+impl From<Vec<PathSegment>> for Query {
+    fn from(segments: Vec<PathSegment>) -> Self {
+        Self { segments }
+    }
+}
+
+impl std::fmt::Display for Query {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_path(&self.segments))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::path::parse_path;
+    use serde_json::json;
+
+    #[test]
+    fn test_builder_matches_parsed_path() {
+        let query = Query::new().field("users").index(0).field("name");
+        assert_eq!(query.segments(), parse_path(".users[0].name").unwrap());
+        assert_eq!(query.to_string(), ".users[0].name");
+    }
+
+    #[test]
+    fn test_run_query() {
+        let data = json!({
+            "users": [
+                {"name": "Alice", "tags": ["a", 1]},
+                {"name": "Bob", "tags": ["b", 2]}
+            ]
+        });
+
+        let first = Query::new().field("users").index(0).field("name");
+        assert_eq!(first.run(&data).unwrap(), vec![&json!("Alice")]);
+
+        let strings = Query::new()
+            .field("users")
+            .wildcard()
+            .field("tags")
+            .wildcard()
+            .type_filter("string");
+        assert_eq!(strings.run(&data).unwrap(), vec![&json!("a"), &json!("b")]);
+
+        let all = Query::new().recursive().run(&data).unwrap();
+        assert!(all.contains(&&json!("Bob")));
+
+        assert_eq!(Query::new().run(&data).unwrap(), vec![&data]);
+    }
+
+    #[test]
+    fn test_query_str() {
+        let yaml = "user:\n  name: Alice\n";
+        assert_eq!(
+            query_str(yaml, ".user.name").unwrap(),
+            vec![json!("Alice")]
+        );
+
+        assert!(matches!(
+            query_str(yaml, ".user[").unwrap_err(),
+            crate::XqpathError::Parse(_)
+        ));
+        assert!(matches!(
+            query_str("", ".user").unwrap_err(),
+            crate::XqpathError::Format(_)
+        ));
+        assert!(matches!(
+            query_str("items: [1]\n", ".items[3]").unwrap_err(),
+            crate::XqpathError::Extract(_)
+        ));
+    }
+
+    #[test]
+    fn test_query_bytes() {
+        let data = br#"{"users": [{"name": "Alice"}, {"name": "Bob"}]}"#;
+        assert_eq!(
+            query_bytes(data, ".users[*].name").unwrap(),
+            vec![json!("Alice"), json!("Bob")]
+        );
+        assert_eq!(
+            query_bytes(b"user:\n  name: Alice\n", ".user.name").unwrap(),
+            vec![json!("Alice")]
+        );
+        assert!(matches!(
+            query_bytes(b"\xFF\xFE", ".a").unwrap_err(),
+            crate::XqpathError::Format(crate::FormatError::InvalidEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_query_into() {
+        let json = r#"{"users": [{"name": "Alice"}, {"name": "Bob"}]}"#;
+
+        assert_eq!(
+            query_into(json.to_string(), ".users[*].name").unwrap(),
+            vec![json!("Alice"), json!("Bob")]
+        );
+        assert_eq!(
+            query_into(json.to_string(), ".users[1]").unwrap(),
+            vec![json!({"name": "Bob"})]
+        );
+        assert_eq!(
+            query_into("user:\n  name: Alice\n".to_string(), ".user.name")
+                .unwrap(),
+            vec![json!("Alice")]
+        );
+
+        // 匹配整个根节点时直接返回解析结果
+        assert_eq!(
+            query_into("[1, 2]".to_string(), "").unwrap(),
+            vec![json!([1, 2])]
+        );
+        assert_eq!(
+            query_into("[1, 2]".to_string(), "[*] | number").unwrap(),
+            vec![json!(1), json!(2)]
+        );
+
+        // 嵌套匹配退回到克隆，结果与借用式提取一致
+        let nested = r#"{"a": {"a": {"a": 1}}}"#;
+        assert_eq!(
+            query_into(nested.to_string(), "**").unwrap(),
+            query_str(nested, "**").unwrap()
+        );
+
+        assert!(matches!(
+            query_into(json.to_string(), ".users[").unwrap_err(),
+            crate::XqpathError::Parse(_)
+        ));
+    }
+
+    #[test]
+    fn test_locate_disjoint() {
+        let data = json!({"a": [1, {"b": 2}], "c": 3});
+        let matches = extract(&data, &parse_path(".a[*]").unwrap()).unwrap();
+        assert_eq!(
+            locate_disjoint(&data, &matches).unwrap(),
+            vec![
+                vec![
+                    PathSegment::Field("a".to_string()),
+                    PathSegment::Index(0)
+                ],
+                vec![
+                    PathSegment::Field("a".to_string()),
+                    PathSegment::Index(1)
+                ],
+            ]
+        );
+
+        // 同一节点出现两次或嵌套匹配时无法移动
+        let outer = &data["a"];
+        let inner = &data["a"][1];
+        assert!(locate_disjoint(&data, &[outer, outer]).is_none());
+        assert!(locate_disjoint(&data, &[outer, inner]).is_none());
+        assert_eq!(locate_disjoint(&data, &[&data]).unwrap(), vec![vec![]]);
+    }
+
+    #[test]
+    fn test_query_stream() {
+        let input = r#"[
+            {"name": "Alice", "tags": ["a", "b"]},
+            {"name": "Bob", "tags": []},
+            {"name": "Carol", "tags": ["c"]}
+        ]"#;
+
+        let mut names = Vec::new();
+        let count =
+            query_stream(input.as_bytes(), ".name", |v| names.push(v)).unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(names, vec![json!("Alice"), json!("Bob"), json!("Carol")]);
+
+        let mut tags = Vec::new();
+        query_stream(input.as_bytes(), ".tags[*]", |v| tags.push(v)).unwrap();
+        assert_eq!(tags, vec![json!("a"), json!("b"), json!("c")]);
+
+        assert_eq!(query_stream("[]".as_bytes(), ".a", |_| {}).unwrap(), 0);
+
+        assert!(matches!(
+            query_stream(r#"{"a": 1}"#.as_bytes(), ".a", |_| {}).unwrap_err(),
+            crate::XqpathError::Format(_)
+        ));
+        assert!(matches!(
+            query_stream("[1, 2".as_bytes(), ".a", |_| {}).unwrap_err(),
+            crate::XqpathError::Format(_)
+        ));
+        assert!(matches!(
+            query_stream("[] []".as_bytes(), ".a", |_| {}).unwrap_err(),
+            crate::XqpathError::Format(_)
+        ));
+        assert!(matches!(
+            query_stream("[[1]]".as_bytes(), "[3]", |_| {}).unwrap_err(),
+            crate::XqpathError::Extract(_)
+        ));
+        assert!(matches!(
+            query_stream("[]".as_bytes(), ".a[", |_| {}).unwrap_err(),
+            crate::XqpathError::Parse(_)
+        ));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let segments: Vec<PathSegment> =
+            Query::new().field("a").wildcard().into();
+        assert_eq!(
+            segments,
+            vec![PathSegment::Field("a".to_string()), PathSegment::Wildcard]
+        );
+        assert_eq!(Query::from(segments.clone()).into_segments(), segments);
+    }
+}
@@ -1,6 +1,12 @@
 #[cfg(feature = "update")]
+use crate::error::{XqpathError, XqpathResult};
+#[cfg(feature = "update")]
+use crate::parser::path::format_path;
+#[cfg(feature = "update")]
 use crate::parser::path::PathSegment;
 #[cfg(feature = "update")]
+use crate::value::stream::walk_paths;
+#[cfg(feature = "update")]
 use serde_json::Value;
 
 #[cfg(feature = "update")]
@@ -13,6 +19,8 @@ pub enum UpdateError {
     InvalidPath(String),
     InvalidOperation(String),
     CannotCreatePath(String),
+    /// 重命名的目标键已存在，附带所有冲突对象的路径
+    KeyCollision(String, Vec<String>),
 }
 
 #[cfg(feature = "update")]
@@ -38,6 +46,9 @@ impl std::fmt::Display for UpdateError {
             UpdateError::CannotCreatePath(msg) => {
                 write!(f, "Cannot create path: {msg}")
             }
+            UpdateError::KeyCollision(key, paths) => {
+                write!(f, "Key '{key}' already exists at: {}", paths.join(", "))
+            }
         }
     }
 }
@@ -115,6 +126,9 @@ impl Updater {
             PathSegment::TypeFilter(_) => Err(UpdateError::InvalidOperation(
                 "Cannot update with type filter".to_string(),
             )),
+            PathSegment::Optional(_) => Err(UpdateError::InvalidOperation(
+                "Cannot update with optional segment".to_string(),
+            )),
         }
     }
 
@@ -248,6 +262,163 @@ impl Updater {
         }
     }
 
+    /// 删除指定路径上的值
+    pub fn delete(
+        root: &mut Value,
+        path: &[PathSegment],
+    ) -> Result<(), UpdateError> {
+        let (last, parents) = path.split_last().ok_or_else(|| {
+            UpdateError::InvalidOperation(
+                "Cannot delete the root value".to_string(),
+            )
+        })?;
+
+        Self::visit_mut(root, parents, 0, &mut |parent| {
+            Self::delete_segment(parent, last)
+        })
+    }
+
+    /// 将对象递归合并到指定路径上的值，路径不存在时直接设置
+    pub fn merge(
+        root: &mut Value,
+        path: &[PathSegment],
+        patch: Value,
+    ) -> Result<(), UpdateError> {
+        let result = Self::visit_mut(root, path, 0, &mut |target| {
+            merge_values(target, patch.clone());
+            Ok(())
+        });
+
+        match result {
+            Err(UpdateError::PathNotFound(_)) => {
+                Self::update(root, path, patch)
+            }
+            other => other,
+        }
+    }
+
+    /// 沿路径定位所有已存在的目标值并依次调用回调（不创建缺失节点）
+    fn visit_mut(
+        current: &mut Value,
+        remaining_path: &[PathSegment],
+        depth: usize,
+        f: &mut dyn FnMut(&mut Value) -> Result<(), UpdateError>,
+    ) -> Result<(), UpdateError> {
+        if depth > 1000 {
+            return Err(UpdateError::InvalidPath(
+                "Maximum recursion depth exceeded".to_string(),
+            ));
+        }
+
+        let Some((segment, rest_path)) = remaining_path.split_first() else {
+            return f(current);
+        };
+
+        match segment {
+            PathSegment::Field(field_name) => {
+                let type_name = Self::get_value_type_name(current);
+                let obj = current.as_object_mut().ok_or_else(|| {
+                    UpdateError::TypeMismatch(
+                        "object".to_string(),
+                        type_name.to_string(),
+                    )
+                })?;
+                let child = obj.get_mut(field_name).ok_or_else(|| {
+                    UpdateError::PathNotFound(field_name.to_string())
+                })?;
+                Self::visit_mut(child, rest_path, depth + 1, f)
+            }
+            PathSegment::Index(index) => {
+                let type_name = Self::get_value_type_name(current);
+                let arr = current.as_array_mut().ok_or_else(|| {
+                    UpdateError::TypeMismatch(
+                        "array".to_string(),
+                        type_name.to_string(),
+                    )
+                })?;
+                let len = arr.len();
+                let child = arr
+                    .get_mut(*index)
+                    .ok_or(UpdateError::IndexOutOfBounds(*index, len))?;
+                Self::visit_mut(child, rest_path, depth + 1, f)
+            }
+            PathSegment::Wildcard => match current {
+                Value::Object(map) => {
+                    for (_, child) in map.iter_mut() {
+                        Self::visit_mut(child, rest_path, depth + 1, f)?;
+                    }
+                    Ok(())
+                }
+                Value::Array(arr) => {
+                    for child in arr.iter_mut() {
+                        Self::visit_mut(child, rest_path, depth + 1, f)?;
+                    }
+                    Ok(())
+                }
+                _ => Err(UpdateError::TypeMismatch(
+                    "object or array".to_string(),
+                    Self::get_value_type_name(current).to_string(),
+                )),
+            },
+            PathSegment::RecursiveWildcard => {
+                Err(UpdateError::InvalidOperation(
+                    "Cannot update with recursive wildcard".to_string(),
+                ))
+            }
+            PathSegment::TypeFilter(_) => Err(UpdateError::InvalidOperation(
+                "Cannot update with type filter".to_string(),
+            )),
+            PathSegment::Optional(_) => Err(UpdateError::InvalidOperation(
+                "Cannot update with optional segment".to_string(),
+            )),
+        }
+    }
+
+    /// 从父节点中删除单个路径段对应的子值
+    fn delete_segment(
+        parent: &mut Value,
+        segment: &PathSegment,
+    ) -> Result<(), UpdateError> {
+        match (segment, parent) {
+            (PathSegment::Field(field_name), Value::Object(map)) => map
+                .shift_remove(field_name)
+                .map(|_| ())
+                .ok_or_else(|| UpdateError::PathNotFound(field_name.clone())),
+            (PathSegment::Index(index), Value::Array(arr)) => {
+                if *index < arr.len() {
+                    arr.remove(*index);
+                    Ok(())
+                } else {
+                    Err(UpdateError::IndexOutOfBounds(*index, arr.len()))
+                }
+            }
+            (PathSegment::Wildcard, Value::Object(map)) => {
+                map.clear();
+                Ok(())
+            }
+            (PathSegment::Wildcard, Value::Array(arr)) => {
+                arr.clear();
+                Ok(())
+            }
+            (PathSegment::Field(_), other) => Err(UpdateError::TypeMismatch(
+                "object".to_string(),
+                Self::get_value_type_name(other).to_string(),
+            )),
+            (PathSegment::Index(_), other) => Err(UpdateError::TypeMismatch(
+                "array".to_string(),
+                Self::get_value_type_name(other).to_string(),
+            )),
+            (PathSegment::Wildcard, other) => Err(UpdateError::TypeMismatch(
+                "object or array".to_string(),
+                Self::get_value_type_name(other).to_string(),
+            )),
+            _ => Err(UpdateError::InvalidOperation(
+                "Cannot delete with recursive wildcard, type filter or optional segment"
+                    .to_string(),
+            )),
+        }
+    }
+
     /// 根据路径段类型创建适当的中间值
     fn create_intermediate_value(next_segment: &PathSegment) -> Value {
         match next_segment {
@@ -268,6 +439,40 @@ impl Updater {
             Value::Object(_) => "object",
         }
     }
+
+    /// 递归地把文档中所有名为 `from` 的对象键重命名为 `to`，返回重命名的次数
+    ///
+    /// 键在对象中的位置保持不变。只要有一个同时包含 `from` 与 `to` 的对象，
+    /// 就不做任何修改并返回列出全部冲突位置的 [`UpdateError::KeyCollision`]。
+    pub fn rename_key(
+        root: &mut Value,
+        from: &str,
+        to: &str,
+    ) -> Result<usize, UpdateError> {
+        if from == to {
+            return Ok(0);
+        }
+
+        let has_collision = |node: &Value| {
+            node.as_object().is_some_and(|map| {
+                map.contains_key(from) && map.contains_key(to)
+            })
+        };
+        let mut collisions = Vec::new();
+        if has_collision(root) {
+            collisions.push(format_path(&[]));
+        }
+        collisions.extend(
+            walk_paths(root)
+                .filter(|(_, node)| has_collision(node))
+                .map(|(path, _)| format_path(&path)),
+        );
+        if !collisions.is_empty() {
+            return Err(UpdateError::KeyCollision(to.to_string(), collisions));
+        }
+
+        Ok(rename_key_in(root, from, to))
+    }
 }
 
 #[cfg(feature = "update")]
@@ -276,8 +481,74 @@ pub fn update(
     root: &mut Value,
     path: &[PathSegment],
     new_value: Value,
-) -> Result<(), UpdateError> {
-    Updater::update(root, path, new_value)
+) -> XqpathResult<()> {
+    Updater::update(root, path, new_value).map_err(XqpathError::from)
+}
+
+#[cfg(feature = "update")]
+/// 便利函数，删除指定路径上的值
+pub fn delete(root: &mut Value, path: &[PathSegment]) -> XqpathResult<()> {
+    Updater::delete(root, path).map_err(XqpathError::from)
+}
+
+#[cfg(feature = "update")]
+/// 便利函数，递归重命名对象键，见 [`Updater::rename_key`]
+pub fn rename_key(
+    root: &mut Value,
+    from: &str,
+    to: &str,
+) -> XqpathResult<usize> {
+    Updater::rename_key(root, from, to).map_err(XqpathError::from)
+}
+
+#[cfg(feature = "update")]
+fn rename_key_in(value: &mut Value, from: &str, to: &str) -> usize {
+    match value {
+        Value::Object(map) => {
+            let mut renamed = 0;
+            if map.contains_key(from) {
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(key, child)| {
+                        if key == from {
+                            (to.to_string(), child)
+                        } else {
+                            (key, child)
+                        }
+                    })
+                    .collect();
+                renamed += 1;
+            }
+            renamed
+                + map
+                    .values_mut()
+                    .map(|child| rename_key_in(child, from, to))
+                    .sum::<usize>()
+        }
+        Value::Array(arr) => arr
+            .iter_mut()
+            .map(|item| rename_key_in(item, from, to))
+            .sum(),
+        _ => 0,
+    }
+}
+
+#[cfg(feature = "update")]
+/// 递归合并：对象按键合并，其他类型直接以补丁值替换
+pub fn merge_values(target: &mut Value, patch: Value) {
+    match (target, patch) {
+        (Value::Object(target_map), Value::Object(patch_map)) => {
+            for (key, patch_value) in patch_map {
+                match target_map.get_mut(&key) {
+                    Some(existing) => merge_values(existing, patch_value),
+                    None => {
+                        target_map.insert(key, patch_value);
+                    }
+                }
+            }
+        }
+        (target, patch) => *target = patch,
+    }
 }
 
 #[cfg(feature = "update")]
@@ -392,6 +663,9 @@ impl ConfigurableUpdater {
             PathSegment::TypeFilter(_) => Err(UpdateError::InvalidOperation(
                 "Cannot update with type filter".to_string(),
             )),
+            PathSegment::Optional(_) => Err(UpdateError::InvalidOperation(
+                "Cannot update with optional segment".to_string(),
+            )),
         }
     }
 
@@ -637,4 +911,93 @@ mod tests {
         assert_eq!(data[5], 6);
         assert_eq!(data[3], Value::Null);
     }
+
+    #[test]
+    fn test_delete_field_and_index() {
+        let mut data = json!({"a": 1, "b": [1, 2, 3], "c": true});
+
+        delete(&mut data, &parse_path(".a").unwrap()).unwrap();
+        delete(&mut data, &parse_path(".b[1]").unwrap()).unwrap();
+
+        assert_eq!(data, json!({"b": [1, 3], "c": true}));
+        assert!(delete(&mut data, &parse_path(".missing").unwrap()).is_err());
+        assert!(delete(&mut data, &[]).is_err());
+    }
+
+    #[test]
+    fn test_delete_wildcard_children() {
+        let mut data =
+            json!({"users": [{"id": 1, "pw": "x"}, {"id": 2, "pw": "y"}]});
+
+        delete(&mut data, &parse_path(".users[*].pw").unwrap()).unwrap();
+        assert_eq!(data, json!({"users": [{"id": 1}, {"id": 2}]}));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut data = json!({"server": {"host": "localhost", "port": 80}});
+
+        Updater::merge(
+            &mut data,
+            &parse_path(".server").unwrap(),
+            json!({"port": 8080, "tls": true}),
+        )
+        .unwrap();
+        Updater::merge(
+            &mut data,
+            &parse_path(".log").unwrap(),
+            json!({"level": "info"}),
+        )
+        .unwrap();
+
+        assert_eq!(
+            data,
+            json!({
+                "server": {"host": "localhost", "port": 8080, "tls": true},
+                "log": {"level": "info"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_rename_key() {
+        let mut data = json!({
+            "old": 1,
+            "keep": {"old": {"old": true}},
+            "list": [{"old": "a", "x": 1}, "old", {"other": 2}]
+        });
+
+        assert_eq!(rename_key(&mut data, "old", "new").unwrap(), 4);
+        assert_eq!(
+            data,
+            json!({
+                "new": 1,
+                "keep": {"new": {"new": true}},
+                "list": [{"new": "a", "x": 1}, "old", {"other": 2}]
+            })
+        );
+        assert_eq!(rename_key(&mut data, "missing", "x").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_rename_key_collision() {
+        let original = json!({
+            "a": 1,
+            "b": 2,
+            "nested": {"items": [{"a": 1, "b": 2}, {"a": 3}]}
+        });
+        let mut data = original.clone();
+
+        let err = rename_key(&mut data, "a", "b").unwrap_err();
+        match &err {
+            XqpathError::Update(UpdateError::KeyCollision(key, paths)) => {
+                assert_eq!(key, "b");
+                assert_eq!(paths, &vec![".", ".nested.items[0]"]);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+        assert!(err.to_string().contains(".nested.items[0]"));
+        // 冲突时文档保持不变
+        assert_eq!(data, original);
+    }
 }
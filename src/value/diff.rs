@@ -0,0 +1,185 @@
+use crate::parser::path::{format_path, PathSegment};
+use serde_json::Value;
+
+/// 两个文档之间的单条结构化差异
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    /// 新文档中新增的值
+    Added {
+        path: Vec<PathSegment>,
+        value: Value,
+    },
+    /// 旧文档中被移除的值
+    Removed {
+        path: Vec<PathSegment>,
+        value: Value,
+    },
+    /// 同一路径上发生变化的值
+    Changed {
+        path: Vec<PathSegment>,
+        old: Value,
+        new: Value,
+    },
+}
+
+impl Difference {
+    /// 获取差异所在的路径
+    pub fn path(&self) -> &[PathSegment] {
+        match self {
+            Difference::Added { path, .. }
+            | Difference::Removed { path, .. }
+            | Difference::Changed { path, .. } => path,
+        }
+    }
+
+    /// 获取 jq 风格的路径字符串
+    pub fn path_string(&self) -> String {
+        format_path(self.path())
+    }
+
+    /// 获取差异类型名称：added / removed / changed
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Difference::Added { .. } => "added",
+            Difference::Removed { .. } => "removed",
+            Difference::Changed { .. } => "changed",
+        }
+    }
+}
+
+/// 比较两个值，返回按路径排列的结构化差异列表
+///
+/// 对象按键递归比较，数组按索引递归比较；类型不同或标量不等时报告为 `changed`。
+pub fn diff_values(a: &Value, b: &Value) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    let mut path = Vec::new();
+    diff_recursive(a, b, &mut path, &mut differences);
+    differences
+}
+
+fn diff_recursive(
+    a: &Value,
+    b: &Value,
+    path: &mut Vec<PathSegment>,
+    differences: &mut Vec<Difference>,
+) {
+    match (a, b) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                path.push(PathSegment::Field(key.clone()));
+                match new_map.get(key) {
+                    Some(new_value) => {
+                        diff_recursive(old_value, new_value, path, differences)
+                    }
+                    None => differences.push(Difference::Removed {
+                        path: path.clone(),
+                        value: old_value.clone(),
+                    }),
+                }
+                path.pop();
+            }
+
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    path.push(PathSegment::Field(key.clone()));
+                    differences.push(Difference::Added {
+                        path: path.clone(),
+                        value: new_value.clone(),
+                    });
+                    path.pop();
+                }
+            }
+        }
+        (Value::Array(old_arr), Value::Array(new_arr)) => {
+            for (idx, old_value) in old_arr.iter().enumerate() {
+                path.push(PathSegment::Index(idx));
+                match new_arr.get(idx) {
+                    Some(new_value) => {
+                        diff_recursive(old_value, new_value, path, differences)
+                    }
+                    None => differences.push(Difference::Removed {
+                        path: path.clone(),
+                        value: old_value.clone(),
+                    }),
+                }
+                path.pop();
+            }
+
+            for (idx, new_value) in
+                new_arr.iter().enumerate().skip(old_arr.len())
+            {
+                path.push(PathSegment::Index(idx));
+                differences.push(Difference::Added {
+                    path: path.clone(),
+                    value: new_value.clone(),
+                });
+                path.pop();
+            }
+        }
+        _ => {
+            if a != b {
+                differences.push(Difference::Changed {
+                    path: path.clone(),
+                    old: a.clone(),
+                    new: b.clone(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_identical() {
+        let value = json!({"a": [1, 2, {"b": true}]});
+        assert!(diff_values(&value, &value).is_empty());
+    }
+
+    #[test]
+    fn test_diff_objects() {
+        let old = json!({"name": "app", "port": 80, "debug": true});
+        let new = json!({"name": "app", "port": 8080, "tls": {"on": true}});
+
+        let diffs = diff_values(&old, &new);
+        assert_eq!(diffs.len(), 3);
+        assert!(diffs.contains(&Difference::Changed {
+            path: vec![PathSegment::Field("port".to_string())],
+            old: json!(80),
+            new: json!(8080),
+        }));
+
+        let removed: Vec<_> =
+            diffs.iter().filter(|d| d.kind() == "removed").collect();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].path_string(), ".debug");
+
+        let added: Vec<_> =
+            diffs.iter().filter(|d| d.kind() == "added").collect();
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].path_string(), ".tls");
+    }
+
+    #[test]
+    fn test_diff_nested_arrays() {
+        let old = json!({"users": [{"name": "Alice"}, {"name": "Bob"}]});
+        let new = json!({"users": [{"name": "Alice"}, {"name": "Carol"}, {"name": "Dan"}]});
+
+        let diffs = diff_values(&old, &new);
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].kind(), "changed");
+        assert_eq!(diffs[0].path_string(), ".users[1].name");
+        assert_eq!(diffs[1].kind(), "added");
+        assert_eq!(diffs[1].path_string(), ".users[2]");
+    }
+
+    #[test]
+    fn test_diff_type_change_at_root() {
+        let diffs = diff_values(&json!([1]), &json!({"a": 1}));
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].path_string(), ".");
+    }
+}
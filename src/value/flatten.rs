@@ -0,0 +1,166 @@
+//! 点分隔键的扁平化与还原 - 在嵌套配置和 `KEY=value` 形式之间转换
+//!
+//! 数组索引渲染为数字段（`a.0.b`）。键本身包含 `.`，或对象键全为数字时，
+//! 还原无法区分它们与嵌套层级/数组索引，此时往返转换是有损的。
+
+use crate::value::stream::{dotted_path, stream_value};
+use serde_json::{Map, Value};
+
+/// 将嵌套文档扁平化为以点分隔键为键的单层对象
+///
+/// 空数组和空对象作为值保留，以保证往返无损；非容器输入原样返回。
+pub fn flatten_object(value: &Value) -> Value {
+    if !value.is_array() && !value.is_object() {
+        return value.clone();
+    }
+
+    let flattened: Map<String, Value> = stream_value(value)
+        .filter(|(path, _)| !path.is_empty())
+        .map(|(path, leaf)| (dotted_path(&path), leaf.clone()))
+        .collect();
+    Value::Object(flattened)
+}
+
+/// 将点分隔键的单层对象还原为嵌套文档，[`flatten_object`] 的逆操作
+///
+/// 全为数字且小于键总数的段视为数组索引，缺失的位置以 `null` 填充；
+/// 更大的数字段（如 `build.20240101`）保留为对象键，避免分配巨大的数组。
+/// 同一位置的键冲突时（如 `a` 与 `a.b`）后出现的键覆盖先出现的。非对象输入原样返回。
+pub fn unflatten_object(value: &Value) -> Value {
+    let Value::Object(map) = value else {
+        return value.clone();
+    };
+
+    let mut root = Value::Null;
+    for (key, leaf) in map {
+        let segments: Vec<&str> = key.split('.').collect();
+        insert_at(&mut root, &segments, leaf.clone(), map.len());
+    }
+    if root.is_null() {
+        Value::Object(Map::new())
+    } else {
+        root
+    }
+}
+
+/// 扁平化的数组有 n 个元素时至少产生 n 个键，因此索引以 `max_index` 为上界
+fn insert_at(
+    node: &mut Value,
+    segments: &[&str],
+    leaf: Value,
+    max_index: usize,
+) {
+    let Some((segment, rest)) = segments.split_first() else {
+        *node = leaf;
+        return;
+    };
+
+    let index = parse_index(segment).filter(|&index| index < max_index);
+    let child = match index {
+        Some(index) => {
+            if !node.is_array() {
+                *node = Value::Array(Vec::new());
+            }
+            let arr = node.as_array_mut().expect("node is an array");
+            if arr.len() <= index {
+                arr.resize(index + 1, Value::Null);
+            }
+            &mut arr[index]
+        }
+        None => {
+            if !node.is_object() {
+                *node = Value::Object(Map::new());
+            }
+            node.as_object_mut()
+                .expect("node is an object")
+                .entry(segment.to_string())
+                .or_insert(Value::Null)
+        }
+    };
+    insert_at(child, rest, leaf, max_index);
+}
+
+/// 全为 ASCII 数字的段视为数组索引
+fn parse_index(segment: &str) -> Option<usize> {
+    if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) {
+        segment.parse().ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_flatten_object() {
+        let data = json!({
+            "db": {"host": "localhost", "port": 5432},
+            "hosts": [{"name": "a"}, "b"],
+            "empty": {}
+        });
+
+        assert_eq!(
+            flatten_object(&data),
+            json!({
+                "db.host": "localhost",
+                "db.port": 5432,
+                "hosts.0.name": "a",
+                "hosts.1": "b",
+                "empty": {}
+            })
+        );
+        assert_eq!(flatten_object(&json!(3)), json!(3));
+    }
+
+    #[test]
+    fn test_unflatten_roundtrip() {
+        let data = json!({
+            "db": {"host": "localhost", "tags": []},
+            "hosts": [{"name": "a"}, null, "c", "d", "e", "f", "g", "h", "i", "j", "k"],
+            "top": [[1, 2], [3]]
+        });
+
+        assert_eq!(unflatten_object(&flatten_object(&data)), data);
+        assert_eq!(
+            unflatten_object(&json!({"a.1": true, "b": 0})),
+            json!({"a": [null, true], "b": 0})
+        );
+        assert_eq!(unflatten_object(&json!({})), json!({}));
+        assert_eq!(unflatten_object(&json!([1])), json!([1]));
+    }
+
+    #[test]
+    fn test_unflatten_large_numeric_segments() {
+        // 超出键总数的数字段保留为对象键
+        assert_eq!(
+            unflatten_object(&json!({"a.18446744073709551615": 1})),
+            json!({"a": {"18446744073709551615": 1}})
+        );
+        assert_eq!(
+            unflatten_object(&json!({"build.20240101": "x"})),
+            json!({"build": {"20240101": "x"}})
+        );
+        assert_eq!(
+            unflatten_object(&json!({"a.1": true})),
+            json!({"a": {"1": true}})
+        );
+    }
+
+    #[test]
+    fn test_unflatten_conflicts() {
+        // 后出现的键覆盖先出现的标量
+        assert_eq!(
+            unflatten_object(&json!({"a": 1, "a.b": 2})),
+            json!({"a": {"b": 2}})
+        );
+        // 包含点的键无法与嵌套层级区分
+        let dotted = json!({"a.b": 1});
+        assert_eq!(
+            unflatten_object(&flatten_object(&dotted)),
+            json!({"a": {"b": 1}})
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::error::{XqpathError, XqpathResult};
+use crate::value::yaml::{YamlSpecialValues, YamlSupport};
 use serde_json::Value;
 use std::fmt;
 
@@ -7,6 +9,12 @@ pub enum FormatError {
     ParseError(String),
     SerializeError(String),
     UnsupportedFormat(String),
+    /// 输入可被多种格式解析，首个候选为推荐格式
+    Ambiguous(Vec<String>),
+    /// 输入为空或仅包含空白
+    Empty,
+    /// 输入不是合法的 UTF-8
+    InvalidEncoding(String),
 }
 
 impl fmt::Display for FormatError {
@@ -19,12 +27,52 @@ impl fmt::Display for FormatError {
             FormatError::UnsupportedFormat(format) => {
                 write!(f, "Unsupported format: {format}")
             }
+            FormatError::Ambiguous(candidates) => {
+                write!(
+                    f,
+                    "Ambiguous input format: matches {}",
+                    candidates.join(", ")
+                )
+            }
+            FormatError::Empty => write!(f, "Empty input"),
+            FormatError::InvalidEncoding(msg) => {
+                write!(f, "Invalid UTF-8 input: {msg}")
+            }
         }
     }
 }
 
 impl std::error::Error for FormatError {}
 
+/// 去除开头的 UTF-8 BOM（Windows 编辑器保存的文件常带有）
+pub fn strip_bom(input: &str) -> &str {
+    input.strip_prefix('\u{FEFF}').unwrap_or(input)
+}
+
+/// 按字符截断字符串，超出 `max_chars` 时以 `...` 结尾且总长不超过 `max_chars`
+///
+/// 以字符而非字节计数，不会切在多字节字符（中文、emoji）中间。
+///
+/// ```rust
+/// use xqpath::truncate_str;
+///
+/// assert_eq!(truncate_str("hello", 10), "hello");
+/// assert_eq!(truncate_str("数据结构与算法", 5), "数据...");
+/// ```
+pub fn truncate_str(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        return s.to_string();
+    }
+    if max_chars <= 3 {
+        return s.chars().take(max_chars).collect();
+    }
+    let end = s
+        .char_indices()
+        .nth(max_chars - 3)
+        .map_or(s.len(), |(i, _)| i);
+    format!("{}...", &s[..end])
+}
+
 /// 数据格式处理统一接口
 pub trait ValueFormat: Send + Sync {
     /// 解析输入字符串为 Value
@@ -42,7 +90,7 @@ pub struct JsonFormat;
 
 impl ValueFormat for JsonFormat {
     fn parse(&self, input: &str) -> Result<Value, FormatError> {
-        serde_json::from_str(input).map_err(|e| {
+        serde_json::from_str(strip_bom(input)).map_err(|e| {
             FormatError::ParseError(format!("JSON parse error: {e}"))
         })
     }
@@ -64,10 +112,12 @@ pub struct YamlFormat;
 impl ValueFormat for YamlFormat {
     fn parse(&self, input: &str) -> Result<Value, FormatError> {
         // 先解析为 serde_yaml::Value，然后转换为 serde_json::Value
-        let yaml_value: serde_yaml::Value = serde_yaml::from_str(input)
-            .map_err(|e| {
+        let mut yaml_value: serde_yaml::Value =
+            serde_yaml::from_str(strip_bom(input)).map_err(|e| {
                 FormatError::ParseError(format!("YAML parse error: {e}"))
             })?;
+        // 非有限浮点数（.inf/.nan）无法用 JSON 数字表示，按 jq 的约定转换
+        YamlSpecialValues::replace_special_floats(&mut yaml_value);
 
         // 转换为 JSON Value 以保持统一
         let json_str = serde_json::to_string(&yaml_value).map_err(|e| {
@@ -84,9 +134,7 @@ impl ValueFormat for YamlFormat {
     }
 
     fn to_string(&self, value: &Value) -> Result<String, FormatError> {
-        serde_yaml::to_string(value).map_err(|e| {
-            FormatError::SerializeError(format!("YAML serialize error: {e}"))
-        })
+        YamlSupport::to_string(value)
     }
 
     fn name(&self) -> &'static str {
@@ -95,11 +143,18 @@ impl ValueFormat for YamlFormat {
 }
 
 /// 自动检测输入格式并返回相应的格式处理器
-pub fn detect_format(input: &str) -> Result<Box<dyn ValueFormat>, FormatError> {
-    let trimmed = input.trim_start();
+pub fn detect_format(input: &str) -> XqpathResult<Box<dyn ValueFormat>> {
+    detect_input_format(input).map_err(XqpathError::from)
+}
+
+/// 检测输入格式，返回具体的 [`FormatError`]，供库内部使用
+pub(crate) fn detect_input_format(
+    input: &str,
+) -> Result<Box<dyn ValueFormat>, FormatError> {
+    let trimmed = strip_bom(input).trim();
 
     if trimmed.is_empty() {
-        return Err(FormatError::UnsupportedFormat("empty input".to_string()));
+        return Err(FormatError::Empty);
     }
 
     // 检测 JSON 格式
@@ -111,6 +166,42 @@ pub fn detect_format(input: &str) -> Result<Box<dyn ValueFormat>, FormatError> {
     }
 }
 
+/// 直接从字节解析输入，返回检测到的格式与解析结果
+///
+/// 输入只做一次 UTF-8 校验，随后借用为 `&str` 解析，不会复制为 `String`；
+/// 非 UTF-8 输入返回 [`FormatError::InvalidEncoding`] 而不做有损转换。
+pub fn parse_bytes(data: &[u8]) -> XqpathResult<(Box<dyn ValueFormat>, Value)> {
+    let text = std::str::from_utf8(data)
+        .map_err(|e| FormatError::InvalidEncoding(e.to_string()))?;
+    let format = detect_input_format(text)?;
+    let value = format.parse(text)?;
+    Ok((format, value))
+}
+
+/// 严格检测输入格式，实际解析每个候选格式
+///
+/// 输入同时是合法 JSON 与 YAML 且两者解析结果不同时返回
+/// [`FormatError::Ambiguous`]（JSON 在前，为推荐格式），调用方可提示用户显式指定格式。
+/// 结果一致时优先返回 JSON。
+pub fn detect_format_strict(input: &str) -> XqpathResult<Box<dyn ValueFormat>> {
+    let detected = detect_input_format(input)?;
+
+    let json = JsonFormat.parse(input).ok();
+    let yaml = YamlFormat.parse(input).ok();
+
+    match (json, yaml) {
+        (Some(json), Some(yaml)) if json != yaml => {
+            Err(XqpathError::Format(FormatError::Ambiguous(vec![
+                "json".to_string(),
+                "yaml".to_string(),
+            ])))
+        }
+        (Some(_), _) => Ok(Box::new(JsonFormat)),
+        (None, Some(_)) => Ok(Box::new(YamlFormat)),
+        (None, None) => Ok(detected),
+    }
+}
+
 /// 格式注册表，支持运行时格式扩展
 pub struct FormatRegistry {
     formats: std::collections::HashMap<String, Box<dyn ValueFormat>>,
@@ -145,6 +236,19 @@ impl FormatRegistry {
     pub fn list_formats(&self) -> Vec<&str> {
         self.formats.keys().map(|s| s.as_str()).collect()
     }
+
+    /// 自动检测输入格式并转换为注册表中名为 `to` 的格式
+    pub fn convert(
+        &self,
+        input: &str,
+        to: &str,
+    ) -> Result<String, FormatError> {
+        let target = self
+            .get(to)
+            .ok_or_else(|| FormatError::UnsupportedFormat(to.to_string()))?;
+        let value = detect_input_format(input)?.parse(input)?;
+        target.to_string(&value)
+    }
 }
 
 impl Default for FormatRegistry {
@@ -157,6 +261,17 @@ impl Default for FormatRegistry {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_truncate_str() {
+        assert_eq!(truncate_str("short", 5), "short");
+        assert_eq!(truncate_str("abcdefgh", 6), "abc...");
+        assert_eq!(truncate_str("中文字符串测试", 6), "中文字...");
+        assert_eq!(truncate_str("😀😃😄😁😆😅", 5), "😀😃...");
+        assert_eq!(truncate_str("👨‍👩‍👧", 4), "👨...");
+        assert_eq!(truncate_str("😀😃😄😁", 2), "😀😃");
+        assert_eq!(truncate_str("", 0), "");
+    }
+
     #[test]
     fn test_json_format() {
         let format = JsonFormat;
@@ -186,6 +301,22 @@ age: 30
         assert!(output.contains("Alice"));
     }
 
+    #[test]
+    fn test_yaml_format_integer_forms() {
+        let format = YamlFormat;
+        let value = format
+            .parse("id: 08\noctal: 0o17\nhex: 0x1F\ntime: 1:30\n")
+            .unwrap();
+        assert_eq!(value["id"], "08");
+        assert_eq!(value["octal"], 15);
+        assert_eq!(value["hex"], 31);
+        assert_eq!(value["time"], "1:30");
+
+        let output = format.to_string(&value).unwrap();
+        assert!(output.contains("'1:30'"), "{output}");
+        assert_eq!(format.parse(&output).unwrap(), value);
+    }
+
     #[test]
     fn test_detect_json_format() {
         let input = r#"{"name": "Alice"}"#;
@@ -200,6 +331,87 @@ age: 30
         assert_eq!(format.name(), "yaml");
     }
 
+    #[test]
+    fn test_detect_empty_input() {
+        assert!(matches!(
+            detect_format(""),
+            Err(XqpathError::Format(FormatError::Empty))
+        ));
+        assert!(matches!(
+            detect_format("  \n\t"),
+            Err(XqpathError::Format(FormatError::Empty))
+        ));
+    }
+
+    #[test]
+    fn test_detect_format_strict() {
+        let format = detect_format_strict(r#"{"name": "Alice"}"#).unwrap();
+        assert_eq!(format.name(), "json");
+
+        // 流式 YAML 不是合法 JSON
+        let format = detect_format_strict("{name: Alice}").unwrap();
+        assert_eq!(format.name(), "yaml");
+
+        // `-0` 在 JSON 中为浮点 -0.0，在 YAML 中为整数 0
+        match detect_format_strict("[-0]") {
+            Err(XqpathError::Format(FormatError::Ambiguous(candidates))) => {
+                assert_eq!(candidates, vec!["json", "yaml"])
+            }
+            other => panic!("expected ambiguous, got {:?}", other.err()),
+        }
+
+        let err = FormatError::Ambiguous(vec!["json".into(), "yaml".into()]);
+        assert_eq!(
+            err.to_string(),
+            "Ambiguous input format: matches json, yaml"
+        );
+    }
+
+    #[test]
+    fn test_detect_with_bom() {
+        let input = "\u{FEFF}{\"name\": \"Alice\"}";
+        let format = detect_format(input).unwrap();
+        assert_eq!(format.name(), "json");
+        assert_eq!(format.parse(input).unwrap()["name"], "Alice");
+
+        let input = "\u{FEFF}\n  \n[1, 2]\n";
+        let format = detect_format(input).unwrap();
+        assert_eq!(format.name(), "json");
+        assert_eq!(format.parse(input).unwrap()[1], 2);
+
+        let input = "\u{FEFF}name: Alice\nage: 30\n";
+        let format = detect_format(input).unwrap();
+        assert_eq!(format.name(), "yaml");
+        let value = format.parse(input).unwrap();
+        assert_eq!(value["name"], "Alice");
+        assert_eq!(value["age"], 30);
+
+        assert!(matches!(
+            detect_format("\u{FEFF} \n"),
+            Err(XqpathError::Format(FormatError::Empty))
+        ));
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        let (format, value) = parse_bytes(br#"{"name": "Alice"}"#).unwrap();
+        assert_eq!(format.name(), "json");
+        assert_eq!(value["name"], "Alice");
+
+        let (format, value) = parse_bytes(b"\xEF\xBB\xBFname: Bob\n").unwrap();
+        assert_eq!(format.name(), "yaml");
+        assert_eq!(value["name"], "Bob");
+
+        assert!(matches!(
+            parse_bytes(b"{\"name\": \"\xFF\"}"),
+            Err(XqpathError::Format(FormatError::InvalidEncoding(_)))
+        ));
+        assert!(matches!(
+            parse_bytes(b" "),
+            Err(XqpathError::Format(FormatError::Empty))
+        ));
+    }
+
     #[test]
     fn test_format_registry() {
         let registry = FormatRegistry::new();
@@ -214,4 +426,46 @@ age: 30
         assert!(formats.contains(&"json"));
         assert!(formats.contains(&"yaml"));
     }
+
+    /// 只能输出的示例格式：每个顶层键一行 `key=value`
+    struct KeyValueFormat;
+
+    impl ValueFormat for KeyValueFormat {
+        fn parse(&self, _input: &str) -> Result<Value, FormatError> {
+            Err(FormatError::UnsupportedFormat("kv".to_string()))
+        }
+
+        fn to_string(&self, value: &Value) -> Result<String, FormatError> {
+            let map = value.as_object().ok_or_else(|| {
+                FormatError::SerializeError("expected an object".to_string())
+            })?;
+            Ok(map.iter().map(|(k, v)| format!("{k}={v}\n")).collect())
+        }
+
+        fn name(&self) -> &'static str {
+            "kv"
+        }
+    }
+
+    #[test]
+    fn test_format_registry_convert() {
+        let mut registry = FormatRegistry::new();
+        registry.register("kv".to_string(), Box::new(KeyValueFormat));
+
+        assert_eq!(registry.convert("a: 1\n", "kv").unwrap(), "a=1\n");
+        assert_eq!(registry.convert(r#"{"a": 1}"#, "yml").unwrap(), "a: 1\n");
+
+        assert!(matches!(
+            registry.convert("[1]", "kv"),
+            Err(FormatError::SerializeError(_))
+        ));
+        assert!(matches!(
+            registry.convert("{}", "toml"),
+            Err(FormatError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            registry.convert("", "kv"),
+            Err(FormatError::Empty)
+        ));
+    }
 }
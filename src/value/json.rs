@@ -1,4 +1,4 @@
-use crate::value::format::FormatError;
+use crate::value::format::{strip_bom, FormatError};
 use serde_json::Value;
 
 /// JSON 特定的便利函数和扩展
@@ -7,7 +7,7 @@ pub struct JsonSupport;
 impl JsonSupport {
     /// 解析 JSON 字符串
     pub fn parse(input: &str) -> Result<Value, FormatError> {
-        serde_json::from_str(input).map_err(|e| {
+        serde_json::from_str(strip_bom(input)).map_err(|e| {
             FormatError::ParseError(format!("JSON parse error: {e}"))
         })
     }
@@ -1,10 +1,24 @@
+pub mod diff;
+pub mod flatten;
 pub mod format;
 pub mod json;
+pub mod patch;
+pub mod stream;
 pub mod yaml;
 
+pub use diff::{diff_values, Difference};
+pub use flatten::{flatten_object, unflatten_object};
 pub use format::{
-    detect_format, FormatError, FormatRegistry, JsonFormat, ValueFormat,
-    YamlFormat,
+    detect_format, detect_format_strict, parse_bytes, strip_bom, truncate_str,
+    FormatError, FormatRegistry, JsonFormat, ValueFormat, YamlFormat,
 };
 pub use json::{JsonPath, JsonSupport};
-pub use yaml::{YamlFormatter, YamlSpecialValues, YamlSupport};
+pub use patch::{
+    apply_json_patch, apply_merge_patch, parse_json_pointer, JsonPatch,
+    PatchError, PatchOperation,
+};
+pub use stream::{
+    dotted_path, leaf_paths, path_to_value, stream_value, walk_paths, PathWalk,
+    ValueStream,
+};
+pub use yaml::{YamlFormatter, YamlQuoteStyle, YamlSpecialValues, YamlSupport};
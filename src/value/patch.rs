@@ -0,0 +1,482 @@
+use crate::error::{XqpathError, XqpathResult};
+use serde_json::Value;
+use std::fmt;
+
+/// JSON Patch 应用错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError {
+    /// 补丁文档格式不正确
+    InvalidPatch(String),
+    /// JSON Pointer 格式不正确
+    InvalidPointer(String),
+    /// 目标路径不存在
+    PathNotFound(String),
+    /// test 操作比较失败
+    TestFailed(String),
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::InvalidPatch(msg) => write!(f, "Invalid patch: {msg}"),
+            PatchError::InvalidPointer(pointer) => {
+                write!(f, "Invalid JSON pointer: {pointer}")
+            }
+            PatchError::PathNotFound(pointer) => {
+                write!(f, "Path not found: {pointer}")
+            }
+            PatchError::TestFailed(pointer) => {
+                write!(f, "Test operation failed at: {pointer}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// RFC 6902 补丁操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+/// RFC 6902 JSON Patch 文档
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct JsonPatch {
+    pub operations: Vec<PatchOperation>,
+}
+
+impl JsonPatch {
+    /// 从补丁文档（操作对象数组）解析
+    pub fn from_value(patch: &Value) -> Result<Self, PatchError> {
+        let ops = patch.as_array().ok_or_else(|| {
+            PatchError::InvalidPatch(
+                "patch document must be an array".to_string(),
+            )
+        })?;
+
+        let operations = ops
+            .iter()
+            .enumerate()
+            .map(|(i, op)| {
+                Self::parse_operation(op).map_err(|e| match e {
+                    PatchError::InvalidPatch(msg) => PatchError::InvalidPatch(
+                        format!("operation #{}: {msg}", i + 1),
+                    ),
+                    other => other,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { operations })
+    }
+
+    fn parse_operation(op: &Value) -> Result<PatchOperation, PatchError> {
+        let obj = op.as_object().ok_or_else(|| {
+            PatchError::InvalidPatch("operation must be an object".to_string())
+        })?;
+
+        let string_field = |name: &str| -> Result<String, PatchError> {
+            obj.get(name)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    PatchError::InvalidPatch(format!(
+                        "missing string member '{name}'"
+                    ))
+                })
+        };
+        let value_field = || -> Result<Value, PatchError> {
+            obj.get("value").cloned().ok_or_else(|| {
+                PatchError::InvalidPatch("missing member 'value'".to_string())
+            })
+        };
+
+        let path = string_field("path")?;
+        match string_field("op")?.as_str() {
+            "add" => Ok(PatchOperation::Add {
+                path,
+                value: value_field()?,
+            }),
+            "remove" => Ok(PatchOperation::Remove { path }),
+            "replace" => Ok(PatchOperation::Replace {
+                path,
+                value: value_field()?,
+            }),
+            "move" => Ok(PatchOperation::Move {
+                from: string_field("from")?,
+                path,
+            }),
+            "copy" => Ok(PatchOperation::Copy {
+                from: string_field("from")?,
+                path,
+            }),
+            "test" => Ok(PatchOperation::Test {
+                path,
+                value: value_field()?,
+            }),
+            other => Err(PatchError::InvalidPatch(format!(
+                "unknown operation '{other}'"
+            ))),
+        }
+    }
+
+    /// 将补丁应用到目标值；任一操作失败时目标保持不变
+    pub fn apply(&self, target: &mut Value) -> Result<(), PatchError> {
+        let mut working = target.clone();
+        for operation in &self.operations {
+            apply_operation(&mut working, operation)?;
+        }
+        *target = working;
+        Ok(())
+    }
+}
+
+/// 解析 JSON Pointer (RFC 6901) 为引用令牌序列
+pub fn parse_json_pointer(pointer: &str) -> XqpathResult<Vec<String>> {
+    pointer_tokens(pointer).map_err(XqpathError::from)
+}
+
+fn pointer_tokens(pointer: &str) -> Result<Vec<String>, PatchError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let rest = pointer
+        .strip_prefix('/')
+        .ok_or_else(|| PatchError::InvalidPointer(pointer.to_string()))?;
+
+    rest.split('/')
+        .map(|token| {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                if c == '~' {
+                    match chars.next() {
+                        Some('0') => unescaped.push('~'),
+                        Some('1') => unescaped.push('/'),
+                        _ => {
+                            return Err(PatchError::InvalidPointer(
+                                pointer.to_string(),
+                            ))
+                        }
+                    }
+                } else {
+                    unescaped.push(c);
+                }
+            }
+            Ok(unescaped)
+        })
+        .collect()
+}
+
+/// 将 RFC 6902 补丁文档应用到目标值
+pub fn apply_json_patch(target: &mut Value, patch: &Value) -> XqpathResult<()> {
+    JsonPatch::from_value(patch)?.apply(target)?;
+    Ok(())
+}
+
+/// 应用 RFC 7386 JSON Merge Patch：补丁中的 null 删除键，对象递归合并
+pub fn apply_merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch_map) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(serde_json::Map::new());
+    }
+
+    if let Value::Object(target_map) = target {
+        for (key, patch_value) in patch_map {
+            if patch_value.is_null() {
+                remove_key(target_map, key);
+            } else {
+                let entry =
+                    target_map.entry(key.clone()).or_insert(Value::Null);
+                apply_merge_patch(entry, patch_value);
+            }
+        }
+    }
+}
+
+fn apply_operation(
+    target: &mut Value,
+    operation: &PatchOperation,
+) -> Result<(), PatchError> {
+    match operation {
+        PatchOperation::Add { path, value } => {
+            add_value(target, path, value.clone())
+        }
+        PatchOperation::Remove { path } => {
+            remove_value(target, path).map(|_| ())
+        }
+        PatchOperation::Replace { path, value } => {
+            let slot = get_mut(target, &pointer_tokens(path)?)
+                .ok_or_else(|| PatchError::PathNotFound(path.clone()))?;
+            *slot = value.clone();
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            if path.starts_with(&format!("{from}/")) {
+                return Err(PatchError::InvalidPatch(format!(
+                    "cannot move '{from}' into its own child '{path}'"
+                )));
+            }
+            let value = remove_value(target, from)?;
+            add_value(target, path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = get_mut(target, &pointer_tokens(from)?)
+                .ok_or_else(|| PatchError::PathNotFound(from.clone()))?
+                .clone();
+            add_value(target, path, value)
+        }
+        PatchOperation::Test { path, value } => {
+            match get_mut(target, &pointer_tokens(path)?) {
+                Some(actual) if actual == value => Ok(()),
+                _ => Err(PatchError::TestFailed(path.clone())),
+            }
+        }
+    }
+}
+
+fn get_mut<'a>(
+    value: &'a mut Value,
+    tokens: &[String],
+) -> Option<&'a mut Value> {
+    tokens
+        .iter()
+        .try_fold(value, |current, token| match current {
+            Value::Object(map) => map.get_mut(token),
+            Value::Array(arr) => {
+                parse_array_index(token).and_then(move |i| arr.get_mut(i))
+            }
+            _ => None,
+        })
+}
+
+/// 解析数组索引令牌，不允许前导零
+fn parse_array_index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || (token.len() > 1 && token.starts_with('0'))
+        || !token.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    token.parse().ok()
+}
+
+fn add_value(
+    target: &mut Value,
+    path: &str,
+    value: Value,
+) -> Result<(), PatchError> {
+    let tokens = pointer_tokens(path)?;
+    let Some((last, parents)) = tokens.split_last() else {
+        *target = value;
+        return Ok(());
+    };
+
+    match get_mut(target, parents) {
+        Some(Value::Object(map)) => {
+            map.insert(last.clone(), value);
+            Ok(())
+        }
+        Some(Value::Array(arr)) => {
+            if last == "-" {
+                arr.push(value);
+                return Ok(());
+            }
+            match parse_array_index(last) {
+                Some(i) if i <= arr.len() => {
+                    arr.insert(i, value);
+                    Ok(())
+                }
+                _ => Err(PatchError::PathNotFound(path.to_string())),
+            }
+        }
+        _ => Err(PatchError::PathNotFound(path.to_string())),
+    }
+}
+
+/// 移除对象键；启用 preserve_order 时保持其余键的相对顺序
+pub(crate) fn remove_key(
+    map: &mut serde_json::Map<String, Value>,
+    key: &str,
+) -> Option<Value> {
+    #[cfg(feature = "update")]
+    {
+        map.shift_remove(key)
+    }
+    #[cfg(not(feature = "update"))]
+    {
+        map.remove(key)
+    }
+}
+
+fn remove_value(target: &mut Value, path: &str) -> Result<Value, PatchError> {
+    let tokens = pointer_tokens(path)?;
+    let (last, parents) = tokens.split_last().ok_or_else(|| {
+        PatchError::InvalidPatch("cannot remove the root value".to_string())
+    })?;
+
+    let removed = match get_mut(target, parents) {
+        Some(Value::Object(map)) => remove_key(map, last),
+        Some(Value::Array(arr)) => parse_array_index(last)
+            .filter(|&i| i < arr.len())
+            .map(|i| arr.remove(i)),
+        _ => None,
+    };
+
+    removed.ok_or_else(|| PatchError::PathNotFound(path.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_json_pointer() {
+        assert_eq!(parse_json_pointer("").unwrap(), Vec::<String>::new());
+        assert_eq!(parse_json_pointer("/a/0").unwrap(), vec!["a", "0"]);
+        assert_eq!(
+            parse_json_pointer("/a~1b/m~0n").unwrap(),
+            vec!["a/b", "m~n"]
+        );
+        assert!(parse_json_pointer("a").is_err());
+        assert!(parse_json_pointer("/a~2").is_err());
+    }
+
+    #[test]
+    fn test_add_and_remove() {
+        let mut doc = json!({"foo": ["bar", "baz"]});
+        apply_json_patch(
+            &mut doc,
+            &json!([
+                {"op": "add", "path": "/foo/1", "value": "qux"},
+                {"op": "add", "path": "/foo/-", "value": "end"},
+                {"op": "add", "path": "/baz", "value": {"x": 1}},
+                {"op": "remove", "path": "/foo/0"}
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(doc, json!({"foo": ["qux", "baz", "end"], "baz": {"x": 1}}));
+    }
+
+    #[test]
+    fn test_replace_move_copy() {
+        let mut doc = json!({"a": {"b": 1}, "c": 2});
+        apply_json_patch(
+            &mut doc,
+            &json!([
+                {"op": "replace", "path": "/c", "value": 3},
+                {"op": "move", "from": "/a/b", "path": "/d"},
+                {"op": "copy", "from": "/d", "path": "/a/e"}
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(doc, json!({"a": {"e": 1}, "c": 3, "d": 1}));
+    }
+
+    #[test]
+    fn test_failed_test_leaves_target_unchanged() {
+        let mut doc = json!({"baz": "qux"});
+        let result = apply_json_patch(
+            &mut doc,
+            &json!([
+                {"op": "add", "path": "/new", "value": 1},
+                {"op": "test", "path": "/baz", "value": "bar"}
+            ]),
+        );
+
+        assert!(matches!(
+            result,
+            Err(XqpathError::Patch(PatchError::TestFailed(path))) if path == "/baz"
+        ));
+        assert_eq!(doc, json!({"baz": "qux"}));
+    }
+
+    #[test]
+    fn test_invalid_operations() {
+        let mut doc = json!({"a": [1]});
+        assert!(apply_json_patch(&mut doc, &json!({"op": "add"})).is_err());
+        assert!(apply_json_patch(
+            &mut doc,
+            &json!([{"op": "frobnicate", "path": "/a"}])
+        )
+        .is_err());
+        assert!(apply_json_patch(
+            &mut doc,
+            &json!([{"op": "add", "path": "/a/5", "value": 1}])
+        )
+        .is_err());
+        assert!(apply_json_patch(
+            &mut doc,
+            &json!([{"op": "move", "from": "/a", "path": "/a/0"}])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_merge_patch_rfc7386_examples() {
+        let cases = [
+            (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+            (
+                json!({"a": "b"}),
+                json!({"b": "c"}),
+                json!({"a": "b", "b": "c"}),
+            ),
+            (json!({"a": "b"}), json!({"a": null}), json!({})),
+            (
+                json!({"a": "b", "b": "c"}),
+                json!({"a": null}),
+                json!({"b": "c"}),
+            ),
+            (json!({"a": ["b"]}), json!({"a": "c"}), json!({"a": "c"})),
+            (json!({"a": "c"}), json!({"a": ["b"]}), json!({"a": ["b"]})),
+            (
+                json!({"a": {"b": "c"}}),
+                json!({"a": {"b": "d", "c": null}}),
+                json!({"a": {"b": "d"}}),
+            ),
+            (
+                json!({"a": [{"b": "c"}]}),
+                json!({"a": [1]}),
+                json!({"a": [1]}),
+            ),
+            (json!(["a", "b"]), json!(["c", "d"]), json!(["c", "d"])),
+            (json!({"a": "b"}), json!(["c"]), json!(["c"])),
+            (json!({"a": "foo"}), json!(null), json!(null)),
+            (json!({"a": "foo"}), json!("bar"), json!("bar")),
+            (
+                json!({"e": null}),
+                json!({"a": 1}),
+                json!({"e": null, "a": 1}),
+            ),
+            (
+                json!([1, 2]),
+                json!({"a": "b", "c": null}),
+                json!({"a": "b"}),
+            ),
+            (
+                json!({}),
+                json!({"a": {"bb": {"ccc": null}}}),
+                json!({"a": {"bb": {}}}),
+            ),
+        ];
+
+        for (mut target, patch, expected) in cases {
+            apply_merge_patch(&mut target, &patch);
+            assert_eq!(target, expected, "patch: {patch}");
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use crate::parser::path::PathSegment;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// 将文档展开为 `(路径, 叶子值)` 事件流，类似 jq 的 `--stream`
+///
+/// 按文档顺序惰性遍历；标量以及空数组/空对象视为叶子。
+pub fn stream_value(value: &Value) -> ValueStream<'_> {
+    ValueStream {
+        stack: vec![(Vec::new(), value)],
+    }
+}
+
+/// [`stream_value`] 返回的惰性迭代器
+pub struct ValueStream<'a> {
+    stack: Vec<(Vec<PathSegment>, &'a Value)>,
+}
+
+impl<'a> Iterator for ValueStream<'a> {
+    type Item = (Vec<PathSegment>, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((path, value)) = self.stack.pop() {
+            match value {
+                Value::Array(arr) if !arr.is_empty() => {
+                    // 逆序压栈以保证按文档顺序输出
+                    for (index, item) in arr.iter().enumerate().rev() {
+                        let mut child = path.clone();
+                        child.push(PathSegment::Index(index));
+                        self.stack.push((child, item));
+                    }
+                }
+                Value::Object(map) if !map.is_empty() => {
+                    let entries: Vec<_> = map.iter().collect();
+                    for (key, item) in entries.into_iter().rev() {
+                        let mut child = path.clone();
+                        child.push(PathSegment::Field(key.clone()));
+                        self.stack.push((child, item));
+                    }
+                }
+                _ => return Some((path, value)),
+            }
+        }
+        None
+    }
+}
+
+/// 按文档顺序前序遍历文档中的每个节点（不含根节点），返回 `(路径, 节点)`
+///
+/// 与 [`stream_value`] 不同，中间的数组和对象节点同样会被返回，对应 jq 的 `paths`。
+pub fn walk_paths(value: &Value) -> PathWalk<'_> {
+    let mut walk = PathWalk { stack: Vec::new() };
+    walk.push_children(&[], value);
+    walk
+}
+
+/// [`walk_paths`] 返回的惰性迭代器
+pub struct PathWalk<'a> {
+    stack: Vec<(Vec<PathSegment>, &'a Value)>,
+}
+
+impl<'a> PathWalk<'a> {
+    /// 逆序压入子节点以保证按文档顺序输出
+    fn push_children(&mut self, path: &[PathSegment], value: &'a Value) {
+        match value {
+            Value::Array(arr) => {
+                for (index, item) in arr.iter().enumerate().rev() {
+                    let mut child = path.to_vec();
+                    child.push(PathSegment::Index(index));
+                    self.stack.push((child, item));
+                }
+            }
+            Value::Object(map) => {
+                let entries: Vec<_> = map.iter().collect();
+                for (key, item) in entries.into_iter().rev() {
+                    let mut child = path.to_vec();
+                    child.push(PathSegment::Field(key.clone()));
+                    self.stack.push((child, item));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'a> Iterator for PathWalk<'a> {
+    type Item = (Vec<PathSegment>, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, value) = self.stack.pop()?;
+        self.push_children(&path, value);
+        Some((path, value))
+    }
+}
+
+/// 只返回标量叶子节点（不含数组和对象）的 `(路径, 值)`，按文档顺序
+pub fn leaf_paths(
+    value: &Value,
+) -> impl Iterator<Item = (Vec<PathSegment>, &Value)> {
+    walk_paths(value).filter(|(_, node)| !node.is_array() && !node.is_object())
+}
+
+/// 查找提取结果在文档中的具体路径，按文档顺序返回 `(路径, 节点)`，
+/// 根节点对应空路径
+///
+/// `targets` 应是从 `root` 借出的引用（例如 [`crate::extract`] 的结果）；
+/// 按地址匹配，不在文档中的值会被忽略，重复的引用只返回一次。
+pub fn locate_paths<'a>(
+    root: &'a Value,
+    targets: &[&Value],
+) -> Vec<(Vec<PathSegment>, &'a Value)> {
+    let addresses: HashSet<*const Value> =
+        targets.iter().map(|value| *value as *const Value).collect();
+
+    std::iter::once((Vec::new(), root))
+        .chain(walk_paths(root))
+        .filter(|(_, node)| addresses.contains(&(*node as *const Value)))
+        .collect()
+}
+
+/// 将路径渲染为点分隔的键，如 `a.b.0.c`
+pub fn dotted_path(path: &[PathSegment]) -> String {
+    path.iter()
+        .map(|segment| match segment {
+            PathSegment::Field(name) => name.clone(),
+            PathSegment::Index(index) => index.to_string(),
+            other => other.to_jq_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// 将路径转换为 jq 风格的路径数组，如 `["users", 0, "name"]`
+pub fn path_to_value(path: &[PathSegment]) -> Value {
+    Value::Array(
+        path.iter()
+            .map(|segment| match segment {
+                PathSegment::Field(name) => Value::String(name.clone()),
+                PathSegment::Index(index) => Value::Number((*index).into()),
+                other => Value::String(other.to_jq_string()),
+            })
+            .collect(),
+    )
+}
+
+/// 将 jq 风格的路径数组还原为路径段，元素只能是字符串或非负整数
+pub fn value_to_path(value: &Value) -> Option<Vec<PathSegment>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|item| match item {
+            Value::String(name) => Some(PathSegment::Field(name.clone())),
+            Value::Number(n) => {
+                n.as_u64().map(|index| PathSegment::Index(index as usize))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_stream_value() {
+        let data = json!({"a": [1, {"b": null}], "c": {}, "d": []});
+        let events: Vec<(Value, Value)> = stream_value(&data)
+            .map(|(path, leaf)| (path_to_value(&path), leaf.clone()))
+            .collect();
+
+        assert_eq!(
+            events,
+            vec![
+                (json!(["a", 0]), json!(1)),
+                (json!(["a", 1, "b"]), json!(null)),
+                (json!(["c"]), json!({})),
+                (json!(["d"]), json!([])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_paths() {
+        let data = json!({"a": [1, {"b": null}], "c": {}});
+        let paths: Vec<Value> = walk_paths(&data)
+            .map(|(path, _)| path_to_value(&path))
+            .collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                json!(["a"]),
+                json!(["a", 0]),
+                json!(["a", 1]),
+                json!(["a", 1, "b"]),
+                json!(["c"]),
+            ]
+        );
+        assert_eq!(walk_paths(&json!(1)).count(), 0);
+    }
+
+    #[test]
+    fn test_value_to_path() {
+        let path = vec![
+            PathSegment::Field("users".to_string()),
+            PathSegment::Index(0),
+            PathSegment::Field("name".to_string()),
+        ];
+        assert_eq!(value_to_path(&path_to_value(&path)), Some(path));
+        assert_eq!(value_to_path(&json!([])), Some(vec![]));
+        assert_eq!(value_to_path(&json!(["a", -1])), None);
+        assert_eq!(value_to_path(&json!("a")), None);
+    }
+
+    #[test]
+    fn test_locate_paths() {
+        let data = json!({"a": [1, {"b": 1}], "c": 1});
+        let targets = vec![&data["c"], &data["a"][1]["b"], &data["c"]];
+        let paths: Vec<Value> = locate_paths(&data, &targets)
+            .iter()
+            .map(|(path, node)| {
+                assert!(targets.iter().any(|t| std::ptr::eq(*t, *node)));
+                path_to_value(path)
+            })
+            .collect();
+        assert_eq!(paths, vec![json!(["a", 1, "b"]), json!(["c"])]);
+
+        assert_eq!(locate_paths(&data, &[&data]), vec![(vec![], &data)]);
+
+        let other = json!(1);
+        assert!(locate_paths(&data, &[&other]).is_empty());
+    }
+
+    #[test]
+    fn test_leaf_paths() {
+        let data = json!({"a": [1, {"b": null}], "c": {}, "d": "x"});
+        let leaves: Vec<(String, Value)> = leaf_paths(&data)
+            .map(|(path, leaf)| (dotted_path(&path), leaf.clone()))
+            .collect();
+
+        assert_eq!(
+            leaves,
+            vec![
+                ("a.0".to_string(), json!(1)),
+                ("a.1.b".to_string(), json!(null)),
+                ("d".to_string(), json!("x")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stream_scalar_and_laziness() {
+        let scalar = json!("x");
+        let events: Vec<_> = stream_value(&scalar).collect();
+        assert_eq!(events, vec![(vec![], &scalar)]);
+
+        let large = Value::Array((0..10_000).map(|i| json!(i)).collect());
+        let (path, leaf) = stream_value(&large).nth(2).unwrap();
+        assert_eq!(path, vec![PathSegment::Index(2)]);
+        assert_eq!(leaf, &json!(2));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::value::format::FormatError;
+use crate::value::format::{strip_bom, FormatError};
 use serde::Deserialize;
 use serde_json::Value;
 
@@ -9,8 +9,8 @@ impl YamlSupport {
     /// 解析 YAML 字符串为 JSON Value
     pub fn parse(input: &str) -> Result<Value, FormatError> {
         // 先解析为 serde_yaml::Value
-        let yaml_value: serde_yaml::Value = serde_yaml::from_str(input)
-            .map_err(|e| {
+        let yaml_value: serde_yaml::Value =
+            serde_yaml::from_str(strip_bom(input)).map_err(|e| {
                 FormatError::ParseError(format!("YAML parse error: {e}"))
             })?;
 
@@ -20,9 +20,7 @@ impl YamlSupport {
 
     /// 将 JSON Value 转换为 YAML 字符串
     pub fn to_string(value: &Value) -> Result<String, FormatError> {
-        serde_yaml::to_string(value).map_err(|e| {
-            FormatError::SerializeError(format!("YAML serialize error: {e}"))
-        })
+        YamlFormatter::default().format(value)
     }
 
     /// 检查字符串是否为有效的 YAML
@@ -43,6 +41,9 @@ impl YamlSupport {
                 } else if let Some(u) = n.as_u64() {
                     Ok(Value::Number(serde_json::Number::from(u)))
                 } else if let Some(f) = n.as_f64() {
+                    if !f.is_finite() {
+                        return Ok(YamlSpecialValues::special_float_value(f));
+                    }
                     serde_json::Number::from_f64(f)
                         .map(Value::Number)
                         .ok_or_else(|| {
@@ -100,7 +101,7 @@ impl YamlSupport {
         let mut documents = Vec::new();
 
         // 使用 serde_yaml 的多文档解析
-        let deserializer = serde_yaml::Deserializer::from_str(input);
+        let deserializer = serde_yaml::Deserializer::from_str(strip_bom(input));
         for document in deserializer {
             let yaml_value =
                 serde_yaml::Value::deserialize(document).map_err(|e| {
@@ -133,37 +134,154 @@ impl YamlSpecialValues {
         }
     }
 
-    /// 尝试解析 YAML 数字
-    pub fn parse_yaml_number(s: &str) -> Option<Value> {
-        // 尝试整数
-        if let Ok(i) = s.parse::<i64>() {
-            return Some(Value::Number(serde_json::Number::from(i)));
+    /// 非有限浮点数在 JSON 中无法表示，按 jq 的约定转换：
+    /// 正负无穷为 ±`f64::MAX`，NaN 为 null
+    ///
+    /// 字符串始终保持为字符串，不会与特殊浮点数混淆。
+    pub fn special_float_value(f: f64) -> Value {
+        if f.is_nan() {
+            Value::Null
+        } else {
+            Value::from(f64::MAX.copysign(f))
+        }
+    }
+
+    /// 获取数字的浮点值，±`f64::MAX` 按 jq 的约定视为正负无穷；非数字返回 `None`
+    pub fn as_f64(value: &Value) -> Option<f64> {
+        let f = value.as_f64()?;
+        if f.abs() == f64::MAX {
+            Some(f64::INFINITY.copysign(f))
+        } else {
+            Some(f)
         }
+    }
 
-        // 尝试浮点数
-        if let Ok(f) = s.parse::<f64>() {
-            if let Some(n) = serde_json::Number::from_f64(f) {
-                return Some(Value::Number(n));
+    /// 转换为用于序列化的 YAML 值，±`f64::MAX` 输出为 `.inf`/`-.inf`
+    pub fn to_yaml_value(value: &Value) -> serde_yaml::Value {
+        match value {
+            Value::Null => serde_yaml::Value::Null,
+            Value::Bool(b) => serde_yaml::Value::Bool(*b),
+            Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    serde_yaml::Value::Number(i.into())
+                } else if let Some(u) = n.as_u64() {
+                    serde_yaml::Value::Number(u.into())
+                } else {
+                    serde_yaml::Value::Number(
+                        Self::as_f64(value).unwrap_or(0.0).into(),
+                    )
+                }
+            }
+            Value::String(s) => serde_yaml::Value::String(s.clone()),
+            Value::Array(arr) => serde_yaml::Value::Sequence(
+                arr.iter().map(Self::to_yaml_value).collect(),
+            ),
+            Value::Object(map) => serde_yaml::Value::Mapping(
+                map.iter()
+                    .map(|(k, v)| {
+                        (
+                            serde_yaml::Value::String(k.clone()),
+                            Self::to_yaml_value(v),
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// 将 YAML 值中的非有限浮点数替换为 JSON 可表示的值，
+    /// 规则同 [`special_float_value`](Self::special_float_value)
+    pub(crate) fn replace_special_floats(value: &mut serde_yaml::Value) {
+        match value {
+            serde_yaml::Value::Number(n) => {
+                if let Some(f) = n.as_f64().filter(|f| !f.is_finite()) {
+                    *value = if f.is_nan() {
+                        serde_yaml::Value::Null
+                    } else {
+                        serde_yaml::Value::Number(f64::MAX.copysign(f).into())
+                    };
+                }
+            }
+            serde_yaml::Value::Sequence(seq) => {
+                seq.iter_mut().for_each(Self::replace_special_floats)
             }
+            serde_yaml::Value::Mapping(map) => map
+                .iter_mut()
+                .for_each(|(_, v)| Self::replace_special_floats(v)),
+            serde_yaml::Value::Tagged(tagged) => {
+                Self::replace_special_floats(&mut tagged.value)
+            }
+            _ => {}
         }
+    }
 
-        // 处理科学计数法
-        if s.contains('e') || s.contains('E') {
-            if let Ok(f) = s.parse::<f64>() {
-                if let Some(n) = serde_json::Number::from_f64(f) {
-                    return Some(Value::Number(n));
+    /// 尝试解析 YAML 数字，规则与 [`YamlFormat`](crate::YamlFormat) 的解析一致
+    ///
+    /// 遵循 YAML 1.2 核心模式：`0o17`、`0x1F` 分别为 15 和 31；
+    /// 带前导零的 `08`、`010` 以及六十进制形式的 `1:30` 不是数字，
+    /// 返回 `None`（解析文档时保留为字符串，避免丢失前导零 ID）。
+    pub fn parse_yaml_number(s: &str) -> Option<Value> {
+        match serde_yaml::from_str::<serde_yaml::Value>(s).ok()? {
+            serde_yaml::Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    Some(Value::Number(i.into()))
+                } else if let Some(u) = n.as_u64() {
+                    Some(Value::Number(u.into()))
+                } else {
+                    serde_json::Number::from_f64(n.as_f64()?).map(Value::Number)
                 }
             }
+            _ => None,
         }
+    }
+
+    /// 是否为 YAML 1.1 读取器会当作数字、而 YAML 1.2 视为字符串的写法
+    ///
+    /// 包括六十进制（`1:30`）和带下划线分隔的数字（`1_000`）。
+    /// 输出时这些字符串会加引号，避免被其他 YAML 工具读成数字。
+    pub fn is_yaml11_number(s: &str) -> bool {
+        let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+        digits.starts_with(|c: char| c.is_ascii_digit())
+            && digits.contains([':', '_'])
+            && digits
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, ':' | '_' | '.'))
+    }
 
-        None
+    /// 值中是否含有需要额外加引号的字符串（键或值）
+    fn has_yaml11_numbers(value: &Value) -> bool {
+        match value {
+            Value::String(s) => Self::is_yaml11_number(s),
+            Value::Array(arr) => arr.iter().any(Self::has_yaml11_numbers),
+            Value::Object(map) => map.iter().any(|(k, v)| {
+                Self::is_yaml11_number(k) || Self::has_yaml11_numbers(v)
+            }),
+            _ => false,
+        }
     }
 }
 
+/// YAML 字符串值的引号风格
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum YamlQuoteStyle {
+    /// 由 serde_yaml 决定，仅在必要时加引号
+    #[default]
+    Auto,
+    /// 单引号，包含换行的字符串退回到双引号
+    Single,
+    /// 双引号，使用 JSON 兼容的转义
+    Double,
+}
+
 /// YAML 格式化选项
+#[derive(Debug, Clone)]
 pub struct YamlFormatter {
     pub indent: usize,
     pub width: usize,
+    /// 是否将块序列相对父键缩进（`key:\n  - a`），默认与 serde_yaml 一致不缩进
+    pub indent_sequences: bool,
+    /// 字符串值的引号风格，不影响映射键
+    pub quote_style: YamlQuoteStyle,
 }
 
 impl Default for YamlFormatter {
@@ -171,6 +289,8 @@ impl Default for YamlFormatter {
         Self {
             indent: 2,
             width: 80,
+            indent_sequences: false,
+            quote_style: YamlQuoteStyle::Auto,
         }
     }
 }
@@ -193,11 +313,191 @@ impl YamlFormatter {
         self
     }
 
+    /// 设置块序列是否相对父键缩进
+    pub fn with_indent_sequences(mut self, indent_sequences: bool) -> Self {
+        self.indent_sequences = indent_sequences;
+        self
+    }
+
+    /// 设置字符串值的引号风格
+    pub fn with_quote_style(mut self, quote_style: YamlQuoteStyle) -> Self {
+        self.quote_style = quote_style;
+        self
+    }
+
     /// 格式化 Value 为 YAML
+    ///
+    /// 默认选项直接使用 serde_yaml 的输出；自定义缩进时按相同的结构重新排版，
+    /// 标量的表示（引号、块字面量）仍由 serde_yaml 决定。行宽暂不生效。
     pub fn format(&self, value: &Value) -> Result<String, FormatError> {
-        // 注意：serde_yaml 不直接支持自定义格式化选项
-        // 这里提供基本的格式化功能
-        YamlSupport::to_string(value)
+        if self.indent == 2
+            && !self.indent_sequences
+            && self.quote_style == YamlQuoteStyle::Auto
+            && !YamlSpecialValues::has_yaml11_numbers(value)
+        {
+            let yaml_value = YamlSpecialValues::to_yaml_value(value);
+            return serde_yaml::to_string(&yaml_value).map_err(|e| {
+                FormatError::SerializeError(format!(
+                    "YAML serialize error: {e}"
+                ))
+            });
+        }
+
+        let yaml = YamlSpecialValues::to_yaml_value(value);
+        let mut out = String::new();
+        match &yaml {
+            serde_yaml::Value::Mapping(map) if !map.is_empty() => {
+                self.write_mapping(map, 0, false, &mut out)?
+            }
+            serde_yaml::Value::Sequence(seq) if !seq.is_empty() => {
+                self.write_sequence(seq, 0, false, &mut out)?
+            }
+            scalar => {
+                out.push_str(&self.scalar(scalar, 0)?);
+                out.push('\n');
+            }
+        }
+        Ok(out)
+    }
+
+    /// 写出映射；`inline` 为真时第一个键紧跟在已有前缀（如 `- `）之后
+    fn write_mapping(
+        &self,
+        map: &serde_yaml::Mapping,
+        pad: usize,
+        inline: bool,
+        out: &mut String,
+    ) -> Result<(), FormatError> {
+        for (i, (key, value)) in map.iter().enumerate() {
+            if i > 0 || !inline {
+                out.push_str(&" ".repeat(pad));
+            }
+            out.push_str(&Self::key(key)?);
+            out.push(':');
+            match value {
+                serde_yaml::Value::Mapping(child) if !child.is_empty() => {
+                    out.push('\n');
+                    self.write_mapping(child, pad + self.indent, false, out)?;
+                }
+                serde_yaml::Value::Sequence(seq) if !seq.is_empty() => {
+                    out.push('\n');
+                    let seq_pad = if self.indent_sequences {
+                        pad + self.indent
+                    } else {
+                        pad
+                    };
+                    self.write_sequence(seq, seq_pad, false, out)?;
+                }
+                scalar => {
+                    out.push(' ');
+                    out.push_str(&self.scalar(scalar, pad + self.indent)?);
+                    out.push('\n');
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 写出块序列，元素内容与 `- ` 之后的列对齐
+    fn write_sequence(
+        &self,
+        seq: &[serde_yaml::Value],
+        pad: usize,
+        inline: bool,
+        out: &mut String,
+    ) -> Result<(), FormatError> {
+        for (i, item) in seq.iter().enumerate() {
+            if i > 0 || !inline {
+                out.push_str(&" ".repeat(pad));
+            }
+            out.push_str("- ");
+            match item {
+                serde_yaml::Value::Mapping(map) if !map.is_empty() => {
+                    self.write_mapping(map, pad + 2, true, out)?
+                }
+                serde_yaml::Value::Sequence(child) if !child.is_empty() => {
+                    self.write_sequence(child, pad + 2, true, out)?
+                }
+                scalar => {
+                    out.push_str(&self.scalar(scalar, pad + 2)?);
+                    out.push('\n');
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 渲染映射键，多行键使用双引号形式
+    fn key(key: &serde_yaml::Value) -> Result<String, FormatError> {
+        match key {
+            serde_yaml::Value::String(s) if s.contains('\n') => {
+                serde_json::to_string(s).map_err(|e| {
+                    FormatError::SerializeError(format!(
+                        "YAML serialize error: {e}"
+                    ))
+                })
+            }
+            other => YamlFormatter::default().scalar(other, 0),
+        }
+    }
+
+    /// 渲染标量（或空集合），块字面量的内容行缩进到 `pad`
+    fn scalar(
+        &self,
+        value: &serde_yaml::Value,
+        pad: usize,
+    ) -> Result<String, FormatError> {
+        match (value, self.quote_style) {
+            (serde_yaml::Value::Mapping(_), _) => return Ok("{}".to_string()),
+            (serde_yaml::Value::Sequence(_), _) => return Ok("[]".to_string()),
+            (serde_yaml::Value::String(s), YamlQuoteStyle::Auto)
+                if YamlSpecialValues::is_yaml11_number(s) =>
+            {
+                return Ok(format!("'{s}'"));
+            }
+            (serde_yaml::Value::String(s), YamlQuoteStyle::Single)
+                if !s.contains('\n') =>
+            {
+                return Ok(format!("'{}'", s.replace('\'', "''")));
+            }
+            (
+                serde_yaml::Value::String(s),
+                YamlQuoteStyle::Single | YamlQuoteStyle::Double,
+            ) => {
+                return serde_json::to_string(s).map_err(|e| {
+                    FormatError::SerializeError(format!(
+                        "YAML serialize error: {e}"
+                    ))
+                });
+            }
+            _ => {}
+        }
+
+        let rendered = serde_yaml::to_string(value).map_err(|e| {
+            FormatError::SerializeError(format!("YAML serialize error: {e}"))
+        })?;
+        let mut lines = rendered.trim_end_matches('\n').split('\n');
+        let mut scalar = lines.next().unwrap_or_default().to_string();
+        // 带显式缩进指示符（如 `|2-`）的块字面量在重新缩进后指示符会失效，
+        // 改用双引号形式以免内容的前导空格被改变
+        if scalar.starts_with(['|', '>'])
+            && scalar.contains(|c: char| c.is_ascii_digit())
+        {
+            return serde_json::to_string(value).map_err(|e| {
+                FormatError::SerializeError(format!(
+                    "YAML serialize error: {e}"
+                ))
+            });
+        }
+        // serde_yaml 将顶层块字面量的内容缩进两格
+        for line in lines {
+            scalar.push('\n');
+            if let Some(content) = line.strip_prefix("  ") {
+                scalar.push_str(&" ".repeat(pad));
+                scalar.push_str(content);
+            }
+        }
+        Ok(scalar)
     }
 }
 
@@ -227,6 +527,53 @@ address:
         assert_eq!(value["address"]["street"], "123 Main St");
     }
 
+    #[test]
+    fn test_special_floats() {
+        let value = YamlSupport::parse("a: [.inf, -.Inf, .NaN, 1.5]").unwrap();
+        assert_eq!(value["a"], json!([f64::MAX, f64::MIN, null, 1.5]));
+
+        let values: Vec<f64> = value["a"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(YamlSpecialValues::as_f64)
+            .collect();
+        assert_eq!(values, vec![f64::INFINITY, f64::NEG_INFINITY, 1.5]);
+
+        // 字符串不会被当作特殊浮点数
+        assert_eq!(YamlSpecialValues::as_f64(&json!(".inf")), None);
+        assert_eq!(YamlSpecialValues::as_f64(&json!(".nan")), None);
+    }
+
+    #[test]
+    fn test_special_values_roundtrip() {
+        let cases = [
+            ("a: ~\n", json!({"a": null}), "a: null\n"),
+            ("a: null\n", json!({"a": null}), "a: null\n"),
+            ("a: .inf\n", json!({"a": f64::MAX}), "a: .inf\n"),
+            ("a: -.inf\n", json!({"a": f64::MIN}), "a: -.inf\n"),
+            // NaN 在 JSON 中无法表示，与 jq 一样变为 null
+            ("a: .nan\n", json!({"a": null}), "a: null\n"),
+            // 拼写相同的字符串保持为字符串
+            ("a: '.inf'\n", json!({"a": ".inf"}), "a: '.inf'\n"),
+            ("a: '.nan'\n", json!({"a": ".nan"}), "a: '.nan'\n"),
+        ];
+
+        for (input, expected, serialized) in cases {
+            let value = YamlSupport::parse(input).unwrap();
+            assert_eq!(value, expected, "{input}");
+
+            let output = YamlSupport::to_string(&value).unwrap();
+            assert_eq!(output, serialized, "{input}");
+            assert_eq!(YamlSupport::parse(&output).unwrap(), value);
+
+            let format = crate::value::format::YamlFormat;
+            use crate::value::format::ValueFormat;
+            assert_eq!(format.parse(input).unwrap(), value);
+            assert_eq!(format.to_string(&value).unwrap(), serialized);
+        }
+    }
+
     #[test]
     fn test_yaml_serialize() {
         let value = json!({
@@ -264,6 +611,8 @@ age: 25
         assert!(YamlSpecialValues::is_yaml_null("null"));
         assert!(YamlSpecialValues::is_yaml_null("~"));
         assert!(YamlSpecialValues::is_yaml_null(""));
+        assert!(YamlSpecialValues::is_yaml_null("nil"));
+        assert!(YamlSpecialValues::is_yaml_null("NULL"));
 
         assert_eq!(YamlSpecialValues::is_yaml_bool("true"), Some(true));
         assert_eq!(YamlSpecialValues::is_yaml_bool("yes"), Some(true));
@@ -285,6 +634,50 @@ age: 25
             YamlSpecialValues::parse_yaml_number("1.23e4").unwrap(),
             json!(12300.0)
         );
+        assert_eq!(
+            YamlSpecialValues::parse_yaml_number("0o17").unwrap(),
+            json!(15)
+        );
+        assert_eq!(
+            YamlSpecialValues::parse_yaml_number("0x1F").unwrap(),
+            json!(31)
+        );
+        assert_eq!(YamlSpecialValues::parse_yaml_number("08"), None);
+        assert_eq!(YamlSpecialValues::parse_yaml_number("1:30"), None);
+        assert_eq!(YamlSpecialValues::parse_yaml_number("abc"), None);
+    }
+
+    #[test]
+    fn test_yaml_integer_edge_cases() {
+        let data = YamlSupport::parse(
+            "leading_zero: 08\noctal: 0o17\nhex: 0x1F\nsexagesimal: 1:30\n\
+             zero_padded: 007\nunderscore: 1_000\n",
+        )
+        .unwrap();
+        assert_eq!(
+            data,
+            json!({
+                "leading_zero": "08",
+                "octal": 15,
+                "hex": 31,
+                "sexagesimal": "1:30",
+                "zero_padded": "007",
+                "underscore": "1_000"
+            })
+        );
+
+        // 输出时加引号，YAML 1.1 工具也不会把它们读成数字
+        let yaml = YamlSupport::to_string(&data).unwrap();
+        for quoted in ["'08'", "'1:30'", "'007'", "'1_000'"] {
+            assert!(yaml.contains(quoted), "{yaml}");
+        }
+        assert_eq!(YamlSupport::parse(&yaml).unwrap(), data);
+
+        assert!(YamlSpecialValues::is_yaml11_number("1:30"));
+        assert!(YamlSpecialValues::is_yaml11_number("-190:20:30.15"));
+        assert!(!YamlSpecialValues::is_yaml11_number("12"));
+        assert!(!YamlSpecialValues::is_yaml11_number("a:1"));
+        assert!(!YamlSpecialValues::is_yaml11_number("10:00 am"));
     }
 
     #[test]
@@ -295,4 +688,149 @@ age: 25
         let formatted = formatter.format(&value).unwrap();
         assert!(formatted.contains("name: Alice"));
     }
+
+    #[test]
+    fn test_yaml_formatter_indent() {
+        let value: Value = serde_json::from_str(
+            r#"{"a": {"b": [1, [2, 3], {"c": 4, "d": [5]}], "e": "x\ny"}, "f": []}"#,
+        )
+        .unwrap();
+
+        // 两格缩进且不缩进序列时与 serde_yaml 完全一致
+        let default = YamlFormatter::new().format(&value).unwrap();
+        assert_eq!(default, YamlSupport::to_string(&value).unwrap());
+
+        let four = YamlFormatter::new().with_indent(4).format(&value).unwrap();
+        let expected = "\
+a:
+    b:
+    - 1
+    - - 2
+      - 3
+    - c: 4
+      d:
+      - 5
+    e: |-
+        x
+        y
+f: []
+";
+        assert_eq!(four, expected);
+        assert_eq!(YamlSupport::parse(&four).unwrap(), value);
+
+        let indented = YamlFormatter::new()
+            .with_indent(4)
+            .with_indent_sequences(true)
+            .format(&value)
+            .unwrap();
+        let expected = "\
+a:
+    b:
+        - 1
+        - - 2
+          - 3
+        - c: 4
+          d:
+              - 5
+    e: |-
+        x
+        y
+f: []
+";
+        assert_eq!(indented, expected);
+        assert_eq!(YamlSupport::parse(&indented).unwrap(), value);
+
+        // 重新排版后与 serde_yaml 的输出等价
+        let two = YamlFormatter::new()
+            .with_indent_sequences(true)
+            .format(&json!([{"k": ["v"]}, "s", {}]))
+            .unwrap();
+        assert_eq!(two, "- k:\n    - v\n- s\n- {}\n");
+        assert_eq!(
+            YamlFormatter::new()
+                .with_indent(4)
+                .format(&json!("x"))
+                .unwrap(),
+            "x\n"
+        );
+    }
+
+    #[test]
+    fn test_yaml_formatter_leading_space_block_roundtrip() {
+        let value = json!({
+            "a": " x\ny",
+            "list": ["  p\nq", {"k": " r\n s"}],
+            "nested": {"deep": ["\tt\nu", "plain\nlines"]}
+        });
+
+        for indent_sequences in [false, true] {
+            let output = YamlFormatter::new()
+                .with_indent(4)
+                .with_indent_sequences(indent_sequences)
+                .format(&value)
+                .unwrap();
+            assert_eq!(YamlSupport::parse(&output).unwrap(), value, "{output}");
+            assert!(output.contains("a: \" x\\ny\"\n"), "{output}");
+            // 没有前导空白的多行字符串仍使用块字面量
+            assert!(output.contains("- |-\n"), "{output}");
+        }
+    }
+
+    #[test]
+    fn test_yaml_formatter_quote_style() {
+        let value =
+            json!({"name": "Alice", "note": "it's", "n": 1, "t": "a\nb"});
+
+        let double = YamlFormatter::new()
+            .with_quote_style(YamlQuoteStyle::Double)
+            .format(&value)
+            .unwrap();
+        assert!(double.contains("name: \"Alice\"\n"), "{double}");
+        assert!(double.contains("note: \"it's\"\n"), "{double}");
+        assert!(double.contains("n: 1\n"), "{double}");
+        assert!(double.contains("t: \"a\\nb\"\n"), "{double}");
+        assert_eq!(YamlSupport::parse(&double).unwrap(), value);
+
+        let single = YamlFormatter::new()
+            .with_quote_style(YamlQuoteStyle::Single)
+            .format(&value)
+            .unwrap();
+        assert!(single.contains("name: 'Alice'\n"), "{single}");
+        assert!(single.contains("note: 'it''s'\n"), "{single}");
+        assert!(single.contains("t: \"a\\nb\"\n"), "{single}");
+        assert_eq!(YamlSupport::parse(&single).unwrap(), value);
+
+        // 默认保持 serde_yaml 的启发式
+        let auto = YamlFormatter::new().format(&value).unwrap();
+        assert!(auto.contains("name: Alice\n"), "{auto}");
+
+        assert_eq!(
+            YamlFormatter::new()
+                .with_quote_style(YamlQuoteStyle::Double)
+                .format(&json!(["x", ".inf"]))
+                .unwrap(),
+            "- \"x\"\n- \".inf\"\n"
+        );
+
+        // 强制引号风格下，所有形似其他类型的字符串都加引号
+        let lookalikes =
+            json!(["true", "1", "null", "~", ".inf", "-.inf", ".nan", "08"]);
+        for (style, quote) in [
+            (YamlQuoteStyle::Double, '"'),
+            (YamlQuoteStyle::Single, '\''),
+        ] {
+            let output = YamlFormatter::new()
+                .with_quote_style(style)
+                .format(&lookalikes)
+                .unwrap();
+            for line in output.lines() {
+                let scalar = line.strip_prefix("- ").unwrap();
+                assert!(
+                    scalar.starts_with(quote) && scalar.ends_with(quote),
+                    "{output}"
+                );
+            }
+            assert_eq!(YamlSupport::parse(&output).unwrap(), lookalikes);
+        }
+    }
 }
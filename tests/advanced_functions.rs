@@ -1,5 +1,11 @@
 use serde_json::json;
-use xqpath::{evaluate_path_expression, parse_path_expression, PathExpression};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use xqpath::{
+    evaluate_path_expression, parse_path_expression, BuiltinFunction,
+    EvaluationError, ExpressionEvaluator, FunctionRegistry, PathExpression,
+    XqpathError,
+};
 
 #[allow(clippy::uninlined_format_args)]
 #[test]
@@ -30,6 +36,43 @@ fn test_map_function() {
     assert_eq!(result, vec![json!(["Alice", "Bob"])]);
 }
 
+#[test]
+fn test_map_values_function() {
+    // 对象：保留键，只变换值
+    let data = json!({"a": 1, "b": 2});
+    let result = evaluate_path_expression(
+        &parse_path_expression("map_values(. > 1)").unwrap(),
+        &data,
+    )
+    .unwrap();
+    assert_eq!(result, vec![json!({"a": false, "b": true})]);
+
+    // 无结果的值会被移除
+    let data = json!({"a": {"x": 1}, "b": {"y": 2}});
+    let result = evaluate_path_expression(
+        &parse_path_expression("map_values(select(.x))").unwrap(),
+        &data,
+    )
+    .unwrap();
+    assert_eq!(result, vec![json!({"a": {"x": 1}})]);
+
+    // 数组：每个元素只取第一个结果
+    let data = json!([{"a": 1, "b": 2}, {"a": 3, "b": 4}]);
+    let result = evaluate_path_expression(
+        &parse_path_expression("map_values(.a, .b)").unwrap(),
+        &data,
+    )
+    .unwrap();
+    assert_eq!(result, vec![json!([1, 3])]);
+
+    // 标量不支持
+    assert!(evaluate_path_expression(
+        &parse_path_expression("map_values(.)").unwrap(),
+        &json!(1),
+    )
+    .is_err());
+}
+
 #[test]
 fn test_select_function() {
     let data = json!([1, 2, 3, 4, 5]);
@@ -65,6 +108,37 @@ fn test_select_function() {
     );
 }
 
+#[test]
+fn test_any_all_functions() {
+    let eval = |expr: &str, data: &serde_json::Value| {
+        evaluate_path_expression(&parse_path_expression(expr).unwrap(), data)
+            .unwrap()
+    };
+
+    // 无参形式判断元素本身的真值
+    assert_eq!(eval("any()", &json!([false, null, 1])), vec![json!(true)]);
+    assert_eq!(eval("all()", &json!([true, 1, "x"])), vec![json!(true)]);
+    assert_eq!(eval("all()", &json!([true, 0])), vec![json!(false)]);
+
+    // 条件形式
+    let users = json!({"users": [{"age": 20}, {"age": 17}]});
+    assert_eq!(eval(".users | all(.age >= 18)", &users), vec![json!(false)]);
+    assert_eq!(eval(".users | any(.age >= 18)", &users), vec![json!(true)]);
+
+    // 空数组：all 为 true，any 为 false
+    assert_eq!(eval("all()", &json!([])), vec![json!(true)]);
+    assert_eq!(eval("any()", &json!([])), vec![json!(false)]);
+    assert_eq!(eval("all(. > 1)", &json!([])), vec![json!(true)]);
+    assert_eq!(eval("any(. > 1)", &json!([])), vec![json!(false)]);
+
+    // 非数组输入报错
+    assert!(evaluate_path_expression(
+        &parse_path_expression("any()").unwrap(),
+        &json!({"a": 1}),
+    )
+    .is_err());
+}
+
 #[test]
 fn test_sort_function() {
     let data = json!([3, 1, 4, 1, 5, 9, 2, 6]);
@@ -129,6 +203,89 @@ fn test_sort_by_function() {
     );
 }
 
+#[test]
+fn test_sort_by_multiple_keys() {
+    let data = json!([
+        {"name": "Alice", "dept": "Sales", "age": 40},
+        {"name": "Bob", "dept": "Eng", "age": 35},
+        {"name": "Carol", "dept": "Sales", "age": 25},
+        {"name": "Dave", "dept": "Eng", "age": 28}
+    ]);
+
+    let expected = json!(["Dave", "Bob", "Carol", "Alice"]);
+    for expr in ["sort_by(.dept, .age)", "sort_by(.dept; .age)"] {
+        let result = evaluate_path_expression(
+            &parse_path_expression(&format!("{expr} | map(.name)")).unwrap(),
+            &data,
+        )
+        .unwrap();
+        assert_eq!(result, vec![expected.clone()], "{expr}");
+    }
+}
+
+#[test]
+fn test_function_argument_separator() {
+    // jq 风格以分号分隔参数；逗号属于参数内部的逗号表达式
+    let arg_count = |source: &str| match parse_path_expression(source).unwrap()
+    {
+        PathExpression::FunctionCall { args, .. } => args.len(),
+        other => panic!("expected a function call, got {other:?}"),
+    };
+    assert_eq!(arg_count("sort_by(.a; .b)"), 2);
+    assert_eq!(arg_count("sort_by( .a ;.b ; .c )"), 3);
+    assert_eq!(arg_count("sort_by(.a, .b)"), 1);
+    assert_eq!(arg_count("sort_by(.a, .b; .c)"), 2);
+
+    assert!(parse_path_expression("sort_by(.a;)").is_err());
+    assert!(parse_path_expression("sort_by(; .a)").is_err());
+}
+
+#[test]
+fn test_sort_desc_functions() {
+    let result = evaluate_path_expression(
+        &parse_path_expression("sort_desc()").unwrap(),
+        &json!([3, null, 1, 2]),
+    )
+    .unwrap();
+    assert_eq!(result, vec![json!([3, 2, 1, null])]);
+
+    let data = json!([
+        {"name": "Alice", "age": 30},
+        {"name": "Bob", "age": 25},
+        {"name": "Carol", "age": 35},
+        {"name": "Dave", "age": 30}
+    ]);
+    let result = evaluate_path_expression(
+        &parse_path_expression("sort_by_desc(.age) | map(.name)").unwrap(),
+        &data,
+    )
+    .unwrap();
+    // 相等元素保持原有顺序
+    assert_eq!(result, vec![json!(["Carol", "Alice", "Dave", "Bob"])]);
+}
+
+#[test]
+fn test_min_by_max_by_functions() {
+    let users = json!([
+        {"name": "Alice", "age": 30},
+        {"name": "Bob", "age": 52},
+        {"name": "Carol", "age": 19},
+        {"name": "Dave", "age": 52}
+    ]);
+    let eval = |expr: &str, data: &serde_json::Value| {
+        evaluate_path_expression(&parse_path_expression(expr).unwrap(), data)
+            .unwrap()
+    };
+
+    // 最年长的用户，平局时取第一个
+    assert_eq!(eval("max_by(.age) | .name", &users), vec![json!("Bob")]);
+    assert_eq!(eval("min_by(.age) | .name", &users), vec![json!("Carol")]);
+
+    // 空数组返回 null
+    assert_eq!(eval("max_by(.age)", &json!([])), vec![json!(null)]);
+    assert_eq!(eval("min_by(.age)", &json!([])), vec![json!(null)]);
+}
+
 #[test]
 fn test_group_by_function() {
     let users_data = json!([
@@ -182,6 +339,71 @@ fn test_group_by_function() {
     }
 }
 
+/// 每次调用返回递增计数的函数，用于验证键表达式只被求值一次
+struct TickFunction {
+    calls: Arc<AtomicUsize>,
+}
+
+impl BuiltinFunction for TickFunction {
+    fn name(&self) -> &str {
+        "tick"
+    }
+
+    fn execute(
+        &self,
+        _args: &[serde_json::Value],
+        _input: &serde_json::Value,
+    ) -> Result<Vec<serde_json::Value>, EvaluationError> {
+        // 倒序计数，使分组顺序与输入顺序相反
+        let call = self.calls.fetch_add(1, AtomicOrdering::SeqCst);
+        Ok(vec![json!(100 - call)])
+    }
+}
+
+#[test]
+fn test_group_by_evaluates_key_once() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut registry = FunctionRegistry::new();
+    registry.register(Box::new(TickFunction {
+        calls: Arc::clone(&calls),
+    }));
+    let evaluator = ExpressionEvaluator::with_function_registry(registry);
+
+    let data = json!(["a", "b", "c"]);
+    let result = evaluator
+        .evaluate(&parse_path_expression("group_by(tick())").unwrap(), &data)
+        .unwrap();
+
+    // 每个元素只求值一次键，并按保存的键（100、99、98）排序
+    assert_eq!(calls.load(AtomicOrdering::SeqCst), 3);
+    assert_eq!(result, vec![json!([["c"], ["b"], ["a"]])]);
+}
+
+#[test]
+fn test_count_by_function() {
+    let users = json!([
+        {"name": "Alice", "role": "admin"},
+        {"name": "Bob", "role": "user"},
+        {"name": "Carol", "role": "user"},
+        {"name": "Dave", "role": "user"},
+        {"name": "Eve"}
+    ]);
+
+    let result = evaluate_path_expression(
+        &parse_path_expression("count_by(.role)").unwrap(),
+        &users,
+    )
+    .unwrap();
+    assert_eq!(result, vec![json!({"admin": 1, "user": 3, "null": 1})]);
+
+    let result = evaluate_path_expression(
+        &parse_path_expression("count_by(. > 2)").unwrap(),
+        &json!([1, 2, 3, 4, 5]),
+    )
+    .unwrap();
+    assert_eq!(result, vec![json!({"false": 2, "true": 3})]);
+}
+
 #[test]
 fn test_unique_function() {
     let data = json!([1, 2, 2, 3, 3, 3, 4]);
@@ -253,6 +475,307 @@ fn test_reverse_function() {
     assert_eq!(result, vec![json!(["cherry", "banana", "apple"])]);
 }
 
+/// 测试 paths 函数：无参数列出全部路径，带过滤条件只保留匹配的节点
+#[test]
+fn test_paths_function() {
+    let data = json!({
+        "server": {"host": "localhost", "port": 8080},
+        "replicas": 3,
+        "tags": ["web", {"env": "prod"}]
+    });
+
+    let expr = parse_path_expression("paths()").unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result.len(), 8);
+    assert!(result.contains(&json!(["server"])));
+    assert!(result.contains(&json!(["tags", 1, "env"])));
+
+    let expr = parse_path_expression(r#"paths(type() == "string")"#).unwrap();
+    let mut result = evaluate_path_expression(&expr, &data).unwrap();
+    result.sort_by_key(|path| path.to_string());
+    assert_eq!(
+        result,
+        vec![
+            json!(["server", "host"]),
+            json!(["tags", 0]),
+            json!(["tags", 1, "env"]),
+        ]
+    );
+
+    let expr = parse_path_expression(r#"paths(type() == "number")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result.len(), 2);
+    assert!(result.contains(&json!(["replicas"])));
+    assert!(result.contains(&json!(["server", "port"])));
+
+    // 过滤表达式中的裸标识符是无参函数调用，带点的仍是字段访问
+    let expr = parse_path_expression(r#"paths(type == "number")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result.len(), 2);
+    assert!(result.contains(&json!(["replicas"])));
+    assert!(result.contains(&json!(["server", "port"])));
+
+    let expr = parse_path_expression(r#"paths(.env == "prod")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result, vec![json!(["tags", 1])]);
+
+    let expr = parse_path_expression("paths(host)").unwrap();
+    assert!(matches!(
+        evaluate_path_expression(&expr, &data),
+        Err(XqpathError::Evaluation(EvaluationError::UnknownFunction(name)))
+            if name == "host"
+    ));
+
+    // 标量没有子路径
+    let expr = parse_path_expression("paths()").unwrap();
+    assert!(evaluate_path_expression(&expr, &json!(1))
+        .unwrap()
+        .is_empty());
+}
+
+/// 测试 leaf_paths 函数只返回标量叶子的 [路径, 值]
+#[test]
+fn test_leaf_paths_function() {
+    let data = json!({"a": {"b": 1, "c": []}, "d": ["x"]});
+
+    let expr = parse_path_expression("leaf_paths()").unwrap();
+    let mut result = evaluate_path_expression(&expr, &data).unwrap();
+    result.sort_by_key(|pair| pair.to_string());
+    assert_eq!(result, vec![json!([["a", "b"], 1]), json!([["d", 0], "x"])]);
+}
+
+/// 测试 input/inputs 从求值器的输入队列中读取后续文档
+#[test]
+fn test_input_functions() {
+    let evaluator = ExpressionEvaluator::new().with_inputs(vec![
+        json!({"id": 1}),
+        json!({"id": 2}),
+        json!({"id": 3}),
+    ]);
+
+    let expr = parse_path_expression("input() | .id").unwrap();
+    assert_eq!(
+        evaluator.evaluate(&expr, &json!(null)).unwrap(),
+        vec![json!(1)]
+    );
+
+    let expr = parse_path_expression("inputs() | .id").unwrap();
+    assert_eq!(
+        evaluator.evaluate(&expr, &json!(null)).unwrap(),
+        vec![json!(2), json!(3)]
+    );
+
+    // 输入耗尽后 input 报错，inputs 返回空流
+    let expr = parse_path_expression("input()").unwrap();
+    assert!(evaluator.evaluate(&expr, &json!(null)).is_err());
+    let expr = parse_path_expression("inputs()").unwrap();
+    assert!(evaluator.evaluate(&expr, &json!(null)).unwrap().is_empty());
+}
+
+/// 测试 range 函数生成升序、降序与带步长的数值流
+#[test]
+fn test_range_function() {
+    let eval = |source: &str| {
+        let expr = parse_path_expression(source).unwrap();
+        evaluate_path_expression(&expr, &json!({"n": 3}))
+    };
+
+    assert_eq!(
+        eval("range(0; 5)").unwrap(),
+        vec![json!(0), json!(1), json!(2), json!(3), json!(4)]
+    );
+    assert_eq!(
+        eval("range(0; 10; 4)").unwrap(),
+        vec![json!(0), json!(4), json!(8)]
+    );
+    assert_eq!(
+        eval("range(5; 0; -2)").unwrap(),
+        vec![json!(5), json!(3), json!(1)]
+    );
+    assert_eq!(
+        eval("range(.n)").unwrap(),
+        vec![json!(0), json!(1), json!(2)]
+    );
+    assert_eq!(
+        eval("range(0; 1; 0.25)").unwrap(),
+        vec![json!(0), json!(0.25), json!(0.5), json!(0.75)]
+    );
+
+    // 方向与步长相反时为空
+    assert!(eval("range(5; 0)").unwrap().is_empty());
+
+    assert!(matches!(
+        eval("range(0; 5; 0)"),
+        Err(XqpathError::Evaluation(EvaluationError::InvalidArguments(
+            _
+        )))
+    ));
+    assert!(eval(r#"range("a"; 5)"#).is_err());
+    assert!(eval("range()").is_err());
+
+    // 元素过多时在生成前报错，而不是耗尽内存
+    for expr in [
+        "range(10000000000)",
+        "first(range(10000000000))",
+        "range(0; 1; 0.0000000001)",
+        "range(0; 0 - 100000000000; 0 - 1)",
+    ] {
+        assert!(
+            matches!(
+                eval(expr),
+                Err(XqpathError::Evaluation(
+                    EvaluationError::InvalidArguments(_)
+                ))
+            ),
+            "{expr}"
+        );
+    }
+    assert!(eval("range(100000000000; 0)").unwrap().is_empty());
+}
+
+/// 测试 map 与 select 作用于对象输入的行为
+#[test]
+fn test_map_select_on_objects() {
+    // map 遍历对象的值并返回数组
+    let data = json!({"a": {"n": 1}, "b": {"n": 2}});
+    let result = evaluate_path_expression(
+        &parse_path_expression("map(.n)").unwrap(),
+        &data,
+    )
+    .unwrap();
+    assert_eq!(result.len(), 1);
+    let mut values = result[0].as_array().unwrap().clone();
+    values.sort_by_key(|v| v.as_i64());
+    assert_eq!(values, vec![json!(1), json!(2)]);
+
+    let result = evaluate_path_expression(
+        &parse_path_expression("map(.)").unwrap(),
+        &json!({}),
+    )
+    .unwrap();
+    assert_eq!(result, vec![json!([])]);
+
+    // select 对对象整体进行过滤
+    let result = evaluate_path_expression(
+        &parse_path_expression("select(.a.n == 1)").unwrap(),
+        &data,
+    )
+    .unwrap();
+    assert_eq!(result, vec![data.clone()]);
+
+    let result = evaluate_path_expression(
+        &parse_path_expression("select(.a.n == 2)").unwrap(),
+        &data,
+    )
+    .unwrap();
+    assert!(result.is_empty());
+
+    // 标量输入的错误信息包含实际类型
+    let err = evaluate_path_expression(
+        &parse_path_expression("map(.)").unwrap(),
+        &json!("text"),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("got string"), "{err}");
+}
+
+/// 测试 del 一次删除多个键和数组元素
+#[cfg(feature = "update")]
+#[test]
+fn test_del_function() {
+    let data = json!({"a": 1, "b": [10, 20, 30, 40], "c": {"d": 2, "e": 3}});
+    let del = |expr: &str| {
+        evaluate_path_expression(&parse_path_expression(expr).unwrap(), &data)
+    };
+
+    assert_eq!(
+        del("del(.a, .c.d)").unwrap(),
+        vec![json!({"b": [10, 20, 30, 40], "c": {"e": 3}})]
+    );
+    assert_eq!(
+        del("del(.b[1], .b[3]; .a)").unwrap(),
+        vec![json!({"b": [10, 30], "c": {"d": 2, "e": 3}})]
+    );
+    assert_eq!(
+        del("del(.b[*])").unwrap(),
+        vec![json!({"a": 1, "b": [], "c": {"d": 2, "e": 3}})]
+    );
+
+    // 父节点与子节点同时匹配、重复路径和不存在的字段
+    assert_eq!(
+        del("del(.c.d, .c, .a, .a, .missing)").unwrap(),
+        vec![json!({"b": [10, 20, 30, 40]})]
+    );
+
+    assert_eq!(del("del(.)").unwrap(), vec![json!(null)]);
+
+    assert!(del("del()").is_err());
+    assert!(del("del(1 + 1)").is_err());
+    assert!(del("del(.b[9])").is_err());
+}
+
+#[test]
+fn test_first_last_nth_functions() {
+    let data = json!({"items": [{"id": 1}, {"id": 2}, {"id": 3}], "empty": []});
+    let eval = |expr: &str| {
+        evaluate_path_expression(&parse_path_expression(expr).unwrap(), &data)
+    };
+
+    assert_eq!(eval(".items | first()").unwrap(), vec![json!({"id": 1})]);
+    assert_eq!(eval(".items | last()").unwrap(), vec![json!({"id": 3})]);
+    assert_eq!(eval(".items | nth(1)").unwrap(), vec![json!({"id": 2})]);
+    assert_eq!(eval(".empty | first()").unwrap(), vec![json!(null)]);
+    assert_eq!(eval(".items | nth(5)").unwrap(), vec![json!(null)]);
+
+    assert_eq!(
+        eval("first(.items[*] | select(.id > 1))").unwrap(),
+        vec![json!({"id": 2})]
+    );
+    assert_eq!(eval("last(.items[*].id)").unwrap(), vec![json!(3)]);
+    assert_eq!(eval("nth(2; .items[*].id)").unwrap(), vec![json!(3)]);
+    assert_eq!(eval("first(.items[*].id, 10)").unwrap(), vec![json!(1)]);
+    assert_eq!(
+        eval("nth(1; .items[0].id, 20, 30)").unwrap(),
+        vec![json!(20)]
+    );
+
+    // 没有结果时输出为空
+    assert!(eval("first(.empty[*])").unwrap().is_empty());
+    assert!(eval("nth(9; .items[*])").unwrap().is_empty());
+
+    assert!(eval(".items[0] | first()").is_err());
+    assert!(eval(r#"nth("a"; .items[*])"#).is_err());
+    assert!(eval("nth(-1; .items[*])").is_err());
+}
+
+#[test]
+fn test_first_nth_stop_early() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut registry = FunctionRegistry::new();
+    registry.register(Box::new(TickFunction {
+        calls: Arc::clone(&calls),
+    }));
+    let evaluator = ExpressionEvaluator::with_function_registry(registry);
+    let data = json!({"items": (0..100).collect::<Vec<_>>()});
+
+    let first = parse_path_expression("first(.items[*] | tick())").unwrap();
+    assert_eq!(evaluator.evaluate(&first, &data).unwrap(), vec![json!(100)]);
+    assert_eq!(calls.load(AtomicOrdering::SeqCst), 1);
+
+    // 左结合的多级管道同样在凑够结果后停止
+    calls.store(0, AtomicOrdering::SeqCst);
+    let nth =
+        parse_path_expression("nth(2; .items[*] | . + 1 | tick())").unwrap();
+    assert_eq!(evaluator.evaluate(&nth, &data).unwrap(), vec![json!(98)]);
+    assert_eq!(calls.load(AtomicOrdering::SeqCst), 3);
+
+    // last 需要完整求值
+    calls.store(0, AtomicOrdering::SeqCst);
+    let last = parse_path_expression("last(.items[*] | tick())").unwrap();
+    assert_eq!(evaluator.evaluate(&last, &data).unwrap(), vec![json!(1)]);
+    assert_eq!(calls.load(AtomicOrdering::SeqCst), 100);
+}
+
 #[test]
 fn test_advanced_function_combinations() {
     let users_data = json!([
@@ -0,0 +1,30 @@
+#![cfg(feature = "alloc-profiling")]
+
+use xqpath::{query_memory, CountingAllocator};
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator::new();
+
+/// 测试分配器计数反映查询自身的分配峰值
+#[test]
+fn test_counting_allocator_peak() {
+    let data = format!(
+        r#"{{"items": [{}]}}"#,
+        vec![r#"{"name": "item"}"#; 1000].join(",")
+    );
+
+    let (values, report) = query_memory!(data, ".items[*].name").unwrap();
+    assert_eq!(values.len(), 1000);
+    assert!(CountingAllocator::is_installed());
+    assert!(report.peak_memory_bytes > 0);
+    // 峰值只包含查询自身的分配，远小于进程 RSS
+    assert!(report.peak_memory_bytes < 64 * 1024 * 1024);
+
+    CountingAllocator::start();
+    let buffer = vec![0u8; 4096];
+    assert!(CountingAllocator::peak() >= 4096);
+    drop(buffer);
+    assert!(CountingAllocator::freed() >= 4096);
+    CountingAllocator::reset();
+    assert!(CountingAllocator::peak() < 4096);
+}
@@ -1,5 +1,8 @@
 use serde_json::json;
-use xqpath::{evaluate_path_expression, parse_path_expression, PathExpression};
+use xqpath::{
+    evaluate_path_expression, parse_path_expression, FunctionRegistry,
+    PathExpression,
+};
 
 #[test]
 fn test_builtin_functions() {
@@ -47,6 +50,438 @@ fn test_builtin_functions() {
     assert!(values_array.contains(&json!(2)));
 }
 
+#[test]
+fn test_index_and_indices_functions() {
+    let eval = |expr: &str, data: &serde_json::Value| {
+        evaluate_path_expression(&parse_path_expression(expr).unwrap(), data)
+            .unwrap()
+    };
+
+    // 字符串子串查找（按字符计）
+    let text = json!("a,b, cd, efg");
+    assert_eq!(eval(r#"index(", ")"#, &text), vec![json!(3)]);
+    assert_eq!(eval(r#"indices(", ")"#, &text), vec![json!([3, 7])]);
+    assert_eq!(eval(r#"index("é")"#, &json!("café")), vec![json!(3)]);
+    assert_eq!(eval(r#"index("x")"#, &text), vec![json!(null)]);
+    assert_eq!(eval(r#"indices("x")"#, &text), vec![json!([])]);
+
+    // 数组元素与子数组查找
+    let arr = json!([0, 1, 2, 1, 3, 1, 2]);
+    assert_eq!(eval("indices(1)", &arr), vec![json!([1, 3, 5])]);
+    assert_eq!(eval("index(1)", &arr), vec![json!(1)]);
+    assert_eq!(eval("indices([1, 2])", &arr), vec![json!([1, 5])]);
+    assert_eq!(eval("index(9)", &arr), vec![json!(null)]);
+
+    // null 输入返回 null
+    assert_eq!(eval("index(1)", &json!(null)), vec![json!(null)]);
+
+    // 类型不匹配报错
+    assert!(evaluate_path_expression(
+        &parse_path_expression("index(1)").unwrap(),
+        &json!("abc"),
+    )
+    .is_err());
+}
+
+#[test]
+fn test_number_classification_functions() {
+    use xqpath::{ValueFormat, YamlFormat};
+
+    let data = YamlFormat
+        .parse("values: [1.5, 0, .inf, -.inf, .nan, 42]\n")
+        .unwrap();
+
+    let eval = |expr: &str| {
+        evaluate_path_expression(&parse_path_expression(expr).unwrap(), &data)
+            .unwrap()
+    };
+
+    assert_eq!(
+        eval(".values | map(isnan())"),
+        vec![json!([false, false, false, false, true, false])]
+    );
+    assert_eq!(
+        eval(".values | map(isinfinite())"),
+        vec![json!([false, false, true, true, false, false])]
+    );
+    assert_eq!(eval(".values | select(isnormal())"), vec![json!([1.5, 42])]);
+    assert_eq!(
+        eval(".values[*] | select(isnormal())"),
+        vec![json!(1.5), json!(42)]
+    );
+    // .nan 解析为 null，分类函数将其视为 NaN
+    assert_eq!(eval(".values[4] | isnan()"), vec![json!(true)]);
+
+    // 其余非数字输入报错，包括拼写为特殊浮点数的字符串
+    let quoted = YamlFormat
+        .parse("values: ['.nan', '.inf', \"-.inf\"]\n")
+        .unwrap();
+    for function in ["isnan()", "isinfinite()", "isnormal()"] {
+        for input in [json!("abc"), json!([]), quoted["values"][0].clone()] {
+            assert!(
+                evaluate_path_expression(
+                    &parse_path_expression(function).unwrap(),
+                    &input,
+                )
+                .is_err(),
+                "{function} on {input}"
+            );
+        }
+        let mapped = format!(".values | map({function})");
+        assert!(evaluate_path_expression(
+            &parse_path_expression(&mapped).unwrap(),
+            &quoted,
+        )
+        .is_err());
+    }
+}
+
+/// 测试 floor、ceil、round、abs 和 sqrt 数学函数
+#[test]
+fn test_math_functions() {
+    let data = json!([-2.5, -1, 0, 1.4, 2.5, 9]);
+    let eval = |expr: &str| {
+        evaluate_path_expression(&parse_path_expression(expr).unwrap(), &data)
+            .unwrap()
+    };
+
+    assert_eq!(eval("map(floor())"), vec![json!([-3, -1, 0, 1, 2, 9])]);
+    assert_eq!(eval("map(ceil())"), vec![json!([-2, -1, 0, 2, 3, 9])]);
+    assert_eq!(eval("map(round())"), vec![json!([-3, -1, 0, 1, 3, 9])]);
+    assert_eq!(eval("map(abs())"), vec![json!([2.5, 1, 0, 1.4, 2.5, 9])]);
+    let eval_on = |expr: &str, input: serde_json::Value| {
+        evaluate_path_expression(&parse_path_expression(expr).unwrap(), &input)
+            .unwrap()
+    };
+    assert_eq!(eval_on("sqrt()", json!(9)), vec![json!(3)]);
+    assert_eq!(eval_on("sqrt() | floor()", json!(2)), vec![json!(1)]);
+
+    // 负数的平方根为 NaN，与 jq 一样输出 null 而不是与用户数据混淆的字符串
+    assert_eq!(eval_on("sqrt()", json!(-4)), vec![json!(null)]);
+    assert_eq!(eval_on("sqrt() | type()", json!(-4)), vec![json!("null")]);
+
+    // 非数字输入报错
+    assert!(evaluate_path_expression(
+        &parse_path_expression("floor()").unwrap(),
+        &json!("abc"),
+    )
+    .is_err());
+}
+
+/// 测试 to_entries 和 from_entries 函数
+#[test]
+fn test_entries_functions() {
+    let data = json!({"a": 1, "b": [true]});
+
+    let expr = parse_path_expression("to_entries()").unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(
+        result,
+        vec![json!([
+            {"key": "a", "value": 1},
+            {"key": "b", "value": [true]}
+        ])]
+    );
+
+    // 往返后得到原对象
+    let expr = parse_path_expression("to_entries() | from_entries()").unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result, vec![data]);
+
+    // 兼容 k/v、name 以及非字符串键
+    let entries =
+        json!([{"k": "x", "v": 1}, {"name": "y"}, {"key": 2, "value": "z"}]);
+    let expr = parse_path_expression("from_entries()").unwrap();
+    let result = evaluate_path_expression(&expr, &entries).unwrap();
+    assert_eq!(result, vec![json!({"x": 1, "y": null, "2": "z"})]);
+
+    let expr = parse_path_expression("to_entries()").unwrap();
+    assert!(evaluate_path_expression(&expr, &json!([1])).is_err());
+    let expr = parse_path_expression("from_entries()").unwrap();
+    assert!(evaluate_path_expression(&expr, &json!([{"value": 1}])).is_err());
+}
+
+/// 测试 split 按字面分隔符分割字符串
+#[test]
+fn test_split_function() {
+    let data = json!("a, b, c");
+
+    let expr = parse_path_expression(r#"split(", ")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result, vec![json!(["a", "b", "c"])]);
+
+    // 分隔符按字面匹配，不作为正则解释
+    let expr = parse_path_expression(r#"split(".")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &json!("1.2.3")).unwrap();
+    assert_eq!(result, vec![json!(["1", "2", "3"])]);
+
+    let expr = parse_path_expression(r#"split("")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &json!("ab")).unwrap();
+    assert_eq!(result, vec![json!(["a", "b"])]);
+
+    let expr = parse_path_expression(r#"split(",")"#).unwrap();
+    assert!(evaluate_path_expression(&expr, &json!(42)).is_err());
+}
+
+/// 测试带标志的 split 与 splits 按正则分割
+#[cfg(feature = "regex")]
+#[test]
+fn test_regex_split_functions() {
+    let data = json!("a  b\t\tc d");
+
+    let expr = parse_path_expression(r#"split("\s+"; "g")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result, vec![json!(["a", "b", "c", "d"])]);
+
+    // 捕获组不影响分割结果
+    let expr = parse_path_expression(r#"split("(,|;) *"; null)"#).unwrap();
+    let result = evaluate_path_expression(&expr, &json!("x, y;z")).unwrap();
+    assert_eq!(result, vec![json!(["x", "y", "z"])]);
+
+    let expr = parse_path_expression(r#"split("A"; "i")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &json!("xaYAz")).unwrap();
+    assert_eq!(result, vec![json!(["x", "Y", "z"])]);
+
+    let expr = parse_path_expression(r#"splits("[0-9]+")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &json!("a1b22c")).unwrap();
+    assert_eq!(result, vec![json!("a"), json!("b"), json!("c")]);
+
+    let expr = parse_path_expression(r#"split("("; "g")"#).unwrap();
+    assert!(evaluate_path_expression(&expr, &data).is_err());
+    let expr = parse_path_expression(r#"split(" "; "q")"#).unwrap();
+    assert!(evaluate_path_expression(&expr, &data).is_err());
+}
+
+/// 测试 sub/gsub 正则替换：改写日期格式与遮盖密钥
+#[cfg(feature = "regex")]
+#[test]
+fn test_regex_replace_functions() {
+    let dates = json!("2024-01-31, 2023-12-01");
+
+    let expr = parse_path_expression(
+        r#"sub("(?<y>\d{4})-(?<m>\d{2})-(?<d>\d{2})"; "\(.d)/\(.m)/\(.y)")"#,
+    )
+    .unwrap();
+    let result = evaluate_path_expression(&expr, &dates).unwrap();
+    assert_eq!(result, vec![json!("31/01/2024, 2023-12-01")]);
+
+    let expr =
+        parse_path_expression(r#"gsub("(\d{4})-(\d{2})-(\d{2})"; "$3.$2.$1")"#)
+            .unwrap();
+    let result = evaluate_path_expression(&expr, &dates).unwrap();
+    assert_eq!(result, vec![json!("31.01.2024, 01.12.2023")]);
+
+    let data = json!({"secrets": ["token=abc123", "TOKEN=xyz"]});
+    let expr = parse_path_expression(
+        r#".secrets | map(gsub("(?<key>token)=\w+"; "\(.key)=***"; "i"))"#,
+    )
+    .unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result, vec![json!(["token=***", "TOKEN=***"])]);
+
+    // sub 加上 g 标志等同于 gsub
+    let expr = parse_path_expression(r#"sub("a"; "b"; "g")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &json!("aaa")).unwrap();
+    assert_eq!(result, vec![json!("bbb")]);
+
+    for invalid in [
+        r#"sub("("; "x")"#,
+        r#"sub("(?<a>x)"; "\(.b)")"#,
+        r#"gsub("x")"#,
+    ] {
+        let expr = parse_path_expression(invalid).unwrap();
+        let err = evaluate_path_expression(&expr, &json!("x")).unwrap_err();
+        assert!(err.to_string().contains("sub"), "{invalid}: {err}");
+    }
+    let expr = parse_path_expression(r#"gsub("x"; "y")"#).unwrap();
+    assert!(evaluate_path_expression(&expr, &json!(1)).is_err());
+}
+
+/// 测试 trim、ltrim、rtrim 去除首尾空白
+#[test]
+fn test_trim_functions() {
+    let data = json!("  \t value \n");
+
+    for (function, expected) in [
+        ("trim()", "value"),
+        ("ltrim()", "value \n"),
+        ("rtrim()", "  \t value"),
+    ] {
+        let expr = parse_path_expression(function).unwrap();
+        let result = evaluate_path_expression(&expr, &data).unwrap();
+        assert_eq!(result, vec![json!(expected)], "{function}");
+
+        let expr = parse_path_expression(function).unwrap();
+        assert!(evaluate_path_expression(&expr, &json!(1)).is_err());
+    }
+
+    let expr = parse_path_expression(".items | map(trim())").unwrap();
+    let result =
+        evaluate_path_expression(&expr, &json!({"items": [" a", "b "]}))
+            .unwrap();
+    assert_eq!(result, vec![json!(["a", "b"])]);
+}
+
+/// 测试 @sh 为 shell 命令行安全地加引号
+#[test]
+fn test_sh_format() {
+    let data = json!({
+        "args": ["hello world", "it's", "$HOME", "a\"b", 42, true, null],
+        "name": "rm -rf '/'",
+        "nested": [["x"]]
+    });
+
+    let expr = parse_path_expression(".args | @sh").unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(
+        result,
+        vec![json!(
+            r#"'hello world' 'it'\''s' '$HOME' 'a"b' 42 true null"#
+        )]
+    );
+
+    let expr = parse_path_expression(".name | @sh").unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result, vec![json!(r#"'rm -rf '\''/'\'''"#)]);
+
+    let expr = parse_path_expression(".args | map(@sh)").unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result[0][1], json!(r#"'it'\''s'"#));
+
+    for invalid in [".nested | @sh", ". | @sh", ".args | @nope"] {
+        let expr = parse_path_expression(invalid).unwrap();
+        assert!(evaluate_path_expression(&expr, &data).is_err(), "{invalid}");
+    }
+}
+
+/// 测试 tojson/fromjson 处理内嵌在字符串字段中的 JSON
+#[test]
+fn test_tojson_fromjson() {
+    let data = json!({
+        "config_blob": r#"{"nested": {"port": 8080}, "tags": ["a"]}"#,
+        "bad": "{not json",
+        "n": 1
+    });
+
+    let expr =
+        parse_path_expression(".config_blob | fromjson() | .nested.port")
+            .unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result, vec![json!(8080)]);
+
+    let expr =
+        parse_path_expression(".config_blob | fromjson() | tojson()").unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(
+        result,
+        vec![json!(r#"{"nested":{"port":8080},"tags":["a"]}"#)]
+    );
+
+    let expr = parse_path_expression("tojson() | fromjson()").unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result, vec![data.clone()]);
+
+    // 无效 JSON 与非字符串输入报错并给出原因
+    let expr = parse_path_expression(".bad | fromjson()").unwrap();
+    let err = evaluate_path_expression(&expr, &data).unwrap_err();
+    assert!(
+        err.to_string().contains("fromjson could not parse"),
+        "{err}"
+    );
+    let expr = parse_path_expression(".n | fromjson()").unwrap();
+    let err = evaluate_path_expression(&expr, &data).unwrap_err();
+    assert!(
+        err.to_string().contains("only be applied to strings"),
+        "{err}"
+    );
+}
+
+/// 测试 env 函数读取环境变量
+#[cfg(feature = "env")]
+#[test]
+fn test_env_function() {
+    std::env::set_var("XQPATH_TEST_ENV", "hello");
+    let data = json!(null);
+
+    let expr = parse_path_expression(r#"env("XQPATH_TEST_ENV")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result, vec![json!("hello")]);
+
+    let expr = parse_path_expression("env() | .XQPATH_TEST_ENV").unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result, vec![json!("hello")]);
+
+    let expr = parse_path_expression(r#"env("XQPATH_TEST_MISSING")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &data).unwrap();
+    assert_eq!(result, vec![json!(null)]);
+
+    let expr = parse_path_expression("env(1)").unwrap();
+    assert!(evaluate_path_expression(&expr, &data).is_err());
+}
+
+/// 测试未启用 env feature 时 env 函数不可用
+#[cfg(not(feature = "env"))]
+#[test]
+fn test_env_function_disabled() {
+    let expr = parse_path_expression("env()").unwrap();
+    assert!(evaluate_path_expression(&expr, &json!(null)).is_err());
+}
+
+/// 测试 now、todate 和 strftime 时间函数
+#[cfg(feature = "time")]
+#[test]
+fn test_time_functions() {
+    let expr = parse_path_expression("now()").unwrap();
+    let result = evaluate_path_expression(&expr, &json!(null)).unwrap();
+    assert!(result[0].as_f64().unwrap() > 1_600_000_000.0);
+
+    let timestamp = json!(1425599507);
+    let expr = parse_path_expression("todate()").unwrap();
+    let result = evaluate_path_expression(&expr, &timestamp).unwrap();
+    assert_eq!(result, vec![json!("2015-03-05T23:51:47Z")]);
+
+    let expr =
+        parse_path_expression(r#"strftime("%A, %B %d, %Y %I:%M %p (%j)")"#)
+            .unwrap();
+    let result = evaluate_path_expression(&expr, &timestamp).unwrap();
+    assert_eq!(
+        result,
+        vec![json!("Thursday, March 05, 2015 11:51 PM (064)")]
+    );
+
+    // 小数部分被截断，负时间戳早于纪元
+    let expr = parse_path_expression(r#"strftime("%F %T")"#).unwrap();
+    let result = evaluate_path_expression(&expr, &json!(-0.5)).unwrap();
+    assert_eq!(result, vec![json!("1969-12-31 23:59:59")]);
+
+    let expr = parse_path_expression(r#"strftime("%Q")"#).unwrap();
+    assert!(evaluate_path_expression(&expr, &timestamp).is_err());
+    let expr = parse_path_expression(r#"strftime("%")"#).unwrap();
+    assert!(evaluate_path_expression(&expr, &timestamp).is_err());
+    let expr = parse_path_expression("todate()").unwrap();
+    assert!(evaluate_path_expression(&expr, &json!("x")).is_err());
+}
+
+/// 测试列出所有已注册函数
+#[test]
+fn test_list_functions() {
+    let registry = FunctionRegistry::new();
+    let functions = registry.list_functions();
+
+    for name in ["map", "select", "length"] {
+        let (_, description) = functions
+            .iter()
+            .find(|(n, _)| *n == name)
+            .unwrap_or_else(|| panic!("{name} not listed"));
+        assert!(!description.is_empty());
+    }
+
+    let names: Vec<&str> = functions.iter().map(|(name, _)| *name).collect();
+    let mut sorted = names.clone();
+    sorted.sort_unstable();
+    assert_eq!(names, sorted);
+}
+
 #[test]
 fn test_function_call_parsing() {
     // 测试无参函数调用
@@ -0,0 +1,1123 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// 以 stdin 输入运行 CLI，返回 (退出码, stdout)
+fn run_cli(args: &[&str], input: &str) -> (i32, String) {
+    let output = run_cli_output(args, input);
+    (
+        output.status.code().unwrap_or(-1),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+/// 以 stdin 输入运行 CLI，返回完整输出（含 stderr）
+fn run_cli_output(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_xqpath"))
+        .args(args)
+        .arg("--no-color")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start xqpath");
+
+    // 不读取 stdin 的命令（如 --null-input）可能先退出，忽略管道断开
+    let written = child.stdin.take().unwrap().write_all(input.as_bytes());
+    if let Err(e) = written {
+        assert_eq!(e.kind(), std::io::ErrorKind::BrokenPipe, "{e}");
+    }
+
+    child.wait_with_output().unwrap()
+}
+
+fn get_json(path: &str, input: &str) -> Vec<serde_json::Value> {
+    let (code, stdout) = run_cli(&["get", path, "-o", "json"], input);
+    assert_eq!(code, 0, "xqpath get {path} failed");
+    serde_json::Deserializer::from_str(&stdout)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+/// 测试 select 作用于单个对象：条件为真时返回对象，否则无输出
+#[test]
+fn test_cli_select_on_single_value() {
+    let active = r#"{"user": {"name": "Alice", "active": true}}"#;
+    assert_eq!(
+        get_json(".user | select(.active)", active),
+        vec![serde_json::json!({"name": "Alice", "active": true})]
+    );
+
+    let inactive = r#"{"user": {"name": "Bob", "active": false}}"#;
+    assert!(get_json(".user | select(.active)", inactive).is_empty());
+
+    let scalar = r#"{"name": "Alice"}"#;
+    assert_eq!(
+        get_json(r#".name | select(. == "Alice")"#, scalar),
+        vec![serde_json::json!("Alice")]
+    );
+    assert!(get_json(r#".name | select(. == "Bob")"#, scalar).is_empty());
+}
+
+/// 测试 select 作用于数组与逐个元素时结果一致
+#[test]
+fn test_cli_select_on_array() {
+    let input = r#"{"users": [
+        {"name": "Alice", "active": true},
+        {"name": "Bob", "active": false}
+    ]}"#;
+
+    assert_eq!(
+        get_json(".users | select(.active)", input),
+        vec![serde_json::json!([{"name": "Alice", "active": true}])]
+    );
+    assert_eq!(
+        get_json(".users[*] | select(.active) | .name", input),
+        vec![serde_json::json!("Alice")]
+    );
+}
+
+/// 测试简单路径仍然使用原有提取器
+#[test]
+fn test_cli_simple_path() {
+    assert_eq!(
+        get_json(".a.b[1]", r#"{"a": {"b": [1, 2]}}"#),
+        vec![serde_json::json!(2)]
+    );
+}
+
+/// 测试 --stream 输出 [path, leaf] 事件
+#[test]
+fn test_cli_stream_events() {
+    let (code, stdout) = run_cli(
+        &["get", ".", "--stream"],
+        r#"{"a": [1, {"b": true}], "c": "x"}"#,
+    );
+    assert_eq!(code, 0);
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        vec![r#"[["a",0],1]"#, r#"[["a",1,"b"],true]"#, r#"[["c"],"x"]"#]
+    );
+}
+
+/// 测试 --stats 输出结果类型直方图
+#[test]
+fn test_cli_stats_histogram() {
+    let (code, stdout) = run_cli(
+        &["get", ".items[*]", "--stats"],
+        r#"{"items": [1, "a", "b", {"k": 1}, 2.5, null]}"#,
+    );
+    assert_eq!(code, 0);
+
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .skip(1)
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            vec!["null", "1"],
+            vec!["number", "2"],
+            vec!["string", "2"],
+            vec!["object", "1"],
+            vec!["total", "6"],
+        ]
+    );
+}
+
+/// 测试强制着色时输出高亮 JSON，去除转义序列后与普通输出一致
+#[test]
+fn test_cli_colorized_output() {
+    let input =
+        r#"{"name": "Alice", "tags": ["a", 1, true, null], "empty": {}}"#;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_xqpath"))
+        .args(["get", "."])
+        .env("CLICOLOR_FORCE", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let colored =
+        String::from_utf8(child.wait_with_output().unwrap().stdout).unwrap();
+    assert!(colored.contains("\u{1b}["));
+
+    let mut plain = String::new();
+    let mut chars = colored.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // 跳过 ESC [ ... m
+            for c in chars.by_ref() {
+                if c == 'm' {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+
+    let (_, uncolored) = run_cli(&["get", "."], input);
+    assert_eq!(plain, uncolored);
+}
+
+/// 测试 --depth 限制递归通配符的下降层数
+#[test]
+fn test_cli_get_depth() {
+    let input = r#"{"a": {"b": {"c": 1}}}"#;
+
+    let (code, stdout) =
+        run_cli(&["get", "**", "--depth", "1", "-o", "json"], input);
+    assert_eq!(code, 0);
+    let values: Vec<serde_json::Value> =
+        serde_json::Deserializer::from_str(&stdout)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+    assert_eq!(
+        values,
+        vec![
+            serde_json::json!({"a": {"b": {"c": 1}}}),
+            serde_json::json!({"b": {"c": 1}}),
+        ]
+    );
+
+    assert_eq!(get_json("**", input).len(), 4);
+
+    // 表达式由求值器执行，无法限制深度，因此拒绝而不是静默忽略
+    for path in ["** | .c?", "[**]", "inputs() | **"] {
+        let output = run_cli_output(&["get", path, "--depth", "1"], input);
+        assert!(!output.status.success(), "{path}");
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("--depth only applies"), "{stderr}");
+    }
+}
+
+/// 测试 --out-file 将结果写入文件且不向标准输出打印
+#[test]
+fn test_cli_out_file() {
+    let dir = std::env::temp_dir()
+        .join(format!("xqpath-out-file-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = r#"{"data": {"b": 2, "a": 1}}"#;
+
+    let get_path = dir.join("get.json");
+    let (code, stdout) = run_cli(
+        &[
+            "get",
+            ".data.a",
+            "-o",
+            "json",
+            "--out-file",
+            get_path.to_str().unwrap(),
+        ],
+        input,
+    );
+    assert_eq!(code, 0);
+    assert!(stdout.is_empty());
+    assert_eq!(std::fs::read_to_string(&get_path).unwrap(), "1\n");
+
+    let keys_path = dir.join("keys.json");
+    let (code, stdout) = run_cli(
+        &[
+            "keys",
+            ".data",
+            "-o",
+            "compact",
+            "--out-file",
+            keys_path.to_str().unwrap(),
+        ],
+        input,
+    );
+    assert_eq!(code, 0);
+    assert!(stdout.is_empty());
+    let mut keys: Vec<String> =
+        serde_json::from_str(&std::fs::read_to_string(&keys_path).unwrap())
+            .unwrap();
+    keys.sort();
+    assert_eq!(keys, ["a", "b"]);
+
+    let yaml_path = dir.join("data.yaml");
+    let (code, stdout) = run_cli(
+        &["convert", "yaml", "--out-file", yaml_path.to_str().unwrap()],
+        input,
+    );
+    assert_eq!(code, 0);
+    assert!(stdout.is_empty());
+    let converted: serde_json::Value =
+        serde_yaml::from_str(&std::fs::read_to_string(&yaml_path).unwrap())
+            .unwrap();
+    assert_eq!(
+        converted,
+        serde_json::from_str::<serde_json::Value>(input).unwrap()
+    );
+
+    // 查询失败时不创建输出文件
+    let failed_path = dir.join("failed.json");
+    let (code, _) = run_cli(
+        &["get", ".data[", "--out-file", failed_path.to_str().unwrap()],
+        input,
+    );
+    assert_ne!(code, 0);
+    assert!(!failed_path.exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// 测试 --null-input 以 null 为输入求值，不读取标准输入
+#[test]
+fn test_cli_null_input() {
+    let (code, stdout) =
+        run_cli(&["get", "-n", r#"{"name": "xqpath"}"#, "-o", "compact"], "");
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "{\"name\":\"xqpath\"}\n");
+
+    // 标准输入的内容被忽略
+    let (code, stdout) =
+        run_cli(&["get", "--null-input", ".", "-o", "json"], "{\"a\": 1}");
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim(), "null");
+
+    let (code, stdout) = run_cli(
+        &["get", "-n", "[1, 2] | length()", "-o", "json"],
+        "not json",
+    );
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim(), "2");
+}
+
+/// 测试 --timing 将执行时间输出到标准错误，不影响标准输出
+#[test]
+fn test_cli_timing() {
+    let output = run_cli_output(
+        &["count", ".items[*]", "--timing"],
+        r#"{"items": [1, 2, 3]}"#,
+    );
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "3\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Execution time:"), "{stderr}");
+}
+
+/// 测试 --explain 在执行前将语法树输出到标准错误
+#[test]
+fn test_cli_explain() {
+    let output = run_cli_output(
+        &[
+            "get",
+            ".items | map(.b) | sort()",
+            "--explain",
+            "-o",
+            "compact",
+        ],
+        r#"{"items": [{"b": 2}, {"b": 1}]}"#,
+    );
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "[1,2]\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Pipe\n"), "{stderr}");
+    assert!(stderr.contains("    Call map\n      Path .b\n"), "{stderr}");
+    assert!(stderr.contains("Pipes: 2"), "{stderr}");
+}
+
+/// 测试 complexity 子命令输出表达式复杂度
+#[test]
+fn test_cli_complexity() {
+    let (code, stdout) = run_cli(&["complexity", ".a[*] | .b, .c"], "");
+    assert_eq!(code, 0);
+
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines
+        .iter()
+        .any(|l| l.starts_with("Pipes:") && l.ends_with(" 1")));
+    assert!(lines
+        .iter()
+        .any(|l| l.starts_with("Comma branches:") && l.ends_with(" 2")));
+    assert!(lines
+        .iter()
+        .any(|l| l.starts_with("Wildcards:") && l.ends_with("yes")));
+    assert!(lines
+        .iter()
+        .any(|l| l.starts_with("Recursive wildcards:") && l.ends_with("no")));
+
+    let (code, _) = run_cli(&["complexity", ".a["], "");
+    assert_ne!(code, 0);
+}
+
+/// 测试 paths 子命令列出全部路径，以及按过滤表达式只列出字符串叶子的路径
+#[test]
+fn test_cli_paths() {
+    let input = r#"{"name": "app", "ports": [80, 443], "db": {"host": "x", "tls": true}}"#;
+
+    let (code, stdout) = run_cli(&["paths", r#"type() == "string""#], input);
+    assert_eq!(code, 0);
+    let mut lines: Vec<&str> = stdout.lines().collect();
+    lines.sort();
+    assert_eq!(lines, vec![r#"["db","host"]"#, r#"["name"]"#]);
+
+    // 与 jq 一致，过滤表达式中的裸 type 是函数调用
+    let (code, stdout) = run_cli(&["paths", r#"type == "number""#], input);
+    assert_eq!(code, 0);
+    let mut lines: Vec<&str> = stdout.lines().collect();
+    lines.sort();
+    assert_eq!(lines, vec![r#"["ports",0]"#, r#"["ports",1]"#]);
+
+    let (code, stdout) = run_cli(&["paths"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout.lines().count(), 7);
+    assert!(stdout.lines().any(|line| line == r#"["ports",1]"#));
+}
+
+/// 测试 paths 子命令按 jq 与 JSONPath 两种语法输出同一位置
+#[test]
+fn test_cli_paths_jsonpath_syntax() {
+    let input = r#"{"users": [{"name": "a", "it's": 1}]}"#;
+
+    let (code, jq) = run_cli(&["paths", "--path-syntax", "jq"], input);
+    assert_eq!(code, 0);
+    let (code, jsonpath) =
+        run_cli(&["paths", "--path-syntax", "jsonpath"], input);
+    assert_eq!(code, 0);
+
+    let pairs: Vec<(&str, &str)> = jq.lines().zip(jsonpath.lines()).collect();
+    assert_eq!(pairs.len(), 4);
+    assert!(pairs.contains(&(".users", "$.users")));
+    assert!(pairs.contains(&(".users[0]", "$.users[0]")));
+    assert!(pairs.contains(&(".users[0].name", "$.users[0].name")));
+    assert!(pairs.contains(&(r#".users[0]."it's""#, r"$.users[0]['it\'s']")));
+}
+
+/// 测试 get 子命令通过 --path-syntax jsonpath 接受 JSONPath 输入
+#[test]
+fn test_cli_get_jsonpath_input() {
+    let input = r#"{"store": {"book": [{"title": "A", "author": "x"}, {"title": "B", "author": "y"}]}}"#;
+
+    let (code, stdout) = run_cli(
+        &["get", "--path-syntax", "jsonpath", "$.store.book[1].title"],
+        input,
+    );
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim(), r#""B""#);
+
+    let (code, stdout) = run_cli(
+        &[
+            "get",
+            "--path-syntax",
+            "jsonpath",
+            "$..author",
+            "-o",
+            "compact",
+        ],
+        input,
+    );
+    assert_eq!(code, 0);
+    assert_eq!(stdout.lines().collect::<Vec<_>>(), vec![r#""x""#, r#""y""#]);
+
+    let output = run_cli_output(
+        &[
+            "get",
+            "--path-syntax",
+            "jsonpath",
+            "$.store.book[?(@.title)]",
+        ],
+        input,
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("filter expressions are not supported"),
+        "{stderr}"
+    );
+}
+
+/// 测试 flatten-paths 子命令按点分隔键输出标量叶子
+#[test]
+fn test_cli_flatten_paths() {
+    let input = "db:\n  host: localhost\n  port: 5432\nhosts:\n  - name: a\nempty: {}\n";
+
+    let (code, stdout) = run_cli(&["flatten-paths"], input);
+    assert_eq!(code, 0);
+    let mut lines: Vec<&str> = stdout.lines().collect();
+    lines.sort();
+    assert_eq!(
+        lines,
+        vec!["db.host=localhost", "db.port=5432", "hosts.0.name=a"]
+    );
+}
+
+/// 测试 flatten 与 unflatten 子命令往返转换
+#[test]
+fn test_cli_flatten_unflatten() {
+    let input = r#"{"db": {"host": "x", "ports": [1, 2]}, "name": "app"}"#;
+
+    let (code, flat) = run_cli(&["flatten", "-o", "json"], input);
+    assert_eq!(code, 0);
+    let flat_value: serde_json::Value = serde_json::from_str(&flat).unwrap();
+    assert_eq!(
+        flat_value,
+        serde_json::json!({
+            "db.host": "x",
+            "db.ports.0": 1,
+            "db.ports.1": 2,
+            "name": "app"
+        })
+    );
+
+    let (code, nested) = run_cli(&["unflatten", "-o", "json"], &flat);
+    assert_eq!(code, 0);
+    let nested_value: serde_json::Value =
+        serde_json::from_str(&nested).unwrap();
+    assert_eq!(
+        nested_value,
+        serde_json::from_str::<serde_json::Value>(input).unwrap()
+    );
+}
+
+/// 测试 --plain 将状态符号替换为 ASCII 等价物
+#[test]
+fn test_cli_plain_output() {
+    let input = r#"{"a": 1}"#;
+
+    let (_, stdout) = run_cli(&["exists", ".a", "-v"], input);
+    assert!(stdout.contains('✓'));
+
+    for flag in ["--plain", "--ascii", "--monochrome-output"] {
+        let (code, stdout) = run_cli(&[flag, "exists", ".a", "-v"], input);
+        assert_eq!(code, 0);
+        assert_eq!(stdout.trim(), "[OK] Path exists");
+    }
+
+    let (_, stdout) = run_cli(&["exists", ".b", "-v", "--plain"], input);
+    assert!(stdout.is_ascii());
+    assert!(stdout.contains("[FAIL]"));
+}
+
+/// 测试 validate 的退出码：有效为 0，无效和无法识别均非 0；--quiet 时不输出任何内容
+#[test]
+fn test_cli_validate_exit_codes() {
+    let cases = [
+        (r#"{"a": 1}"#, 0, "valid"),
+        ("key: value\n", 0, "valid"),
+        (r#"{"a": "#, 1, "invalid"),
+        ("", 1, "unknown"),
+    ];
+
+    for (input, expected_code, expected_status) in cases {
+        let (code, stdout) = run_cli(&["validate"], input);
+        assert_eq!(code, expected_code, "validate {input:?}");
+        assert_eq!(stdout.trim(), expected_status, "validate {input:?}");
+
+        let output = run_cli_output(&["validate", "--quiet"], input);
+        assert_eq!(output.status.code(), Some(expected_code));
+        assert!(output.stdout.is_empty(), "quiet stdout for {input:?}");
+        assert!(output.stderr.is_empty(), "quiet stderr for {input:?}");
+    }
+}
+
+/// 测试 exists --count 输出匹配数量，默认行为不变
+#[test]
+fn test_cli_exists_count() {
+    let input =
+        r#"{"users": [{"email": "a@x"}, {"name": "b"}, {"email": "c@x"}]}"#;
+
+    assert_eq!(
+        run_cli(&["exists", ".users[*].email", "--count"], input),
+        (0, "2\n".to_string())
+    );
+    assert_eq!(
+        run_cli(&["exists", ".users[*].phone", "--count-only"], input),
+        (0, "0\n".to_string())
+    );
+    assert_eq!(
+        run_cli(&["exists", ".users[*].email"], input),
+        (0, "true\n".to_string())
+    );
+}
+
+/// 测试 functions 子命令分组列出函数并支持名称过滤
+#[test]
+fn test_cli_functions() {
+    let (code, stdout) = run_cli(&["functions"], "");
+    assert_eq!(code, 0);
+    let basic = stdout.find("Basic functions:").unwrap();
+    let advanced = stdout.find("Advanced functions:").unwrap();
+    let length = stdout.find("  length").unwrap();
+    let map = stdout.find("  map ").unwrap();
+    assert!(basic < length && length < advanced && advanced < map);
+    assert!(!stdout.contains("\x1b["));
+
+    let (code, stdout) = run_cli(&["functions", "SORT"], "");
+    assert_eq!(code, 0);
+    assert!(!stdout.contains("Basic functions:"));
+    let names: Vec<&str> = stdout
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+    assert_eq!(names, vec!["sort", "sort_by", "sort_by_desc", "sort_desc"]);
+
+    let output = run_cli_output(&["functions", "nonexistent"], "");
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No functions"));
+}
+
+/// 测试 input/inputs 在多文档输入中消费后续文档
+#[test]
+fn test_cli_inputs() {
+    let input = "{\"a\": 1}\n{\"a\": 2}\n{\"a\": 3}\n{\"a\": 4}\n";
+
+    assert_eq!(
+        run_cli(&["get", "-n", "inputs() | .a"], input),
+        (0, "1\n2\n3\n4\n".to_string())
+    );
+    assert_eq!(
+        run_cli(&["get", "(., input()) | .a", "-o", "compact"], input),
+        (0, "1\n2\n3\n4\n".to_string())
+    );
+
+    // 第一个文档作为 schema，其余文档为记录
+    let input = "---\nfields: [name]\n---\nname: a\nage: 1\n---\nname: b\n";
+    assert_eq!(
+        run_cli(&["get", "inputs() | .name", "-o", "json"], input),
+        (0, "\"a\"\n\"b\"\n".to_string())
+    );
+
+    assert_eq!(run_cli(&["get", "-n", "inputs()"], ""), (0, String::new()));
+}
+
+/// 测试 validate --schema 对符合与违反 schema 的文档分别给出结果
+#[cfg(feature = "schema")]
+#[test]
+fn test_cli_validate_schema() {
+    let dir = tempfile::tempdir().unwrap();
+    let schema = dir.path().join("schema.json");
+    std::fs::write(
+        &schema,
+        r#"{"type": "object", "required": ["name"],
+            "properties": {"age": {"type": "integer"}}}"#,
+    )
+    .unwrap();
+    let schema = schema.to_str().unwrap();
+
+    assert_eq!(
+        run_cli(&["validate", "--schema", schema], "name: a\nage: 1\n"),
+        (0, "valid\n".to_string())
+    );
+
+    let (code, stdout) =
+        run_cli(&["validate", "--schema", schema], r#"{"age": "x"}"#);
+    assert_eq!(code, 1);
+    assert!(stdout.starts_with("invalid\n"), "{stdout}");
+    assert!(stdout.contains("/age"), "{stdout}");
+
+    let (code, stdout) = run_cli(
+        &["validate", "--schema", schema, "--quiet"],
+        r#"{"age": 1}"#,
+    );
+    assert_eq!(code, 1);
+    assert!(stdout.is_empty());
+}
+
+/// 测试 --seq 输出 RFC 7464 JSON 文本序列，且与 --pretty 互斥
+#[test]
+fn test_cli_json_seq() {
+    let input = "a: 1\nb: [1, 2]\n";
+
+    assert_eq!(
+        run_cli(&["get", ".a, .b", "--seq"], input),
+        (0, "\u{1e}1\n\u{1e}[1,2]\n".to_string())
+    );
+    assert_eq!(run_cli(&["get", ".missing", "--seq"], input).1, "");
+
+    let output = run_cli_output(&["get", ".a", "--seq", "--pretty"], input);
+    assert!(!output.status.success());
+}
+
+/// 测试 gzip 压缩的输入按扩展名或魔数透明解压
+#[cfg(feature = "gzip")]
+#[test]
+fn test_cli_gzip_input() {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(br#"{"x": {"y": 42}}"#).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    for name in ["data.json.gz", "data.bin"] {
+        let path = dir.path().join(name);
+        std::fs::write(&path, &compressed).unwrap();
+        assert_eq!(
+            run_cli(&["get", ".x.y", "-f", path.to_str().unwrap()], ""),
+            (0, "42\n".to_string())
+        );
+    }
+
+    // 扩展名为 .gz 但内容不是 gzip 时报错
+    let path = dir.path().join("plain.gz");
+    std::fs::write(&path, r#"{"x": 1}"#).unwrap();
+    let output =
+        run_cli_output(&["get", ".x", "-f", path.to_str().unwrap()], "");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("gzip"));
+}
+
+/// 测试非 UTF-8 输入报告编码错误而不做有损转换
+#[test]
+fn test_cli_invalid_utf8_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("data.json");
+    std::fs::write(&path, b"{\"name\": \"\xFF\"}").unwrap();
+
+    let output =
+        run_cli_output(&["get", ".name", "-f", path.to_str().unwrap()], "");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid UTF-8 input"), "{stderr}");
+}
+
+/// 测试 trace 预览长字符串时按字符边界截断，不会因多字节字符崩溃
+#[test]
+fn test_cli_trace_preview_multibyte() {
+    // trace 没有 --no-color 选项，输出到管道时本身不带颜色
+    let run_trace = |args: &[&str], input: &str| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_xqpath"))
+            .arg("trace")
+            .args(args)
+            .env("NO_COLOR", "1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to start xqpath");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        (
+            output.status.code().unwrap_or(-1),
+            String::from_utf8(output.stdout).unwrap(),
+        )
+    };
+
+    let text = format!("{}{}", "a".repeat(46), "中".repeat(10));
+    let input = serde_json::json!({ "text": text }).to_string();
+
+    let (code, stdout) = run_trace(&[".text"], &input);
+    assert_eq!(code, 0);
+    let expected = format!("\"{}中...\"", "a".repeat(46));
+    assert!(stdout.contains(&expected), "{stdout}");
+
+    let text = format!("{}{}", "b".repeat(45), "😀👍".repeat(5));
+    let input = serde_json::json!({ "text": text }).to_string();
+    let (code, stdout) = run_trace(&[".text", "--detailed"], &input);
+    assert_eq!(code, 0);
+    let expected = format!("\"{}😀👍...\"", "b".repeat(45));
+    assert!(stdout.contains(&expected), "{stdout}");
+}
+
+/// 测试 --separator 控制多个结果值之间的分隔符
+#[test]
+fn test_cli_separator() {
+    let input = r#"{"items": ["a", "b", 3]}"#;
+
+    let (code, stdout) = run_cli(&["get", ".items[*]"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "\"a\"\n\"b\"\n3\n");
+
+    let (code, stdout) =
+        run_cli(&["get", ".items[*]", "--separator", "nul"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "\"a\"\0\"b\"\x003\0");
+
+    let (code, stdout) =
+        run_cli(&["get", ".items[*]", "--separator", "none"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "\"a\"\"b\"3");
+
+    let (code, _) = run_cli(&["get", ".items", "--separator", "tab"], input);
+    assert_ne!(code, 0);
+}
+
+/// 测试 get -e 根据最后一个结果的真值设置退出码
+#[test]
+fn test_cli_exit_status() {
+    let input = r#"{"enabled": true, "off": false, "none": null, "n": 0}"#;
+
+    let (code, stdout) = run_cli(&["get", ".enabled", "-e"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim(), "true");
+
+    let (code, stdout) = run_cli(&["get", ".off", "--exit-status"], input);
+    assert_eq!(code, 1);
+    assert_eq!(stdout.trim(), "false");
+
+    let (code, _) = run_cli(&["get", ".none", "-e"], input);
+    assert_eq!(code, 1);
+
+    // 真值判断与表达式求值器一致，0 视为假
+    let (code, _) = run_cli(&["get", ".n", "-e"], input);
+    assert_eq!(code, 1);
+
+    let (code, stdout) = run_cli(&["get", ".missing", "-e"], input);
+    assert_eq!(code, 4);
+    assert!(stdout.is_empty());
+
+    // 只看最后一个结果
+    let (code, _) = run_cli(&["get", ".off, .enabled", "-e"], input);
+    assert_eq!(code, 0);
+
+    // 不加 -e 时退出码不受结果影响
+    let (code, _) = run_cli(&["get", ".off"], input);
+    assert_eq!(code, 0);
+
+    // 非零退出码不会跳过 --timing 的输出，也不会输出错误信息
+    let output = run_cli_output(&["get", ".off", "-e", "--timing"], input);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "false\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Execution time:"), "{stderr}");
+    assert!(!stderr.contains("Error:"), "{stderr}");
+}
+
+/// 测试 convert 通过格式注册表查找目标格式
+#[test]
+fn test_cli_convert_registry_formats() {
+    let input = r#"{"a": 1}"#;
+
+    let (code, stdout) = run_cli(&["convert", "yml"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim(), "a: 1");
+
+    let (code, stdout) = run_cli(&["convert", "JSON-pretty"], "a: 1\n");
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim(), "{\n  \"a\": 1\n}");
+
+    let output = run_cli_output(&["convert", "toml"], input);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Unsupported output format: toml")
+            && stderr.contains("json, yaml, yml"),
+        "{stderr}"
+    );
+}
+
+/// 测试 --yaml-indent 与 --indent-sequences 控制 YAML 输出缩进
+#[test]
+fn test_cli_yaml_indent() {
+    let input = r#"{"a": {"b": [1, 2]}}"#;
+
+    let (code, stdout) = run_cli(&["get", ".", "-o", "yaml"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim_end(), "a:\n  b:\n  - 1\n  - 2");
+
+    let (code, stdout) =
+        run_cli(&["get", ".", "-o", "yaml", "--yaml-indent", "4"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim_end(), "a:\n    b:\n    - 1\n    - 2");
+
+    let (code, stdout) = run_cli(
+        &[
+            "convert",
+            "yaml",
+            "--yaml-indent",
+            "4",
+            "--indent-sequences",
+        ],
+        input,
+    );
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "a:\n    b:\n        - 1\n        - 2\n");
+
+    let (code, _) = run_cli(&["convert", "yaml", "--yaml-indent", "0"], input);
+    assert_ne!(code, 0);
+
+    // 前导空格的多行字符串在自定义缩进下保持不变
+    let input = r#"{"a": " x\ny", "b": ["  p\nq"]}"#;
+    for extra in [&[][..], &["--indent-sequences"][..]] {
+        let mut args = vec!["convert", "yaml", "--yaml-indent", "4"];
+        args.extend_from_slice(extra);
+        let (code, yaml) = run_cli(&args, input);
+        assert_eq!(code, 0);
+        let (code, json) = run_cli(&["convert", "json"], &yaml);
+        assert_eq!(code, 0);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            serde_json::from_str::<serde_json::Value>(input).unwrap(),
+            "{yaml}"
+        );
+    }
+}
+
+/// 测试 --quote-style 强制 YAML 字符串的引号风格
+#[test]
+fn test_cli_yaml_quote_style() {
+    let input = r#"{"name": "Alice", "n": 1}"#;
+
+    let (code, stdout) = run_cli(&["convert", "yaml"], input);
+    assert_eq!(code, 0);
+    assert!(stdout.contains("name: Alice\n"), "{stdout}");
+
+    let (code, stdout) =
+        run_cli(&["convert", "yaml", "--quote-style", "double"], input);
+    assert_eq!(code, 0);
+    assert!(stdout.contains("name: \"Alice\"\n"), "{stdout}");
+    assert!(stdout.contains("n: 1\n"), "{stdout}");
+
+    let (code, stdout) = run_cli(
+        &["get", ".name", "-o", "yaml", "--quote-style", "single"],
+        input,
+    );
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim_end(), "'Alice'");
+}
+
+/// 测试 --ascii-output 将非 ASCII 字符转义为 \uXXXX
+#[test]
+fn test_cli_ascii_output() {
+    let input = r#"{"name": "café", "emoji": "😀", "键": 1}"#;
+
+    let (code, stdout) = run_cli(&["get", ".name", "-a"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim(), r#""caf\u00e9""#);
+
+    let (code, stdout) = run_cli(&["get", ".emoji", "--ascii-output"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim(), r#""\ud83d\ude00""#);
+
+    let (code, stdout) = run_cli(&["convert", "compact", "-a"], input);
+    assert_eq!(code, 0);
+    assert!(stdout.is_ascii(), "{stdout}");
+    let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        parsed,
+        serde_json::from_str::<serde_json::Value>(input).unwrap()
+    );
+
+    // YAML 输出不做转义
+    let (code, stdout) = run_cli(&["get", ".name", "-a", "-o", "yaml"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim(), "café");
+}
+
+/// rename-key 递归重命名对象键，冲突时报错且不输出文档
+#[cfg(feature = "update")]
+#[test]
+fn test_cli_rename_key() {
+    let input = r#"{"id":1,"items":[{"id":2,"name":"a"}],"meta":{"id":3}}"#;
+    let (code, stdout) = run_cli(&["rename-key", "id", "key"], input);
+    assert_eq!(code, 0);
+    let output: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        output,
+        serde_json::json!({
+            "key": 1,
+            "items": [{"key": 2, "name": "a"}],
+            "meta": {"key": 3}
+        })
+    );
+
+    let output = run_cli_output(
+        &["rename-key", "id", "name"],
+        r#"{"items":[{"id":2,"name":"a"}]}"#,
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Key 'name' already exists at: .items[0]"));
+}
+
+/// paths -x 输出 xqpath 路径表达式，每条路径都能被 get 重新解析并取回原值
+#[test]
+fn test_cli_paths_expression() {
+    let input = r#"{"a": {"b": [1, {"c d": "x"}]}, "1st": true}"#;
+
+    let (code, stdout) = run_cli(&["paths", "--expression"], input);
+    assert_eq!(code, 0);
+    let paths: Vec<&str> = stdout.lines().collect();
+    assert!(paths.contains(&".a.b[0]"), "{stdout}");
+    assert!(paths.contains(&r#".a.b[1]."c d""#), "{stdout}");
+    assert!(paths.contains(&r#"."1st""#), "{stdout}");
+
+    let document: serde_json::Value = serde_json::from_str(input).unwrap();
+    for path in paths {
+        let (code, stdout) = run_cli(&["get", path, "-o", "compact"], input);
+        assert_eq!(code, 0, "{path}");
+        let segments = xqpath::parse_path(path).unwrap();
+        let expected = xqpath::extract(&document, &segments).unwrap();
+        assert_eq!(stdout.trim(), serde_json::to_string(expected[0]).unwrap());
+    }
+}
+
+/// 测试 --input-stream blank 按空行切分多个美化输出的 JSON 文档
+#[test]
+fn test_cli_input_stream_blank() {
+    let input =
+        "{\n  \"a\": 1\n}\n\n\n{\n  \"a\": 2\n}\n  \n{\n  \"a\": 3\n}\n";
+
+    let (code, stdout) =
+        run_cli(&["get", ".a", "--input-stream", "blank"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "1\n2\n3\n");
+
+    let (code, stdout) = run_cli(
+        &["get", "length()", "--input-stream", "blank", "--slurp"],
+        input,
+    );
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim(), "3");
+
+    // 格式错误的块报告其起始行号
+    let output = run_cli_output(
+        &["get", ".a", "--input-stream", "blank"],
+        "{\"a\": 1}\n\n{\n  \"a\": [1,\n}\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("starting at line 3"), "{stderr}");
+}
+
+/// 测试 NDJSON 逐行输入在出错时报告行号，并可为结果加上行号前缀
+#[test]
+fn test_cli_input_stream_lines() {
+    let input = "{\"id\": 1, \"tags\": [\"a\"]}\n\n{\"id\": 2, \"tags\": [\"b\", \"c\"]}\n";
+
+    let (code, stdout) = run_cli(
+        &[
+            "get",
+            ".tags[*]",
+            "--input-stream",
+            "lines",
+            "--with-line-numbers",
+        ],
+        input,
+    );
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "1:\"a\"\n3:\"b\"\n3:\"c\"\n");
+
+    let (code, stdout) =
+        run_cli(&["get", ".id", "--input-stream", "lines"], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "1\n2\n");
+
+    // 无法解析的行报告其行号
+    let output = run_cli_output(
+        &["get", ".id", "--input-stream", "lines"],
+        "{\"id\": 1}\n{\"id\": \n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Failed to parse line 2"), "{stderr}");
+
+    // 求值失败的行同样报告行号
+    let output = run_cli_output(
+        &["get", ".id + 1", "--input-stream", "lines"],
+        "{\"id\": 1}\n{\"id\": \"x\"}\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Failed to evaluate line 2"), "{stderr}");
+
+    let output = run_cli_output(&["get", ".id", "--with-line-numbers"], input);
+    assert!(!output.status.success());
+
+    // 使用 input()/inputs() 时结果无法对应到来源行，直接报错
+    let args = ["--input-stream", "lines", "--with-line-numbers"];
+    for extra in [
+        &["get", "., input() | .id"][..],
+        &["get", "-n", "inputs() | .id"],
+    ] {
+        let full: Vec<&str> = extra.iter().chain(&args).copied().collect();
+        let output = run_cli_output(&full, input);
+        assert!(!output.status.success(), "{full:?}");
+        assert!(output.stdout.is_empty(), "{full:?}");
+    }
+}
+
+/// 测试 profile --format json 输出可解析的性能报告
+#[cfg(feature = "profiling")]
+#[test]
+fn test_cli_profile_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("data.json");
+    std::fs::write(&data, r#"{"items": [{"name": "a"}, {"name": "b"}]}"#)
+        .unwrap();
+    let data = data.to_str().unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xqpath"))
+        .args(["profile", ".items[*].name", "-f", data, "--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let report: serde_json::Value =
+        serde_json::from_slice(&output.stdout).unwrap();
+    assert!(report["execution_time_ns"].is_u64(), "{report}");
+    assert!(report["peak_memory_bytes"].is_u64(), "{report}");
+    assert!(report["metrics"].is_object(), "{report}");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xqpath"))
+        .args([
+            "profile", ".items", "-f", data, "--format", "json", "--html",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
+
+/// 测试 benchmark --fail-threshold 在比基线慢超过阈值时以非零退出
+#[cfg(feature = "benchmark")]
+#[test]
+fn test_cli_benchmark_fail_threshold() {
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("data.json");
+    std::fs::write(&data, r#"{"a": 1}"#).unwrap();
+    let baseline = |name: &str, mean_ns: u64| {
+        let path = dir.path().join(name);
+        let result = serde_json::json!([{
+            "name": "query_benchmark",
+            "mean_time_ns": mean_ns,
+            "min_time_ns": mean_ns,
+            "max_time_ns": mean_ns,
+            "std_dev_ns": 0,
+            "iterations": 10,
+            "ops_per_sec": 1.0
+        }]);
+        std::fs::write(&path, result.to_string()).unwrap();
+        path
+    };
+    let run = |baseline: &std::path::Path| {
+        Command::new(env!("CARGO_BIN_EXE_xqpath"))
+            .args(["benchmark", ".a", "-i", "5", "--warmup", "1", "-f"])
+            .arg(&data)
+            .arg("--baseline")
+            .arg(baseline)
+            .args(["--fail-threshold", "10"])
+            .output()
+            .unwrap()
+    };
+
+    // 基线慢得多：当前结果更快，通过
+    let output = run(&baseline("slow.json", 60_000_000_000));
+    assert_eq!(output.status.code(), Some(0));
+
+    // 基线只需 1ns：必然超过阈值
+    let output = run(&baseline("fast.json", 1));
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("regressed more than 10%"), "{stderr}");
+    assert!(stderr.contains("query_benchmark"), "{stderr}");
+}
@@ -55,6 +55,124 @@ fn test_logical_operations() {
     assert_eq!(result, vec![json!(false)]);
 }
 
+#[test]
+fn test_arithmetic_operations() {
+    let data = json!({
+        "price": 10,
+        "count": 3,
+        "name": "widget",
+        "tags": ["a", "b", "c"],
+        "items": [{"price": 2.5}, {"price": 4}]
+    });
+
+    let eval = |source: &str| {
+        let expr = parse_path_expression(source).unwrap();
+        evaluate_path_expression(&expr, &data).unwrap()
+    };
+
+    // 优先级：乘除高于加减，左结合
+    assert_eq!(eval(".count + 1"), vec![json!(4)]);
+    assert_eq!(eval(".price * 1.5"), vec![json!(15)]);
+    assert_eq!(eval(".price - .count * 2"), vec![json!(4)]);
+    assert_eq!(eval("(.price - .count) * 2"), vec![json!(14)]);
+    assert_eq!(eval(".price / 4 - 1"), vec![json!(1.5)]);
+    assert_eq!(eval(".price % .count"), vec![json!(1)]);
+    assert_eq!(eval("10 - 2 - 3"), vec![json!(5)]);
+    assert_eq!(eval(".price + -5"), vec![json!(5)]);
+
+    // 不带空格的操作符：路径之后的 `*` 是乘法而不是通配符
+    assert_eq!(eval(".count*2"), vec![json!(6)]);
+    assert_eq!(eval(".count* .count"), vec![json!(9)]);
+    assert_eq!(eval(".count*.count"), vec![json!(9)]);
+    assert_eq!(eval(".items[1].price*2"), vec![json!(8)]);
+    assert_eq!(eval(".price/.count*3"), vec![json!(10)]);
+    assert_eq!(eval(".price%.count+1"), vec![json!(2)]);
+    assert_eq!(eval("2*.count"), vec![json!(6)]);
+
+    // 通配符仍可写作 `.*` 与 `[*]`
+    assert_eq!(eval(".items[*].price"), vec![json!(2.5), json!(4)]);
+    assert_eq!(eval(".items.*.price"), vec![json!(2.5), json!(4)]);
+
+    // 与比较、管道和函数组合
+    assert_eq!(eval(".price * 2 > 15"), vec![json!(true)]);
+    assert_eq!(eval(".items | map(.price * 2)"), vec![json!([5, 8])]);
+    assert_eq!(eval(".items[0] | .price + 1"), vec![json!(3.5)]);
+
+    // 字符串/数组拼接、数组移除
+    assert_eq!(eval(".name + \"s\""), vec![json!("widgets")]);
+    assert_eq!(eval(".tags + [\"d\"]"), vec![json!(["a", "b", "c", "d"])]);
+    assert_eq!(eval(".tags - [\"b\"]"), vec![json!(["a", "c"])]);
+
+    // 对象减去键时其余键保持原有顺序（启用 update 时对象按插入顺序输出）
+    let ordered = |source: &str| {
+        let values = eval(source);
+        serde_json::to_string(&values[0]).unwrap()
+    };
+    assert_eq!(
+        ordered(r#"{"a":1,"b":2,"c":3,"d":4} - "a""#),
+        r#"{"b":2,"c":3,"d":4}"#
+    );
+    assert_eq!(
+        ordered(r#"{"a":1,"b":2,"c":3,"d":4,"e":5} - ["a", "b"]"#),
+        r#"{"c":3,"d":4,"e":5}"#
+    );
+
+    // 多个结果时取笛卡尔积
+    assert_eq!(
+        eval("(1, 2) + (10, 20)"),
+        vec![json!(11), json!(12), json!(21), json!(22)]
+    );
+
+    // 类型不兼容或除以零时报错
+    for source in [".name + 1", ".price / 0", ".tags * 2", ".name * .tags"] {
+        let expr = parse_path_expression(source).unwrap();
+        assert!(evaluate_path_expression(&expr, &data).is_err(), "{source}");
+    }
+}
+
+#[test]
+fn test_multiplication_semantics() {
+    let data = json!({"base": {"a": {"b": 1}, "x": 1}, "word": "ab"});
+
+    let eval = |source: &str| {
+        let expr = parse_path_expression(source).unwrap();
+        evaluate_path_expression(&expr, &data).unwrap()
+    };
+
+    // 对象乘法为深度合并，而加法只做浅合并
+    assert_eq!(
+        eval(r#"{"a":{"b":1}} * {"a":{"c":2}}"#),
+        vec![json!({"a": {"b": 1, "c": 2}})]
+    );
+    assert_eq!(
+        eval(r#"{"a":{"b":1}} + {"a":{"c":2}}"#),
+        vec![json!({"a": {"c": 2}})]
+    );
+    assert_eq!(
+        eval(r#".base * {"a": {"c": 2}, "x": 2}"#),
+        vec![json!({"a": {"b": 1, "c": 2}, "x": 2})]
+    );
+
+    // 字符串重复，次数不大于 0 时为 null
+    assert_eq!(eval(".word * 3"), vec![json!("ababab")]);
+    assert_eq!(eval(".word * 0"), vec![json!(null)]);
+    assert_eq!(eval(".word * -2"), vec![json!(null)]);
+    assert_eq!(eval(".word * 0.5"), vec![json!("ab")]);
+    assert_eq!(eval(".word * 2.7"), vec![json!("abab")]);
+    assert_eq!(eval("\"\" * 100000000000000000000"), vec![json!("")]);
+
+    // 结果过大时报错而不是耗尽内存
+    for expr in [
+        "\"ab\" * 100000000000000000000",
+        "\"ab\" * 1000000000000",
+        "100000000000000000000 * .word",
+    ] {
+        let parsed = parse_path_expression(expr).unwrap();
+        assert!(evaluate_path_expression(&parsed, &data).is_err(), "{expr}");
+    }
+    assert_eq!(eval("2 * 3"), vec![json!(6)]);
+}
+
 #[test]
 fn test_conditional_expressions() {
     let data = json!({ "age": 25, "name": "Alice" });
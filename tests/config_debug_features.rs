@@ -93,6 +93,7 @@ fn test_debugger_creation() {
 /// 测试调试命令解析
 #[cfg(feature = "interactive-debug")]
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn test_debug_command_parsing() {
     // 测试帮助命令
     let help_cmd = DebugCommand::Help;
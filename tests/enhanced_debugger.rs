@@ -5,6 +5,7 @@ mod enhanced_debugger_tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_enhanced_debugger_creation() {
         let _debugger = XQPathDebugger::new();
         // 调试器创建应该成功
@@ -1,5 +1,9 @@
 use serde_json::json;
-use xqpath::{count, exists, get_type, query};
+use xqpath::{
+    count, evaluate_path_expression, exists, get_type, parse_path,
+    parse_path_expression, query, ConfigurableExtractor, ExtractError,
+    ExtractorConfig, PathExpression,
+};
 
 #[cfg(feature = "update")]
 use xqpath::update;
@@ -321,3 +325,110 @@ fn test_performance_basic() {
         "Performance test took too long: {duration:?}"
     );
 }
+
+/// 测试表达式 AST 经 JSON 序列化往返后保持不变
+#[test]
+fn test_path_expression_serde_roundtrip() {
+    let sources = [
+        ".users[*].name",
+        ".users | map(.name) | sort",
+        "if .active then .name else \"anonymous\" end",
+        ".age >= 18 and .country == \"CN\"",
+        "try .a catch null",
+        ".a, .b?",
+        "{\"x\": 1}",
+        ".price * 1.1 + .tax",
+    ];
+
+    for source in sources {
+        let expr = parse_path_expression(source).unwrap();
+        let json = serde_json::to_string(&expr).unwrap();
+        let restored: PathExpression = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, expr, "round-trip failed for {source}");
+    }
+}
+
+/// 测试 `.**.field` 返回任意深度对象上的字段，与 jq 的 `.. | .field?` 一致
+#[test]
+fn test_recursive_wildcard_field() {
+    let json_data = r#"
+    {
+        "name": "root",
+        "team": {
+            "name": "core",
+            "members": [{"name": "Alice"}, 42, "text", {"age": 3}]
+        },
+        "tags": [],
+        "extra": null
+    }
+    "#;
+
+    // 先序遍历：节点自身先于其后代；非对象节点与缺少字段的对象被跳过
+    let names = query!(json_data, ".**.name").unwrap();
+    assert_eq!(names, vec![json!("root"), json!("core"), json!("Alice")]);
+
+    // 不带点的写法等价
+    assert_eq!(query!(json_data, "**.name").unwrap(), names);
+
+    // 从子树开始递归只包含子树内的匹配
+    assert_eq!(
+        query!(json_data, ".team.**.name").unwrap(),
+        vec![json!("core"), json!("Alice")]
+    );
+
+    // 索引同样跳过非数组和越界的节点
+    assert_eq!(
+        query!(json_data, ".**[0]").unwrap(),
+        vec![json!({"name": "Alice"})]
+    );
+
+    // 表达式求值器给出相同结果
+    let data: serde_json::Value = serde_json::from_str(json_data).unwrap();
+    let expr = parse_path_expression(".**.name").unwrap();
+    assert_eq!(evaluate_path_expression(&expr, &data).unwrap(), names);
+}
+
+/// 测试可选路径段 `.field?` 在类型不匹配时返回空结果
+#[test]
+fn test_optional_path_segment() {
+    let json_data = r#"{"a": "text", "items": [{"id": 1}, 2, {"id": 3}]}"#;
+
+    assert!(query!(json_data, ".items[5]").is_err());
+    assert!(query!(json_data, ".items[5]?").unwrap().is_empty());
+    assert!(query!(json_data, ".a.b?").unwrap().is_empty());
+    assert!(query!(json_data, ".a[0]?").unwrap().is_empty());
+
+    // 可选段只跳过不匹配的元素，其余结果保留
+    assert_eq!(
+        query!(json_data, ".items[*].id?").unwrap(),
+        vec![json!(1), json!(3)]
+    );
+
+    assert_eq!(count!(json_data, ".a.b?").unwrap(), 0);
+}
+
+/// 测试类型不匹配时默认提取器返回空结果，与表达式求值器一致
+#[test]
+fn test_type_mismatch_returns_empty() {
+    let json_data = r#"{"string": "hello", "number": 42, "list": [1]}"#;
+    let data: serde_json::Value = serde_json::from_str(json_data).unwrap();
+
+    for path in [".string.field", ".number[0]", ".list.field", ".string.a.b"] {
+        assert!(query!(json_data, path).unwrap().is_empty(), "{path}");
+
+        let expr = parse_path_expression(path).unwrap();
+        assert!(evaluate_path_expression(&expr, &data).unwrap().is_empty());
+    }
+    assert!(!exists!(json_data, ".string.field").unwrap());
+
+    // 严格模式仍然报告类型不匹配
+    let strict = ConfigurableExtractor::new(ExtractorConfig {
+        ignore_type_mismatches: false,
+        ..Default::default()
+    });
+    let path = parse_path(".string.field").unwrap();
+    assert!(matches!(
+        strict.extract(&data, &path),
+        Err(ExtractError::TypeMismatch(_, _))
+    ));
+}
@@ -34,6 +34,7 @@ mod readme_verification_tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_readme_error_handling() {
         let data = r#"{"user": {"name": "Alice"}}"#;
 